@@ -5,12 +5,27 @@ use ratatui::{
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{collections::HashMap, default, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 use strum::{Display, EnumIter, FromRepr};
 use tui_input::Input;
 
 use crate::{
-    sql::schema::{ChampionHistory, ChampionStats, OverallStats, PlayerDeepStats, PlayerStats},
+    import::{FileNaming, ImportPlan, ImportReport},
+    riot::{KeyType, RiotClient},
+    sql::{
+        filter::GameFilter,
+        roster::Sides,
+        schema::{
+            ChampionHistory, ChampionStats, DraftEntry, GameMetadata, GameTimeline, HeadToHead,
+            Matchup, OverallStats, PlayerDeepStats, PlayerStats, PlayerVersus, Rating, RosterEntry,
+            Series, StandingRow, TeamDraftTendency, TeamStats,
+        },
+        synergy::Synergy,
+    },
     ui::view::GameList,
 };
 
@@ -27,10 +42,100 @@ pub fn db_path(app_name: &str) -> PathBuf {
     path
 }
 
+pub fn aliases_path(app_name: &str) -> PathBuf {
+    let mut path = config_dir(app_name);
+    path.push("aliases.toml");
+    path
+}
+
 #[derive(Debug, Deserialize, Serialize, Default)]
 pub struct Config {
     #[serde(default)]
     api_key: String,
+    #[serde(default)]
+    key_type: KeyType,
+    #[serde(default)]
+    export_dir: Option<PathBuf>,
+    /// How manual files are named, see `import::FileNaming`.
+    #[serde(default = "default_file_patterns")]
+    file_patterns: Vec<String>,
+    /// Team abbreviations used in manual file names to full team names.
+    #[serde(default)]
+    teams: HashMap<String, String>,
+}
+
+fn default_file_patterns() -> Vec<String> {
+    vec!["{team1}_{team2}_{game}".to_string()]
+}
+
+/// Manual games don't record puuids, so `aliases.toml` maps each player's
+/// puuid to the game names they've played under:
+///
+/// ```toml
+/// "dbbd7de3-936e-5146-aded-74badd155826" = ["BeetleBerries", "Beetle"]
+/// ```
+///
+/// Any unique string works as the key for players who've never been fetched
+/// from the API. A name can only belong to one player.
+#[derive(Debug, Deserialize, Default)]
+#[serde(transparent)]
+pub struct Aliases(HashMap<String, Vec<String>>);
+
+impl Aliases {
+    /// Read the alias file, treating a missing file as empty.
+    pub fn load(path: &Path) -> color_eyre::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text)
+    }
+
+    fn parse(text: &str) -> color_eyre::Result<Self> {
+        let aliases: Self = toml::from_str(text)?;
+        let mut owners: HashMap<&str, &str> = HashMap::new();
+        let mut puuids: Vec<&String> = aliases.0.keys().collect();
+        puuids.sort();
+        for puuid in puuids {
+            for name in &aliases.0[puuid] {
+                if let Some(owner) = owners.insert(name, puuid)
+                    && owner != puuid
+                {
+                    return Err(color_eyre::eyre::eyre!(
+                        "the alias {name} is listed under both {owner} and {puuid}"
+                    ));
+                }
+            }
+        }
+        Ok(aliases)
+    }
+
+    /// Game name to puuid.
+    pub fn by_name(&self) -> HashMap<String, String> {
+        self.0
+            .iter()
+            .flat_map(|(puuid, names)| names.iter().map(|n| (n.clone(), puuid.clone())))
+            .collect()
+    }
+}
+
+impl Config {
+    pub fn riot_client(&self) -> RiotClient {
+        RiotClient::new(&self.api_key, self.key_type)
+    }
+
+    /// Where stats exports go, `exports/` in the config directory unless set.
+    pub fn export_dir(&self, app_name: &str) -> PathBuf {
+        self.export_dir.clone().unwrap_or_else(|| {
+            let mut dir = config_dir(app_name);
+            dir.push("exports");
+            dir
+        })
+    }
+
+    pub fn file_naming(&self) -> FileNaming {
+        FileNaming::new(self.file_patterns.clone(), &self.teams)
+    }
 }
 
 #[derive(Copy, Clone, PartialEq)]
@@ -40,6 +145,12 @@ pub enum CurrentScreen {
     ImportManual,
     Search,
     Stats,
+    Roster,
+    EditGame,
+    GameDetail,
+    HeadToHead,
+    PlayerVersus,
+    Synergy,
     Quit,
 }
 
@@ -52,8 +163,12 @@ pub enum StatsTab {
     Player,
     #[strum(to_string = "Team stats")]
     Team,
+    #[strum(to_string = "Standings")]
+    Standings,
     #[strum(to_string = "Champion stats")]
     Champion,
+    #[strum(to_string = "Draft tendencies")]
+    Draft,
 }
 
 impl StatsTab {
@@ -76,6 +191,127 @@ impl StatsTab {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Default, Display, FromRepr, EnumIter)]
+pub enum DetailTab {
+    #[default]
+    Scoreboard,
+    Objectives,
+    #[strum(to_string = "Damage & gold")]
+    Shares,
+    #[strum(to_string = "Gold & XP leads")]
+    Leads,
+    Draft,
+}
+
+impl DetailTab {
+    fn previous(self) -> Self {
+        Self::from_repr((self as usize).saturating_sub(1)).unwrap_or(self)
+    }
+
+    fn next(self) -> Self {
+        Self::from_repr((self as usize).saturating_add(1)).unwrap_or(self)
+    }
+
+    pub fn title(self) -> String {
+        format!("  {self}  ")
+    }
+}
+
+/// A row on the game edit form, in display order.
+#[derive(Copy, Clone, PartialEq, Display, FromRepr, EnumIter)]
+pub enum GameField {
+    #[strum(to_string = "Blue side team")]
+    Team1,
+    #[strum(to_string = "Red side team")]
+    Team2,
+    #[strum(to_string = "Series ID")]
+    Series,
+    #[strum(to_string = "Game number")]
+    GameNumber,
+    Patch,
+    Manual,
+    Notes,
+}
+
+/// The game edit form, holding the changes until they are saved.
+pub struct GameForm {
+    pub game_id: u64,
+    pub metadata: GameMetadata,
+    pub state: TableState,
+}
+
+impl GameForm {
+    pub fn new(game_id: u64, metadata: GameMetadata) -> Self {
+        Self {
+            game_id,
+            metadata,
+            state: TableState::default().with_selected(0),
+        }
+    }
+
+    pub fn selected_field(&self) -> Option<GameField> {
+        self.state.selected().and_then(GameField::from_repr)
+    }
+
+    /// The field's current value as it is shown and edited.
+    pub fn value(&self, field: GameField) -> String {
+        let meta = &self.metadata;
+        let opt = |n: Option<String>| n.unwrap_or_default();
+        match field {
+            GameField::Team1 => meta.team_1.clone(),
+            GameField::Team2 => meta.team_2.clone(),
+            GameField::Series => opt(meta.series_id.map(|n| n.to_string())),
+            GameField::GameNumber => opt(meta.game_number.map(|n| n.to_string())),
+            GameField::Patch => opt(meta.patch.clone()),
+            GameField::Manual => if meta.manual { "yes" } else { "no" }.to_string(),
+            GameField::Notes => opt(meta.notes.clone()),
+        }
+    }
+
+    /// Set a field from what was typed, leaving it unchanged if the input
+    /// doesn't parse. A blank input clears optional fields.
+    pub fn set(&mut self, field: GameField, input: &str) -> Result<(), String> {
+        let input = input.trim();
+        let text = || Some(input.to_string()).filter(|s| !s.is_empty());
+        let meta = &mut self.metadata;
+        match field {
+            GameField::Team1 => meta.team_1 = input.to_string(),
+            GameField::Team2 => meta.team_2 = input.to_string(),
+            GameField::Series => {
+                meta.series_id = match input {
+                    "" => None,
+                    n => Some(n.parse().map_err(|_| format!("'{n}' is not a series ID"))?),
+                }
+            }
+            GameField::GameNumber => {
+                meta.game_number = match input {
+                    "" => None,
+                    n => Some(
+                        n.parse()
+                            .ok()
+                            .filter(|&n| n > 0)
+                            .ok_or(format!("'{n}' is not a game number"))?,
+                    ),
+                }
+            }
+            GameField::Patch => meta.patch = text(),
+            GameField::Manual => {
+                meta.manual = match input.to_lowercase().as_str() {
+                    "yes" | "y" | "true" => true,
+                    "no" | "n" | "false" => false,
+                    other => return Err(format!("'{other}' is not yes or no")),
+                }
+            }
+            GameField::Notes => meta.notes = text(),
+        }
+        Ok(())
+    }
+
+    pub fn swap_sides(&mut self) {
+        std::mem::swap(&mut self.metadata.team_1, &mut self.metadata.team_2);
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Default)]
 pub enum AlertType {
     #[default]
@@ -101,9 +337,17 @@ pub struct App {
     pub post_message: Option<Message>,
     // Config
     pub config: Config,
+    pub riot: RiotClient,
     // Import stuff
     pub import_message: String,
     pub imported_games: Vec<String>,
+    pub import_dir: PathBuf,
+    pub import_naming: FileNaming,
+    /// Abbreviations still to ask the user about before planning the import.
+    pub import_unresolved: Vec<String>,
+    pub import_plan: Option<ImportPlan>,
+    pub import_report: ImportReport,
+    pub import_table_state: TableState,
     // Data stuff
     pub startup: bool,
     pub game_count: i64,
@@ -111,8 +355,22 @@ pub struct App {
     pub test_game: Option<Value>,
     pub db_games: GameList,
     pub search_games: GameList,
+    /// Teams the rosters gave the game being added from search.
+    pub search_sides: Sides,
+    pub edit_game: Option<GameForm>,
+    // match browser filter
+    pub filtering: bool,
+    pub filter_input: Input,
+    pub game_filter: GameFilter,
+    pub filter_error: Option<String>,
     // Stats stuff
     pub stats_tab: StatsTab,
+    pub detail_tab: DetailTab,
+    pub detail_draft: Vec<DraftEntry>,
+    /// Stored timelines by game ID, for the gold lead charts.
+    pub timelines: HashMap<u64, GameTimeline>,
+    /// Cleared when a timeline is stored, so the next reload reads them again.
+    pub timelines_loaded: bool,
     pub overall_stats: OverallStats,
     pub players_stats: Vec<PlayerStats>,
     pub players_table_state: TableState,
@@ -122,11 +380,37 @@ pub struct App {
     // player depth stats
     pub player_deep_stats: PlayerDeepStats,
     // team stats
+    pub teams_stats: Vec<TeamStats>,
+    pub teams_table_state: TableState,
+    pub teams_sort_dir: i64,
+    // standings
+    pub series: Vec<Series>,
+    pub standings: Vec<StandingRow>,
+    pub standings_table_state: TableState,
+    // rosters
+    pub roster: Vec<RosterEntry>,
+    pub roster_table_state: TableState,
     // champ stats
     pub all_champs_stats: Vec<ChampionStats>,
     pub all_champs_state: TableState,
     pub champs_history: HashMap<String, Vec<ChampionHistory>>,
+    /// Lane matchups keyed by champion.
+    pub champion_matchups: HashMap<String, Vec<Matchup>>,
     pub champs_sort_dir: i64,
+    // draft tendencies
+    pub draft_tendencies: Vec<TeamDraftTendency>,
+    pub draft_table_state: TableState,
+    // head to head
+    pub head_to_head: Option<HeadToHead>,
+    /// The first player picked to compare, waiting on the second.
+    pub versus_pick: Option<PlayerStats>,
+    pub player_versus: Option<PlayerVersus>,
+    /// Lane matchups keyed by puuid.
+    pub player_matchups: HashMap<String, Vec<Matchup>>,
+    pub synergy: Option<Synergy>,
+    // ratings, each history oldest first
+    pub team_ratings: HashMap<String, Vec<Rating>>,
+    pub player_ratings: HashMap<String, Vec<Rating>>,
 }
 
 impl App {
@@ -141,14 +425,27 @@ impl App {
             input: Input::new("".to_string()),
             post_message: None,
             config: Config::default(),
+            riot: RiotClient::new("", KeyType::default()),
             import_message: String::new(),
             imported_games: Vec::new(),
+            import_dir: PathBuf::new(),
+            import_naming: FileNaming::default(),
+            import_unresolved: Vec::new(),
+            import_plan: None,
+            import_report: ImportReport::default(),
+            import_table_state: TableState::default(),
             db_connection: None,
             startup: true,
             game_count: 0,
             test_game: None,
             db_games: GameList::default(),
             search_games: GameList::default(),
+            search_sides: Sides::default(),
+            edit_game: None,
+            filtering: false,
+            filter_input: Input::default(),
+            game_filter: GameFilter::default(),
+            filter_error: None,
             show_alert: false,
             alert_type: AlertType::default(),
             alert_message: String::new(),
@@ -160,15 +457,39 @@ impl App {
             players_role_filter: 0,
             player_deep_stats: PlayerDeepStats::default(),
             stats_tab: StatsTab::default(),
+            detail_tab: DetailTab::default(),
+            detail_draft: Vec::new(),
+            timelines: HashMap::new(),
+            timelines_loaded: false,
+            teams_stats: Vec::new(),
+            teams_table_state: TableState::default(),
+            teams_sort_dir: -1,
+            series: Vec::new(),
+            standings: Vec::new(),
+            standings_table_state: TableState::default(),
+            roster: Vec::new(),
+            roster_table_state: TableState::default(),
             all_champs_stats: Vec::new(),
             all_champs_state: TableState::default(),
             champs_history: HashMap::default(),
+            champion_matchups: HashMap::default(),
+            draft_tendencies: Vec::new(),
+            draft_table_state: TableState::default(),
             champs_sort_dir: -1,
+            head_to_head: None,
+            versus_pick: None,
+            player_versus: None,
+            player_matchups: HashMap::default(),
+            synergy: None,
+            team_ratings: HashMap::default(),
+            player_ratings: HashMap::default(),
         }
     }
 
-    pub fn get_config_api_key(&self) -> &str {
-        return &self.config.api_key;
+    /// Replace the config and rebuild the Riot client to match it.
+    pub fn set_config(&mut self, config: Config) {
+        self.riot = config.riot_client();
+        self.config = config;
     }
 
     pub fn next_stats_tab(&mut self) {
@@ -177,6 +498,13 @@ impl App {
     pub fn previous_stats_tab(&mut self) {
         self.stats_tab = self.stats_tab.previous();
     }
+
+    pub fn next_detail_tab(&mut self) {
+        self.detail_tab = self.detail_tab.next();
+    }
+    pub fn previous_detail_tab(&mut self) {
+        self.detail_tab = self.detail_tab.previous();
+    }
 }
 
 #[derive(PartialEq, Clone)]
@@ -191,6 +519,12 @@ pub enum Message {
     InputFinished,
     InputCancelled,
     DoImportManual,
+    PromptImportTeam,
+    ResolveImportTeam,
+    PlanImportManual,
+    ToggleImportFile,
+    ToggleAllImportFiles,
+    ApplyImportManual,
     ListDown,
     ListUp,
     ListEnd,
@@ -206,7 +540,77 @@ pub enum Message {
     OpenStats,
     AddSearchTeam1,
     AddSearchTeam2,
+    PromptSearchTeam,
+    OpenEditGame,
+    EditGameField,
+    SetGameField,
+    SwapGameSides,
+    SaveGame,
+    OpenGameDetail,
+    PromptDraft,
+    SetDraft,
+    OpenFilter,
+    FilterKey(Event),
+    CloseFilter,
+    ClearFilter,
+    OpenRoster,
+    ReloadRoster,
+    /// Ask for the next field of a new roster entry, `0` being the team.
+    PromptRosterField(usize),
+    AddRosterEntry,
+    RemoveRosterEntry,
+    ApplyRoster,
     RemoveGame,
     NextTab,
     PrevTab,
+    ExportStats,
+    /// Ask for the teams to compare, `0` being the first.
+    PromptHeadToHead(usize),
+    OpenHeadToHead,
+    /// Pick the selected player to compare, opening the comparison once
+    /// there are two.
+    PickVersusPlayer,
+    /// Open the selected player's teammates, duos and champion pairs.
+    OpenSynergy,
+    ReturnToStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aliases_map_names_to_puuids() {
+        let aliases = Aliases::parse(
+            r#"
+            "puuid-1" = ["Beetle", "BeetleBerries"]
+            "puuid-2" = ["Faker"]
+            "#,
+        )
+        .unwrap();
+        let by_name = aliases.by_name();
+        assert_eq!(by_name.len(), 3);
+        assert_eq!(by_name["Beetle"], "puuid-1");
+        assert_eq!(by_name["Faker"], "puuid-2");
+    }
+
+    #[test]
+    fn aliases_reject_a_name_under_two_players() {
+        let error = Aliases::parse(
+            r#"
+            "puuid-2" = ["Faker", "Beetle"]
+            "puuid-1" = ["Beetle"]
+            "#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the alias Beetle is listed under both puuid-1 and puuid-2"
+        );
+    }
+
+    #[test]
+    fn aliases_allow_a_name_twice_for_one_player() {
+        assert!(Aliases::parse(r#""puuid-1" = ["Beetle", "Beetle"]"#).is_ok());
+    }
 }
@@ -1,158 +1,485 @@
-use std::fs;
-use std::io;
-use std::io::Write;
-use std::path::Path;
-use std::path::PathBuf;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
-use rusqlite::{Connection, Result};
-use serde_json::Value;
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
+use color_eyre::eyre::{Context, eyre};
+use rusqlite::Connection;
+use serde::Serialize;
 
-use crate::app;
-use crate::riot;
-use crate::sql::repo;
+use crate::app::{self, Aliases, Config};
+use crate::export::{ExportFormat, StatsExport};
+use crate::import::{self, FileStatus};
+use crate::riot::model::MatchDto;
+use crate::sql::filter::GameFilter;
+use crate::sql::schema::{Game, PlayerDeepStats, PlayerStats, RosterEntry};
+use crate::sql::{migrations, repo, roster};
 
 pub const APP_NAME: &str = "fprs";
 
-pub struct AppState {
-    pub api_key: Option<String>,
+/// Collate and view stats for League of Legends games.
+#[derive(Parser)]
+#[command(name = APP_NAME, version)]
+pub struct Cli {
+    /// Defaults to `tui`.
+    #[command(subcommand)]
+    pub command: Option<Command>,
 }
 
-impl AppState {
-    pub fn new() -> Self {
-        Self { api_key: None }
-    }
+#[derive(Subcommand)]
+pub enum Command {
+    /// Import every manually entered game in a directory of JSON files
+    Import {
+        dir: PathBuf,
+        /// List what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Fetch a tournament game from the Riot API and store it
+    Fetch {
+        /// The numeric match ID, with or without the platform prefix (e.g. OC1_)
+        match_id: String,
+        /// Blue side, defaults to the team the rosters give
+        #[arg(long)]
+        team1: Option<String>,
+        /// Red side, defaults to the team the rosters give
+        #[arg(long)]
+        team2: Option<String>,
+        #[arg(long, default_value = "OC1")]
+        platform: String,
+        #[arg(long, default_value = "sea")]
+        region: String,
+    },
+    /// Print stats
+    Stats {
+        #[command(subcommand)]
+        stats: StatsCommand,
+    },
+    /// Work with stored games
+    Games {
+        #[command(subcommand)]
+        games: GamesCommand,
+    },
+    /// Manage which players were on which team
+    Roster {
+        #[command(subcommand)]
+        roster: RosterCommand,
+    },
+    /// Write the stats tables to files
+    Export {
+        /// Defaults to every format
+        #[arg(long, value_enum)]
+        format: Option<ExportFormat>,
+        /// Defaults to `export_dir` from the config
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+    /// Open the terminal UI
+    Tui,
+}
+
+#[derive(Subcommand)]
+pub enum StatsCommand {
+    /// Per-player averages, one row per player and role
+    Players {
+        /// TOP, JUNGLE, MIDDLE, BOTTOM or UTILITY
+        #[arg(long)]
+        role: Option<String>,
+        #[arg(long)]
+        team: Option<String>,
+        #[arg(long)]
+        min_games: Option<u64>,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RosterCommand {
+    /// List every roster entry
+    List,
+    /// Put a player on a team
+    Add {
+        #[arg(long)]
+        team: String,
+        /// A puuid, or a Riot ID as `name` or `name#tag`
+        #[arg(long)]
+        player: String,
+        /// First day on the team, YYYY-MM-DD
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// Last day on the team, YYYY-MM-DD
+        #[arg(long)]
+        to: Option<NaiveDate>,
+    },
+    /// Remove a roster entry by its ID
+    Remove { id: i64 },
+    /// Fill in teams from the rosters on games that are missing them
+    Apply,
+}
+
+#[derive(Subcommand)]
+pub enum GamesCommand {
+    /// List stored games, newest first
+    List {
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+        /// Only list matching games, e.g. "team:tigers champ:ahri winner:blue"
+        #[arg(long, value_parser = GameFilter::parse)]
+        filter: Option<GameFilter>,
+    },
+    /// Fetch timelines for stored API games that don't have one yet
+    Timelines {
+        #[arg(long, default_value = "sea")]
+        region: String,
+    },
 }
 
-pub fn handle_command(cmd: &str, state: &mut AppState) -> Result<()> {
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+/// Run a non-interactive command against the database.
+pub fn run(command: Command, config: &Config) -> color_eyre::Result<()> {
     let db_path = app::db_path(APP_NAME);
-    match cmd {
-        "import-manual" => {
-            let conn = Connection::open(db_path)?;
-            let path = prompt_path("Path to JSON directory");
+    let mut conn = repo::init_db(&db_path)?;
+    migrations::migrate(&mut conn)?;
 
-            if !path.is_dir() {
-                eprintln!("Not a directory: {}", path.display());
+    match command {
+        Command::Import { dir, dry_run } => {
+            if !dir.is_dir() {
+                return Err(eyre!("{} is not a directory.", dir.display()));
             }
-
-            match import_manual_matches(&conn, &path) {
-                Ok(count) => {
-                    println!("Imported {count} manual matches");
+            let mut naming = config.file_naming();
+            for abbr in naming.unresolved(&dir)? {
+                match prompt(&format!("Team name for '{abbr}' (blank to leave unset)"))? {
+                    Some(name) => naming.set_team(&abbr, &name),
+                    None if !io::stdin().is_terminal() => {
+                        eprintln!("No team name for '{abbr}', add it under [teams] in config.toml")
+                    }
+                    None => {}
                 }
-                Err(e) => {
-                    eprintln!("Import failed: {e}");
+            }
+            let plan = import::plan_manual_import(&conn, &dir, &naming)
+                .with_context(|| format!("failed to read {}", dir.display()))?;
+            if dry_run {
+                for file in &plan.files {
+                    println!("{:<24} {}", file.file, file.status);
+                    if let FileStatus::Changed(changes) = &file.status {
+                        for change in changes {
+                            println!("    {change}");
+                        }
+                    }
                 }
+                println!("{}", plan.summary());
+                return Ok(());
             }
+            let aliases = Aliases::load(&app::aliases_path(APP_NAME))?;
+            let report = import::apply(&conn, &plan, &aliases.by_name());
+            for entry in &report.entries {
+                println!("{:<24} {}", entry.file, entry.outcome);
+            }
+            println!("{}", report.summary());
         }
-        "add-game" => {
-            let conn = Connection::open(db_path)?;
-            fetch(state, &conn);
+        Command::Fetch {
+            match_id,
+            team1,
+            team2,
+            platform,
+            region,
+        } => {
+            let game_id = match_id.rsplit('_').next().unwrap_or(&match_id);
+            let match_id = format!("{platform}_{game_id}");
+            let client = config.riot_client();
+            let json = client.fetch_match(&region, &match_id)?;
+            let info = MatchDto::from_value(&json)?.info;
+            let aliases = Aliases::load(&app::aliases_path(APP_NAME))?;
+            let sides = roster::sides(&info, &repo::all_roster(&conn)?, &aliases.by_name());
+            let team = |given: Option<String>, from_roster: Option<String>, side: &str| match given
+                .or(from_roster)
+            {
+                Some(team) => Ok(team),
+                None => prompt(&format!("{side} side team"))?.ok_or_else(|| {
+                    eyre!("The rosters don't settle the {side} side team, pass it in")
+                }),
+            };
+            let team1 = team(team1, sides.blue, "Blue")?;
+            let team2 = team(team2, sides.red, "Red")?;
+            repo::insert_game_with_teams(&conn, game_id, &json, &team1, &team2)
+                .context("failed to store game")?;
+            println!("Stored game {game_id}: {team1} vs {team2}");
+            // The game is usable without it, `games timelines` can try again
+            match client.fetch_timeline(&region, &match_id) {
+                Ok(timeline) => repo::insert_timeline(&conn, info.game_id, &timeline)
+                    .context("failed to store timeline")?,
+                Err(e) => eprintln!("Couldn't fetch the timeline: {e}"),
+            }
         }
-        "init" => match repo::init_db(&db_path) {
-            Ok(_) => println!("Initialised database at {}", db_path.display()),
-            Err(e) => eprintln!("Init failed: {}", e),
-        },
-        "help" => {
-            println!("Available commands:");
-            println!("  init    Initialise app database");
-            println!("  add-game    Add a game to the tracker");
-            println!("  help");
-            println!("  quit | exit");
+        Command::Stats {
+            stats:
+                StatsCommand::Players {
+                    role,
+                    team,
+                    min_games,
+                    format,
+                },
+        } => {
+            let aliases = Aliases::load(&app::aliases_path(APP_NAME))?;
+            repo::refresh_players(&conn, &aliases.by_name())?;
+            let role = role.map(|r| r.to_uppercase());
+            let stats = repo::stats_players(&conn, [team, role, min_games.map(|n| n.to_string())])?;
+            write_output(|out| write_players(out, &stats, format))?;
         }
-        "" => {} // ignore empty input
-        _ => {
-            println!("Unknown command: {}", cmd);
+        Command::Games {
+            games: GamesCommand::List { format, filter },
+        } => {
+            let (games, unreadable) = repo::all_games(&conn, &filter.unwrap_or_default())?;
+            for game in &unreadable {
+                eprintln!("Skipped game {}: {}", game.id, game.error);
+            }
+            let games: Vec<GameSummary> = games.iter().map(GameSummary::from).collect();
+            write_output(|out| write_games(out, &games, format))?;
+        }
+        Command::Games {
+            games: GamesCommand::Timelines { region },
+        } => {
+            let missing = repo::games_missing_timeline(&conn)?;
+            let client = config.riot_client();
+            let mut stored = 0;
+            for (game_id, match_id) in &missing {
+                match client.fetch_timeline(&region, match_id) {
+                    Ok(timeline) => {
+                        repo::insert_timeline(&conn, *game_id, &timeline)
+                            .with_context(|| format!("failed to store timeline for {match_id}"))?;
+                        stored += 1;
+                    }
+                    Err(e) => eprintln!("{match_id}: {e}"),
+                }
+            }
+            println!("Stored {stored} of {} missing timelines", missing.len());
         }
+        Command::Export { format, dir } => {
+            let aliases = Aliases::load(&app::aliases_path(APP_NAME))?;
+            repo::refresh_players(&conn, &aliases.by_name())?;
+            let dir = dir.unwrap_or_else(|| config.export_dir(APP_NAME));
+            let formats = format.map_or(ExportFormat::ALL.to_vec(), |f| vec![f]);
+            let written = export_stats(&conn, &dir, &formats)?;
+            println!("Wrote {} files to {}", written.len(), dir.display());
+        }
+        Command::Roster { roster } => match roster {
+            RosterCommand::List => {
+                let entries = repo::all_roster(&conn)?;
+                write_output(|out| write_roster(out, &entries))?;
+            }
+            RosterCommand::Add {
+                team,
+                player,
+                from,
+                to,
+            } => {
+                let (from, to) = (from.map(|d| d.to_string()), to.map(|d| d.to_string()));
+                let id = repo::insert_roster_entry(
+                    &conn,
+                    &team,
+                    &player,
+                    from.as_deref(),
+                    to.as_deref(),
+                )?;
+                println!("Added {player} to {team} ({id})");
+            }
+            RosterCommand::Remove { id } => {
+                repo::delete_roster_entry(&conn, id)?;
+                println!("Removed roster entry {id}");
+            }
+            RosterCommand::Apply => {
+                let aliases = Aliases::load(&app::aliases_path(APP_NAME))?;
+                let count = repo::assign_all_teams_from_roster(&conn, &aliases.by_name())?;
+                println!("Assigned teams to {count} games");
+            }
+        },
+        Command::Tui => unreachable!("the TUI is started from main"),
     }
     Ok(())
 }
 
-pub fn prompt(label: &str) -> String {
-    use std::io::{self, Write};
-
-    print!("{label}: ");
-    io::stdout().flush().unwrap();
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
-    input.trim().to_string()
+/// Ask a question on the terminal. `None` for a blank answer, or when
+/// stdin isn't a terminal so there's no one to ask.
+fn prompt(question: &str) -> io::Result<Option<String>> {
+    if !io::stdin().is_terminal() {
+        return Ok(None);
+    }
+    print!("{question}: ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok((!answer.is_empty()).then(|| answer.to_string()))
 }
 
-fn prompt_path(label: &str) -> PathBuf {
-    print!("{label}: ");
-    io::stdout().flush().unwrap();
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
-    PathBuf::from(input.trim())
+/// Write to stdout, stopping quietly if the reader goes away (e.g. `| head`).
+fn write_output(f: impl FnOnce(&mut io::StdoutLock) -> io::Result<()>) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    match f(&mut out).and_then(|_| out.flush()) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
 }
 
-fn fetch(state: &mut AppState, conn: &Connection) {
-    let api_key = match &state.api_key {
-        Some(key) => key.clone(),
-        None => {
-            let key = prompt("Riot API key");
-            state.api_key = Some(key.clone());
-            key
-        }
-    };
-
-    let match_id = prompt("Match ID");
-    let game_id = "OC1_".to_owned() + &match_id;
-
-    let region = "sea";
-
-    match riot::fetch_match(&api_key, region, &game_id) {
-        Ok(json) => {
-            println!("Result: {}", json);
-            if let Err(e) = repo::insert_game(conn, &match_id, &json) {
-                eprintln!("Failed to store game: {e}");
-            } else {
-                println!("Game retrieved!");
+fn write_players(out: &mut impl Write, stats: &[PlayerStats], format: Format) -> io::Result<()> {
+    match format {
+        Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(stats)?)?,
+        Format::Table => {
+            writeln!(
+                out,
+                "{:<20} {:<16} {:<8} {:>5} {:>12} {:>6} {:>5} {:>5} {:>6} {:>6} {:>6} {:>5}",
+                "Player",
+                "Team",
+                "Role",
+                "Games",
+                "K/D/A",
+                "KDA",
+                "GPM",
+                "CSPM",
+                "CSD@10",
+                "GD@10",
+                "GD@15",
+                "KP%"
+            )?;
+            for s in stats {
+                writeln!(
+                    out,
+                    "{:<20} {:<16} {:<8} {:>5} {:>12} {:>6.2} {:>5} {:>5.1} {:>6} {:>6} {:>6} {:>5}",
+                    s.riot_id,
+                    s.team_name,
+                    s.role,
+                    s.games,
+                    format!("{}/{}/{}", s.kills, s.deaths, s.assists),
+                    s.kda,
+                    s.gpm,
+                    s.cspm,
+                    s.cd10
+                        .map(|c| format!("{c:.1}"))
+                        .unwrap_or_else(|| "-".into()),
+                    s.gd10
+                        .map(|g| format!("{g:.0}"))
+                        .unwrap_or_else(|| "-".into()),
+                    s.gd15
+                        .map(|g| format!("{g:.0}"))
+                        .unwrap_or_else(|| "-".into()),
+                    s.kill_participation,
+                )?;
             }
         }
-        Err(e) => eprintln!("Fetch failed: {e}"),
     }
+    Ok(())
 }
 
-pub fn import_manual_matches(conn: &Connection, dir: &Path) -> Result<usize> {
-    // conn.execute("DELETE FROM game WHERE manual = 1", [])?;
-
-    let mut inserted = 0;
-
-    for entry in fs::read_dir(dir).expect("Failed to read directory") {
-        let entry = entry.expect("Invalid directory entry");
-        let path = entry.path();
-
-        if path.extension().and_then(|s| s.to_str()) != Some("json") {
-            continue;
-        }
+#[derive(Serialize)]
+struct GameSummary {
+    id: u64,
+    date: Option<String>,
+    team_1: Option<String>,
+    team_2: Option<String>,
+    winner: Option<String>,
+    duration: u64,
+    series_id: Option<i64>,
+    game_number: Option<u64>,
+}
 
-        if path.file_name().and_then(|s| s.to_str()) == Some("template.json") {
-            continue;
+impl From<&Game> for GameSummary {
+    fn from(game: &Game) -> Self {
+        let info = &game.info;
+        let date = info.played_on();
+        let team = |name: &String| (!name.is_empty()).then(|| name.clone());
+        let (team_1, team_2) = (team(&game.team_1), team(&game.team_2));
+        let winner = match info.winner() {
+            Some(100) => team_1.clone(),
+            Some(200) => team_2.clone(),
+            _ => None,
+        };
+        Self {
+            id: game.id,
+            date,
+            team_1,
+            team_2,
+            winner,
+            duration: info.game_duration,
+            series_id: game.series_id,
+            game_number: game.game_number,
         }
+    }
+}
 
-        let json = fs::read_to_string(&path).expect("Failed to read JSON file");
-
-        let v: Value = serde_json::from_str(&json).expect("Invalid JSON");
-
-        // extract info.gameId
-        let foo = v["info"]["gameId"]
-            .as_i64()
-            .expect("info.gameId missing or not an integer");
-
-        conn.execute(
-            r#"
-            INSERT INTO game (id, data, manual)
-            VALUES (?1, ?2, 1)
-            ON CONFLICT(id) DO UPDATE
-            SET
-                data = excluded.data;
-            "#,
-            (foo, &json),
+fn write_roster(out: &mut impl Write, entries: &[RosterEntry]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:>4} {:<20} {:<30} {:<10} {:<10}",
+        "ID", "Team", "Player", "From", "To"
+    )?;
+    for e in entries {
+        writeln!(
+            out,
+            "{:>4} {:<20} {:<30} {:<10} {:<10}",
+            e.id,
+            e.team,
+            e.player,
+            e.start_date.as_deref().unwrap_or("-"),
+            e.end_date.as_deref().unwrap_or("-"),
         )?;
+    }
+    Ok(())
+}
 
-        inserted += 1;
+fn write_games(out: &mut impl Write, games: &[GameSummary], format: Format) -> io::Result<()> {
+    match format {
+        Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(games)?)?,
+        Format::Table => {
+            writeln!(
+                out,
+                "{:<12} {:<10} {:<20} {:<20} {:<20} {:>6}",
+                "ID", "Date", "Blue", "Red", "Winner", "Length"
+            )?;
+            for g in games {
+                writeln!(
+                    out,
+                    "{:<12} {:<10} {:<20} {:<20} {:<20} {:>3}:{:02}",
+                    g.id,
+                    g.date.as_deref().unwrap_or("-"),
+                    g.team_1.as_deref().unwrap_or("-"),
+                    g.team_2.as_deref().unwrap_or("-"),
+                    g.winner.as_deref().unwrap_or("-"),
+                    g.duration / 60,
+                    g.duration % 60,
+                )?;
+            }
+        }
     }
+    Ok(())
+}
 
-    Ok(inserted)
+/// Load the Stats screen's tables from the database and export them.
+pub fn export_stats(
+    conn: &Connection,
+    dir: &Path,
+    formats: &[ExportFormat],
+) -> color_eyre::Result<Vec<PathBuf>> {
+    let overall = repo::stats_overall(conn)?;
+    let players = repo::stats_players(conn, [None, None, None])?;
+    let champions = repo::stats_all_champions(conn)?;
+    let deep = PlayerDeepStats {
+        overall_stats: repo::stats_player_overall(conn)?,
+        role_stats: repo::stats_player_role(conn)?,
+        champion_stats: repo::stats_player_champion(conn)?,
+    };
+    let export = StatsExport {
+        overall: &overall,
+        players: &players,
+        champions: &champions,
+        deep: &deep,
+    };
+    export
+        .write(dir, formats)
+        .with_context(|| format!("failed to export to {}", dir.display()))
 }
@@ -0,0 +1,512 @@
+//! Writes the stats tables to disk as CSV, JSON or Markdown.
+//!
+//! Each table goes to its own file in the export directory, e.g.
+//! `players.csv`, `champions.md`. Existing files are overwritten.
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::sql::schema::{
+    ChampionStats, OverallStats, PlayerChampionStats, PlayerDeepStats, PlayerOverallStats,
+    PlayerRoleStats, PlayerStats,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [Self::Csv, Self::Json, Self::Markdown];
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+/// A stats row that can be laid out as a table.
+trait Tabular: Serialize {
+    fn headers() -> Vec<&'static str>;
+    fn row(&self) -> Vec<String>;
+}
+
+/// The stats shown on the Stats screen.
+pub struct StatsExport<'a> {
+    pub overall: &'a OverallStats,
+    pub players: &'a [PlayerStats],
+    pub champions: &'a [ChampionStats],
+    pub deep: &'a PlayerDeepStats,
+}
+
+impl StatsExport<'_> {
+    /// Write every table in each of `formats` to `dir`, creating it if
+    /// needed. Returns the files written.
+    pub fn write(&self, dir: &Path, formats: &[ExportFormat]) -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+
+        // The deep stats are keyed by puuid, so borrow names from the
+        // players table to make them readable.
+        let names: HashMap<&str, &str> = self
+            .players
+            .iter()
+            .map(|p| (p.puuid.as_str(), p.riot_id.as_str()))
+            .collect();
+        let name = |puuid: &str| names.get(puuid).copied().unwrap_or(puuid).to_string();
+
+        let mut player_overall: Vec<_> = self
+            .deep
+            .overall_stats
+            .values()
+            .map(|s| Named::new(name(&s.puuid), s))
+            .collect();
+        player_overall.sort_by(|a, b| a.player.cmp(&b.player));
+
+        let mut player_roles: Vec<_> = self
+            .deep
+            .role_stats
+            .values()
+            .flatten()
+            .map(|s| Named::new(name(&s.puuid), s))
+            .collect();
+        player_roles.sort_by(|a, b| (&a.player, &a.stats.role).cmp(&(&b.player, &b.stats.role)));
+
+        let mut player_champions: Vec<_> = self
+            .deep
+            .champion_stats
+            .values()
+            .flatten()
+            .map(|s| Named::new(name(&s.puuid), s))
+            .collect();
+        player_champions.sort_by(|a, b| {
+            (&a.player, b.stats.games, &a.stats.champion_name).cmp(&(
+                &b.player,
+                a.stats.games,
+                &b.stats.champion_name,
+            ))
+        });
+
+        let mut written = Vec::new();
+        for &format in formats {
+            let mut write = |stem: &str, contents: String| -> io::Result<()> {
+                let path = dir.join(format!("{stem}.{}", format.extension()));
+                fs::write(&path, contents)?;
+                written.push(path);
+                Ok(())
+            };
+            write(
+                "overall",
+                render(std::slice::from_ref(self.overall), format)?,
+            )?;
+            write("players", render(self.players, format)?)?;
+            write("champions", render(self.champions, format)?)?;
+            write("player_overall", render(&player_overall, format)?)?;
+            write("player_roles", render(&player_roles, format)?)?;
+            write("player_champions", render(&player_champions, format)?)?;
+        }
+        Ok(written)
+    }
+}
+
+fn render<T: Tabular>(rows: &[T], format: ExportFormat) -> io::Result<String> {
+    let mut out = String::new();
+    match format {
+        ExportFormat::Json => {
+            out = serde_json::to_string_pretty(rows)?;
+            out.push('\n');
+        }
+        ExportFormat::Csv => {
+            let line = |cells: Vec<String>| {
+                cells
+                    .iter()
+                    .map(|c| csv_escape(c))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+            out.push_str(&line(T::headers().into_iter().map(String::from).collect()));
+            out.push('\n');
+            for row in rows {
+                out.push_str(&line(row.row()));
+                out.push('\n');
+            }
+        }
+        ExportFormat::Markdown => {
+            let line = |cells: Vec<String>| {
+                let cells: Vec<_> = cells.iter().map(|c| markdown_escape(c)).collect();
+                format!("| {} |\n", cells.join(" | "))
+            };
+            let headers = T::headers();
+            out.push_str(&line(headers.iter().map(|h| h.to_string()).collect()));
+            out.push_str(&line(headers.iter().map(|_| "---".to_string()).collect()));
+            for row in rows {
+                out.push_str(&line(row.row()));
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Pipes would end the cell and line breaks the row, so escape one and turn
+/// the other into `<br>`.
+fn markdown_escape(cell: &str) -> String {
+    cell.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
+
+fn num(n: f64) -> String {
+    format!("{n:.2}")
+}
+
+fn opt<T: Display>(n: Option<T>) -> String {
+    n.map(|n| n.to_string()).unwrap_or_default()
+}
+
+fn opt_num(n: Option<f64>) -> String {
+    n.map(num).unwrap_or_default()
+}
+
+/// A per-player row with the player's name in front.
+#[derive(Serialize)]
+struct Named<'a, T> {
+    player: String,
+    #[serde(flatten)]
+    stats: &'a T,
+}
+
+impl<'a, T> Named<'a, T> {
+    fn new(player: String, stats: &'a T) -> Self {
+        Self { player, stats }
+    }
+}
+
+impl<T: Tabular> Tabular for Named<'_, T> {
+    fn headers() -> Vec<&'static str> {
+        let mut headers = vec!["Player"];
+        headers.extend(T::headers());
+        headers
+    }
+
+    fn row(&self) -> Vec<String> {
+        let mut row = vec![self.player.clone()];
+        row.extend(self.stats.row());
+        row
+    }
+}
+
+impl Tabular for OverallStats {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "Games",
+            "Blue wins",
+            "Red wins",
+            "Avg length",
+            "Min length",
+            "Max length",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.games.to_string(),
+            self.blue_wins.to_string(),
+            self.red_wins.to_string(),
+            self.game_length_avg.to_string(),
+            self.game_length_min.to_string(),
+            self.game_length_max.to_string(),
+        ]
+    }
+}
+
+impl Tabular for PlayerStats {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "Player",
+            "Tag",
+            "Team",
+            "Role",
+            "Games",
+            "Kills",
+            "Deaths",
+            "Assists",
+            "KDA",
+            "GPM",
+            "CSPM",
+            "CSD@10",
+            "GD@10",
+            "XPD@10",
+            "TL CSD@10",
+            "GD@15",
+            "XPD@15",
+            "CSD@15",
+            "KP%",
+            "DP%",
+            "DPM",
+            "VSPM",
+            "FB kills",
+            "FB assists",
+            "FT kills",
+            "FT assists",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.riot_id.clone(),
+            self.tag_line.clone(),
+            self.team_name.clone(),
+            self.role.clone(),
+            self.games.to_string(),
+            self.kills.to_string(),
+            self.deaths.to_string(),
+            self.assists.to_string(),
+            num(self.kda),
+            self.gpm.to_string(),
+            num(self.cspm),
+            opt_num(self.cd10),
+            opt_num(self.gd10),
+            opt_num(self.xpd10),
+            opt_num(self.timeline_cd10),
+            opt_num(self.gd15),
+            opt_num(self.xpd15),
+            opt_num(self.cd15),
+            self.kill_participation.to_string(),
+            self.death_participation.to_string(),
+            opt(self.dpm),
+            opt_num(self.vpm),
+            self.fb_kills.to_string(),
+            self.fb_assists.to_string(),
+            self.ft_kills.to_string(),
+            self.ft_assists.to_string(),
+        ]
+    }
+}
+
+impl Tabular for ChampionStats {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "Champion",
+            "Games",
+            "Pick%",
+            "Ban%",
+            "Presence%",
+            "FP%",
+            "Players",
+            "Wins",
+            "Losses",
+            "Win%",
+            "Kills",
+            "Deaths",
+            "Assists",
+            "KDA",
+            "CS",
+            "CSM",
+            "VS",
+            "VSM",
+            "Gold",
+            "GPM",
+            "Damage",
+            "DPM",
+            "KP%",
+            "Kill share",
+            "Gold share",
+            "Roles",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.champion.clone(),
+            self.games.to_string(),
+            num(self.pick_percentage),
+            num(self.ban_percentage),
+            num(self.presence_percentage),
+            opt_num(self.first_pick_percentage),
+            self.unique_players.to_string(),
+            self.wins.to_string(),
+            self.losses.to_string(),
+            num(self.win_percentage),
+            num(self.kills),
+            num(self.deaths),
+            num(self.assists),
+            num(self.kda),
+            num(self.cs),
+            num(self.csm),
+            opt_num(self.vs),
+            opt_num(self.vsm),
+            num(self.gold),
+            num(self.goldm),
+            opt_num(self.damage),
+            opt_num(self.damagem),
+            num(self.kill_percentage),
+            num(self.kill_share),
+            num(self.gold_share),
+            self.roles.clone(),
+        ]
+    }
+}
+
+impl Tabular for PlayerOverallStats {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "Games", "Wins", "Losses", "Win%", "K/G", "D/G", "A/G", "KDA", "GPM", "DPM", "CSD@10",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.games.to_string(),
+            self.wins.to_string(),
+            self.losses.to_string(),
+            self.win_percent.to_string(),
+            num(self.kills_per_game),
+            num(self.deaths_per_game),
+            num(self.assists_per_game),
+            num(self.kda),
+            self.gpm.to_string(),
+            opt(self.dpm),
+            opt_num(self.cd10),
+        ]
+    }
+}
+
+impl Tabular for PlayerRoleStats {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "Role", "Games", "Wins", "Losses", "Win%", "K/G", "D/G", "A/G", "KDA", "GPM", "DPM",
+            "CSD@10",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.role.clone(),
+            self.games.to_string(),
+            self.wins.to_string(),
+            self.losses.to_string(),
+            self.win_percent.to_string(),
+            num(self.kills_per_game),
+            num(self.deaths_per_game),
+            num(self.assists_per_game),
+            num(self.kda),
+            self.gpm.to_string(),
+            opt(self.dpm),
+            opt_num(self.cd10),
+        ]
+    }
+}
+
+impl Tabular for PlayerChampionStats {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "Champion", "Role", "Games", "Wins", "Losses", "Win%", "K/G", "D/G", "A/G", "KDA",
+            "GPM", "DPM", "CSD@10",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.champion_name.clone(),
+            self.role.clone(),
+            self.games.to_string(),
+            self.wins.to_string(),
+            self.losses.to_string(),
+            self.win_percent.to_string(),
+            num(self.kills_per_game),
+            num(self.deaths_per_game),
+            num(self.assists_per_game),
+            num(self.kda),
+            self.gpm.to_string(),
+            opt(self.dpm),
+            opt_num(self.cd10),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Note {
+        team: String,
+        games: u64,
+    }
+
+    impl Tabular for Note {
+        fn headers() -> Vec<&'static str> {
+            vec!["Team", "Games"]
+        }
+
+        fn row(&self) -> Vec<String> {
+            vec![self.team.clone(), self.games.to_string()]
+        }
+    }
+
+    fn notes() -> Vec<Note> {
+        [
+            "Tigers",
+            "Eagles, \"the Birds\"",
+            "Line one\nline two",
+            "A|B\r\nC",
+        ]
+        .into_iter()
+        .zip(1..)
+        .map(|(team, games)| Note {
+            team: team.into(),
+            games,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn csv_quotes_cells_with_commas_quotes_and_line_breaks() {
+        assert_eq!(
+            render(&notes(), ExportFormat::Csv).unwrap(),
+            "Team,Games\n\
+             Tigers,1\n\
+             \"Eagles, \"\"the Birds\"\"\",2\n\
+             \"Line one\nline two\",3\n\
+             \"A|B\r\nC\",4\n"
+        );
+    }
+
+    #[test]
+    fn markdown_keeps_each_row_on_one_line() {
+        assert_eq!(
+            render(&notes(), ExportFormat::Markdown).unwrap(),
+            "| Team | Games |\n\
+             | --- | --- |\n\
+             | Tigers | 1 |\n\
+             | Eagles, \"the Birds\" | 2 |\n\
+             | Line one<br>line two | 3 |\n\
+             | A\\|B<br>C | 4 |\n"
+        );
+    }
+
+    #[test]
+    fn json_keeps_cells_as_they_are() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&notes(), ExportFormat::Json).unwrap()).unwrap();
+        assert_eq!(json[2]["team"], "Line one\nline two");
+        assert_eq!(json[3]["games"], 4);
+    }
+}
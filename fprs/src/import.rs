@@ -0,0 +1,1058 @@
+//! Importing manually entered games from a folder of JSON files.
+//!
+//! Every file is checked on its own and the result recorded in an
+//! [`ImportReport`], so one bad file doesn't stop the rest.
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rusqlite::Connection;
+use serde_json::Value;
+
+use crate::riot::model::MatchDto;
+use crate::sql::repo;
+use crate::sql::schema::StoredGame;
+
+pub const TEMPLATE_FILE: &str = "template.json";
+
+/// Participant fields required when the folder has no `template.json`.
+const DEFAULT_FIELDS: [&str; 4] = ["riotIdGameName", "kills", "deaths", "assists"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportOutcome {
+    Imported,
+    Updated,
+    Skipped(String),
+    Failed(String),
+}
+
+impl fmt::Display for ImportOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Imported => write!(f, "Imported"),
+            Self::Updated => write!(f, "Updated"),
+            Self::Skipped(reason) => write!(f, "Skipped: {reason}"),
+            Self::Failed(reason) => write!(f, "Failed: {reason}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportEntry {
+    pub file: String,
+    pub game_id: Option<u64>,
+    pub outcome: ImportOutcome,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub entries: Vec<ImportEntry>,
+}
+
+impl ImportReport {
+    fn count(&self, f: impl Fn(&ImportOutcome) -> bool) -> usize {
+        self.entries.iter().filter(|e| f(&e.outcome)).count()
+    }
+
+    pub fn imported(&self) -> usize {
+        self.count(|o| *o == ImportOutcome::Imported)
+    }
+
+    pub fn updated(&self) -> usize {
+        self.count(|o| *o == ImportOutcome::Updated)
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.count(|o| matches!(o, ImportOutcome::Skipped(_)))
+    }
+
+    pub fn failed(&self) -> usize {
+        self.count(|o| matches!(o, ImportOutcome::Failed(_)))
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} imported, {} updated, {} skipped, {} failed",
+            self.imported(),
+            self.updated(),
+            self.skipped(),
+            self.failed()
+        )
+    }
+}
+
+/// Whether a file would be written, and why not if it wouldn't.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileStatus {
+    New,
+    Changed(Vec<FieldChange>),
+    Unchanged,
+    Skipped(String),
+    Failed(String),
+}
+
+impl fmt::Display for FileStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::New => write!(f, "New"),
+            Self::Changed(changes) if changes.len() == 1 => write!(f, "Changed (1 field)"),
+            Self::Changed(changes) => write!(f, "Changed ({} fields)", changes.len()),
+            Self::Unchanged => write!(f, "Unchanged"),
+            Self::Skipped(reason) => write!(f, "Skipped: {reason}"),
+            Self::Failed(reason) => write!(f, "Failed: {reason}"),
+        }
+    }
+}
+
+/// One value that differs between the stored game and the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// `game`, `team 100`, or the participant's name.
+    pub location: String,
+    /// Dotted path within it, e.g. `challenges.soloKills`.
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} -> {}",
+            self.location, self.field, self.old, self.new
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlannedFile {
+    pub file: String,
+    pub game_id: Option<u64>,
+    pub status: FileStatus,
+    /// Whether to write this file when the plan is applied.
+    pub selected: bool,
+    /// Teams and game number read from the file name, filled in on games
+    /// that don't have them yet.
+    pub assignment: Option<Assignment>,
+    game: Option<(Value, MatchDto)>,
+}
+
+impl PlannedFile {
+    /// Only new and changed files have anything to write.
+    pub fn selectable(&self) -> bool {
+        matches!(self.status, FileStatus::New | FileStatus::Changed(_))
+    }
+}
+
+/// What importing a folder would do, worked out without touching the
+/// database. Pick files with `selected`, then [`apply`] it.
+#[derive(Debug, Clone, Default)]
+pub struct ImportPlan {
+    pub files: Vec<PlannedFile>,
+}
+
+impl ImportPlan {
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(file) = self.files.get_mut(index)
+            && file.selectable()
+        {
+            file.selected = !file.selected;
+        }
+    }
+
+    /// Select every new and changed file, or none if they're all selected.
+    pub fn toggle_all(&mut self) {
+        let select = !self
+            .files
+            .iter()
+            .filter(|f| f.selectable())
+            .all(|f| f.selected);
+        for file in self.files.iter_mut().filter(|f| f.selectable()) {
+            file.selected = select;
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let count = |f: fn(&FileStatus) -> bool| self.files.iter().filter(|e| f(&e.status)).count();
+        format!(
+            "{} new, {} changed, {} unchanged, {} skipped, {} failed, {} selected",
+            count(|s| *s == FileStatus::New),
+            count(|s| matches!(s, FileStatus::Changed(_))),
+            count(|s| *s == FileStatus::Unchanged),
+            count(|s| matches!(s, FileStatus::Skipped(_))),
+            count(|s| matches!(s, FileStatus::Failed(_))),
+            self.files.iter().filter(|f| f.selected).count(),
+        )
+    }
+}
+
+/// Check every file in `dir` against the template and the stored games. Only
+/// a missing or unreadable folder is an error, problems with individual
+/// files end up in the plan. New and changed files start out selected.
+pub fn plan_manual_import(
+    conn: &Connection,
+    dir: &Path,
+    naming: &FileNaming,
+) -> io::Result<ImportPlan> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+
+    let fields = template_fields(&dir.join(TEMPLATE_FILE));
+    let mut plan = ImportPlan::default();
+    // Game ID to the file it came from, to catch two files sharing an ID
+    let mut seen: HashMap<u64, String> = HashMap::new();
+
+    for path in paths {
+        if !path.is_file() {
+            continue;
+        }
+        let file = path
+            .file_name()
+            .map(|f| f.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut planned = PlannedFile {
+            file: file.clone(),
+            game_id: None,
+            status: FileStatus::New,
+            selected: false,
+            assignment: None,
+            game: None,
+        };
+
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            planned.status = FileStatus::Skipped("not a JSON file".into());
+        } else if file == TEMPLATE_FILE {
+            planned.status = FileStatus::Skipped("template".into());
+        } else if let Err(reason) = plan_file(conn, &path, &fields, naming, &seen, &mut planned) {
+            planned.status = FileStatus::Failed(reason);
+        } else if let Some(id) = planned.game_id {
+            seen.insert(id, file.clone());
+        }
+        planned.selected = planned.selectable();
+        plan.files.push(planned);
+    }
+
+    Ok(plan)
+}
+
+fn plan_file(
+    conn: &Connection,
+    path: &Path,
+    fields: &[String],
+    naming: &FileNaming,
+    seen: &HashMap<u64, String>,
+    planned: &mut PlannedFile,
+) -> Result<(), String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("could not read file: {e}"))?;
+    let value: Value = serde_json::from_str(&text).map_err(|e| format!("invalid JSON: {e}"))?;
+    let game = validate(&value, fields)?;
+    let id = game.info.game_id;
+    planned.game_id = Some(id);
+    if let Some(other) = seen.get(&id) {
+        return Err(format!("game {id} is already in {other}"));
+    }
+
+    let assignment = path
+        .file_stem()
+        .and_then(|stem| naming.assignment(&stem.to_string_lossy()));
+
+    planned.status = match repo::stored_game(conn, id).map_err(|e| e.to_string())? {
+        None => FileStatus::New,
+        Some(stored) if !stored.manual => {
+            FileStatus::Skipped(format!("game {id} was fetched from the API"))
+        }
+        Some(stored) => {
+            let mut changes = diff(&stored.data, &value);
+            if let Some(assignment) = &assignment {
+                changes.extend(assignment.changes(&stored));
+            }
+            match changes.is_empty() && stored.data == value {
+                true => FileStatus::Unchanged,
+                false => FileStatus::Changed(changes),
+            }
+        }
+    };
+    planned.assignment = assignment;
+    planned.game = Some((value, game));
+    Ok(())
+}
+
+/// Write the selected files, then fill in their teams and series. Everything
+/// else is reported as skipped or failed with the reason from the plan.
+///
+/// Teams the file name doesn't give are taken from the rosters, matching
+/// players through `aliases` (game name to puuid).
+pub fn apply(
+    conn: &Connection,
+    plan: &ImportPlan,
+    aliases: &HashMap<String, String>,
+) -> ImportReport {
+    let mut written = Vec::new();
+    let entries = plan
+        .files
+        .iter()
+        .map(|planned| {
+            let outcome = match (&planned.status, &planned.game) {
+                (FileStatus::Skipped(reason), _) => ImportOutcome::Skipped(reason.clone()),
+                (FileStatus::Failed(reason), _) => ImportOutcome::Failed(reason.clone()),
+                (FileStatus::Unchanged, _) => ImportOutcome::Skipped("unchanged".into()),
+                _ if !planned.selected => ImportOutcome::Skipped("not selected".into()),
+                (status, Some((value, game))) => {
+                    match write_game(conn, value, game, planned.assignment.as_ref(), aliases) {
+                        Ok(()) => {
+                            written.push(planned);
+                            match status {
+                                FileStatus::New => ImportOutcome::Imported,
+                                _ => ImportOutcome::Updated,
+                            }
+                        }
+                        Err(e) => ImportOutcome::Failed(e.to_string()),
+                    }
+                }
+                (_, None) => ImportOutcome::Failed("nothing to import".into()),
+            };
+            ImportEntry {
+                file: planned.file.clone(),
+                game_id: planned.game_id,
+                outcome,
+            }
+        })
+        .collect();
+
+    let mut report = ImportReport { entries };
+    if let Err(e) = assign_series(conn, &written) {
+        report.entries.push(ImportEntry {
+            file: String::new(),
+            game_id: None,
+            outcome: ImportOutcome::Failed(format!("could not group games into series: {e}")),
+        });
+    }
+    report
+}
+
+fn write_game(
+    conn: &Connection,
+    value: &Value,
+    game: &MatchDto,
+    assignment: Option<&Assignment>,
+    aliases: &HashMap<String, String>,
+) -> rusqlite::Result<()> {
+    let id = game.info.game_id;
+    repo::upsert_manual_game(conn, id, value, &game.info)?;
+    if let Some(assignment) = assignment {
+        repo::fill_teams(
+            conn,
+            id,
+            assignment.team_1.as_deref(),
+            assignment.team_2.as_deref(),
+        )?;
+    }
+    repo::assign_teams_from_roster(conn, id, &game.info, aliases)?;
+    Ok(())
+}
+
+/// Put games with a game number from their file name into a series, one per
+/// pair of teams per day, unless they're already in one.
+fn assign_series(conn: &Connection, files: &[&PlannedFile]) -> rusqlite::Result<()> {
+    for file in files {
+        let (Some(number), Some((_, game))) = (
+            file.assignment.as_ref().and_then(|a| a.game_number),
+            &file.game,
+        ) else {
+            continue;
+        };
+        let id = game.info.game_id;
+        let Some(StoredGame {
+            team_1: Some(team_1),
+            team_2: Some(team_2),
+            series_id: None,
+            ..
+        }) = repo::stored_game(conn, id)?
+        else {
+            continue;
+        };
+
+        let date = game.info.played_on();
+        // The file names don't say the format, so the score decides it
+        let series_id = match repo::find_series(conn, &team_1, &team_2, date.as_deref())? {
+            Some(series_id) => series_id,
+            None => repo::insert_series(conn, &team_1, &team_2, None, date.as_deref(), None)?,
+        };
+        repo::attach_game_to_series(conn, id, Some(series_id), Some(number))?;
+    }
+    Ok(())
+}
+
+/// Compare the fields of the game, each team and each participant, and
+/// anything in the file outside `info`. Teams and participants are matched up
+/// by position.
+fn diff(old: &Value, new: &Value) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    diff_fields(
+        "file",
+        &without(old, &["info"]),
+        &without(new, &["info"]),
+        &mut changes,
+    );
+    let (old, new) = (&old["info"], &new["info"]);
+    let listed = ["teams", "participants"];
+    diff_fields(
+        "game",
+        &without(old, &listed),
+        &without(new, &listed),
+        &mut changes,
+    );
+
+    for key in ["teams", "participants"] {
+        let empty = Vec::new();
+        let old_items = old[key].as_array().unwrap_or(&empty);
+        let new_items = new[key].as_array().unwrap_or(&empty);
+        for i in 0..old_items.len().max(new_items.len()) {
+            let (o, n) = (
+                old_items.get(i).unwrap_or(&Value::Null),
+                new_items.get(i).unwrap_or(&Value::Null),
+            );
+            let location = match key {
+                "teams" => format!(
+                    "team {}",
+                    n["teamId"].as_u64().or(o["teamId"].as_u64()).unwrap_or(0)
+                ),
+                _ => n["riotIdGameName"]
+                    .as_str()
+                    .or(o["riotIdGameName"].as_str())
+                    .map(String::from)
+                    .unwrap_or_else(|| format!("participant {}", i + 1)),
+            };
+            diff_fields(&location, o, n, &mut changes);
+        }
+    }
+    changes
+}
+
+fn diff_fields(location: &str, old: &Value, new: &Value, changes: &mut Vec<FieldChange>) {
+    let (old, new) = (scalar_fields(old), scalar_fields(new));
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    let show = |v: Option<&&Value>| v.map(|v| v.to_string()).unwrap_or_else(|| "-".into());
+    for key in keys {
+        let (o, n) = (old.get(key), new.get(key));
+        if o != n {
+            changes.push(FieldChange {
+                location: location.to_string(),
+                field: key.clone(),
+                old: show(o),
+                new: show(n),
+            });
+        }
+    }
+}
+
+/// `value` without the given keys, if it's an object.
+fn without(value: &Value, keys: &[&str]) -> Value {
+    let mut value = value.clone();
+    if let Some(object) = value.as_object_mut() {
+        for key in keys {
+            object.remove(*key);
+        }
+    }
+    value
+}
+
+/// Every scalar value in an object, with nested objects and arrays flattened
+/// into dotted keys, e.g. `bans.0.championId`.
+fn scalar_fields(value: &Value) -> HashMap<String, &Value> {
+    fn walk<'a>(prefix: &str, value: &'a Value, out: &mut HashMap<String, &'a Value>) {
+        let path = |key: &str| match prefix {
+            "" => key.to_string(),
+            _ => format!("{prefix}.{key}"),
+        };
+        match value {
+            Value::Object(object) => {
+                for (key, v) in object {
+                    walk(&path(key), v, out);
+                }
+            }
+            Value::Array(items) => {
+                for (i, v) in items.iter().enumerate() {
+                    walk(&path(&i.to_string()), v, out);
+                }
+            }
+            _ if prefix.is_empty() => {}
+            _ => {
+                out.insert(prefix.to_string(), value);
+            }
+        }
+    }
+    let mut out = HashMap::new();
+    walk("", value, &mut out);
+    out
+}
+
+/// The participant fields named in the template, or [`DEFAULT_FIELDS`] if
+/// it's missing or unreadable.
+fn template_fields(path: &Path) -> Vec<String> {
+    let fields = fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .and_then(|v| {
+            v.pointer("/info/participants/0")
+                .and_then(Value::as_object)
+                .map(|p| p.keys().cloned().collect::<Vec<_>>())
+        });
+    fields.unwrap_or_else(|| DEFAULT_FIELDS.iter().map(|f| f.to_string()).collect())
+}
+
+/// Check a manual game is complete enough to store: a game ID, ten
+/// participants with the template's fields, five a side, and a winner.
+pub fn validate(value: &Value, fields: &[String]) -> Result<MatchDto, String> {
+    let info = value.get("info").ok_or("missing info")?;
+    match info.get("gameId") {
+        Some(id) if id.as_u64().is_some_and(|id| id > 0) => {}
+        Some(id) => return Err(format!("gameId must be a positive number, got {id}")),
+        None => return Err("missing info.gameId".into()),
+    }
+
+    let participants = info
+        .get("participants")
+        .and_then(Value::as_array)
+        .ok_or("missing info.participants")?;
+    if participants.len() != 10 {
+        return Err(format!(
+            "expected 10 participants, found {}",
+            participants.len()
+        ));
+    }
+    for (i, p) in participants.iter().enumerate() {
+        let missing: Vec<&str> = fields
+            .iter()
+            .filter(|f| p.get(f.as_str()).is_none_or(Value::is_null))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!(
+                "participant {} is missing {}",
+                i + 1,
+                missing.join(", ")
+            ));
+        }
+    }
+
+    let game = MatchDto::from_value(value).map_err(|e| format!("invalid match: {e}"))?;
+    let info = &game.info;
+
+    for team_id in [100, 200] {
+        let count = info
+            .participants
+            .iter()
+            .filter(|p| p.team_id == team_id)
+            .count();
+        if count != 5 {
+            return Err(format!(
+                "team {team_id} has {count} participants, expected 5"
+            ));
+        }
+    }
+    match info.winner() {
+        Some(100 | 200) => {}
+        Some(id) => return Err(format!("winner {id} is not team 100 or 200")),
+        None => return Err("no winner, set winningTeamId or a team's win".into()),
+    }
+
+    Ok(game)
+}
+
+/// Reads teams and the game number from manual file names.
+///
+/// Patterns are matched against the name without its extension. `{team1}`
+/// and `{team2}` match team abbreviations, `{game}` the game number, and
+/// anything else has to match exactly, e.g. `{team1}_{team2}_{game}` for
+/// `lt_el_1.json`. Patterns without both teams never match. Abbreviations
+/// are looked up ignoring case.
+#[derive(Debug, Clone, Default)]
+pub struct FileNaming {
+    patterns: Vec<Vec<Token>>,
+    teams: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Token {
+    Literal(String),
+    Team1,
+    Team2,
+    Game,
+}
+
+/// What a file name says about its game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub team_1_abbr: String,
+    pub team_2_abbr: String,
+    /// `None` when the abbreviation isn't in the config.
+    pub team_1: Option<String>,
+    pub team_2: Option<String>,
+    pub game_number: Option<u64>,
+}
+
+impl Assignment {
+    /// The teams and game number this would fill in on a stored game.
+    fn changes(&self, stored: &StoredGame) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+        let mut fill = |field: &str, old: bool, new: Option<String>| {
+            if let (false, Some(new)) = (old, new) {
+                changes.push(FieldChange {
+                    location: "game".into(),
+                    field: field.into(),
+                    old: "-".into(),
+                    new,
+                });
+            }
+        };
+        fill("team_1", stored.team_1.is_some(), self.team_1.clone());
+        fill("team_2", stored.team_2.is_some(), self.team_2.clone());
+        fill(
+            "game_number",
+            stored.series_id.is_some(),
+            self.game_number
+                .filter(|_| self.team_1.is_some() && self.team_2.is_some())
+                .map(|n| n.to_string()),
+        );
+        changes
+    }
+}
+
+impl FileNaming {
+    pub fn new(patterns: Vec<String>, teams: &HashMap<String, String>) -> Self {
+        Self {
+            patterns: patterns.iter().map(|p| tokenize(p)).collect(),
+            teams: teams
+                .iter()
+                .map(|(abbr, name)| (abbr.to_lowercase(), name.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn set_team(&mut self, abbr: &str, name: &str) {
+        self.teams.insert(abbr.to_lowercase(), name.to_string());
+    }
+
+    /// Match a file name (without extension) against the first pattern that fits.
+    pub fn assignment(&self, stem: &str) -> Option<Assignment> {
+        self.patterns.iter().find_map(|pattern| {
+            let mut captures = HashMap::new();
+            if !match_tokens(pattern, stem, &mut captures) {
+                return None;
+            }
+            let team_1_abbr = captures.remove(&Token::Team1)?;
+            let team_2_abbr = captures.remove(&Token::Team2)?;
+            Some(Assignment {
+                team_1: self.teams.get(&team_1_abbr.to_lowercase()).cloned(),
+                team_2: self.teams.get(&team_2_abbr.to_lowercase()).cloned(),
+                team_1_abbr,
+                team_2_abbr,
+                game_number: captures.get(&Token::Game).and_then(|n| n.parse().ok()),
+            })
+        })
+    }
+
+    /// Abbreviations in the file names in `dir` that have no team name,
+    /// sorted and without repeats.
+    pub fn unresolved(&self, dir: &Path) -> io::Result<Vec<String>> {
+        let mut missing = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(assignment) = path
+                .file_stem()
+                .and_then(|stem| self.assignment(&stem.to_string_lossy()))
+            else {
+                continue;
+            };
+            if assignment.team_1.is_none() {
+                missing.push(assignment.team_1_abbr);
+            }
+            if assignment.team_2.is_none() {
+                missing.push(assignment.team_2_abbr);
+            }
+        }
+        missing.sort_by_key(|abbr| abbr.to_lowercase());
+        missing.dedup_by_key(|abbr| abbr.to_lowercase());
+        Ok(missing)
+    }
+}
+
+fn tokenize(pattern: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = pattern;
+    while !rest.is_empty() {
+        let placeholder = [
+            ("{team1}", Token::Team1),
+            ("{team2}", Token::Team2),
+            ("{game}", Token::Game),
+        ]
+        .into_iter()
+        .find(|(text, _)| rest.starts_with(text));
+        match placeholder {
+            Some((text, token)) => {
+                tokens.push(token);
+                rest = &rest[text.len()..];
+            }
+            None => {
+                let c = rest.chars().next().unwrap_or_default();
+                match tokens.last_mut() {
+                    Some(Token::Literal(literal)) => literal.push(c),
+                    _ => tokens.push(Token::Literal(c.to_string())),
+                }
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    tokens
+}
+
+/// Match `text` against `tokens`, trying the shortest capture for each
+/// placeholder first.
+fn match_tokens(tokens: &[Token], text: &str, captures: &mut HashMap<Token, String>) -> bool {
+    let Some((token, rest)) = tokens.split_first() else {
+        return text.is_empty();
+    };
+    match token {
+        Token::Literal(literal) => text
+            .strip_prefix(literal.as_str())
+            .is_some_and(|text| match_tokens(rest, text, captures)),
+        _ => {
+            let ends = text.char_indices().map(|(i, _)| i).skip(1);
+            for end in ends.chain([text.len()]).filter(|&end| end > 0) {
+                let capture = &text[..end];
+                if *token == Token::Game && !capture.chars().all(|c| c.is_ascii_digit()) {
+                    break;
+                }
+                if match_tokens(rest, &text[end..], captures) {
+                    captures.insert(token.clone(), capture.to_string());
+                    return true;
+                }
+            }
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// A manual game blue side won, five players a side.
+    fn game(id: u64) -> Value {
+        let participants: Vec<Value> = (0..10)
+            .map(|i| {
+                json!({
+                    "riotIdGameName": format!("Player {i}"),
+                    "championName": "Ahri",
+                    "teamId": if i < 5 { 100 } else { 200 },
+                    "kills": i,
+                    "deaths": 2,
+                    "assists": 3,
+                })
+            })
+            .collect();
+        json!({
+            "info": {
+                "gameId": id,
+                "gameDuration": 1800,
+                "gameEndDate": "2025-11-26",
+                "winningTeamId": 100,
+                "teams": [
+                    {"teamId": 100, "win": true, "bans": [{"championId": 145, "pickTurn": 1}]},
+                    {"teamId": 200, "win": false, "bans": []},
+                ],
+                "participants": participants,
+            }
+        })
+    }
+
+    fn default_fields() -> Vec<String> {
+        DEFAULT_FIELDS.map(String::from).to_vec()
+    }
+
+    fn change(location: &str, field: &str, old: &str, new: &str) -> FieldChange {
+        FieldChange {
+            location: location.into(),
+            field: field.into(),
+            old: old.into(),
+            new: new.into(),
+        }
+    }
+
+    #[test]
+    fn diff_lists_fields_inside_teams_and_participants() {
+        let old = game(1);
+        let mut new = game(1);
+        new["info"]["gameDuration"] = json!(1900);
+        new["info"]["teams"][0]["bans"][0]["championId"] = json!(157);
+        new["info"]["teams"][1]["bans"] = json!([{"championId": 84, "pickTurn": 2}]);
+        new["info"]["participants"][2]["kills"] = json!(9);
+        new["info"]["participants"][2]["perks"] = json!({"styles": [{"style": 8000}]});
+
+        assert_eq!(
+            diff(&old, &new),
+            [
+                change("game", "gameDuration", "1800", "1900"),
+                change("team 100", "bans.0.championId", "145", "157"),
+                change("team 200", "bans.0.championId", "-", "84"),
+                change("team 200", "bans.0.pickTurn", "-", "2"),
+                change("Player 2", "kills", "2", "9"),
+                change("Player 2", "perks.styles.0.style", "-", "8000"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_lists_fields_outside_info() {
+        let old = game(1);
+        let mut new = game(1);
+        new["metadata"] = json!({"matchId": "OC1_1"});
+        assert_eq!(
+            diff(&old, &new),
+            [change("file", "metadata.matchId", "-", r#""OC1_1""#)]
+        );
+        assert!(diff(&old, &old).is_empty());
+    }
+
+    /// An empty folder of its own under the system temp folder.
+    fn folder(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("fprs-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(dir: &Path, file: &str, value: &Value) {
+        fs::write(dir.join(file), value.to_string()).unwrap();
+    }
+
+    #[test]
+    fn plan_compares_files_with_the_stored_games() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::sql::migrations::migrate(&mut conn).unwrap();
+        let dir = folder("plan");
+        write(&dir, "a.json", &game(1));
+        write(&dir, "b.json", &game(2));
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let plan = plan_manual_import(&conn, &dir, &FileNaming::default()).unwrap();
+        let statuses: Vec<(&str, &FileStatus, bool)> = plan
+            .files
+            .iter()
+            .map(|f| (f.file.as_str(), &f.status, f.selected))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("a.json", &FileStatus::New, true),
+                ("b.json", &FileStatus::New, true),
+                (
+                    "notes.txt",
+                    &FileStatus::Skipped("not a JSON file".into()),
+                    false
+                ),
+            ]
+        );
+        assert_eq!(apply(&conn, &plan, &HashMap::new()).imported(), 2);
+
+        let mut changed = game(2);
+        changed["info"]["participants"][7]["assists"] = json!(4);
+        changed["info"]["teams"][1]["bans"] = json!([{"championId": 84, "pickTurn": 2}]);
+        write(&dir, "b.json", &changed);
+
+        let plan = plan_manual_import(&conn, &dir, &FileNaming::default()).unwrap();
+        assert_eq!(plan.files[0].status, FileStatus::Unchanged);
+        assert!(!plan.files[0].selected);
+        assert_eq!(
+            plan.files[1].status,
+            FileStatus::Changed(vec![
+                change("team 200", "bans.0.championId", "-", "84"),
+                change("team 200", "bans.0.pickTurn", "-", "2"),
+                change("Player 7", "assists", "3", "4"),
+            ])
+        );
+        assert!(plan.files[1].selected);
+
+        let report = apply(&conn, &plan, &HashMap::new());
+        assert_eq!((report.updated(), report.skipped()), (1, 2));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn validate_accepts_a_complete_game() {
+        let game = validate(&game(7), &default_fields()).unwrap();
+        assert_eq!(game.info.game_id, 7);
+        assert_eq!(game.info.participants.len(), 10);
+        assert_eq!(game.info.winner(), Some(100));
+    }
+
+    #[test]
+    fn validate_needs_a_positive_game_id() {
+        assert_eq!(
+            validate(&game(0), &default_fields()).unwrap_err(),
+            "gameId must be a positive number, got 0"
+        );
+        let mut text_id = game(1);
+        text_id["info"]["gameId"] = json!("one");
+        assert_eq!(
+            validate(&text_id, &default_fields()).unwrap_err(),
+            r#"gameId must be a positive number, got "one""#
+        );
+        let mut missing = game(1);
+        missing["info"].as_object_mut().unwrap().remove("gameId");
+        assert_eq!(
+            validate(&missing, &default_fields()).unwrap_err(),
+            "missing info.gameId"
+        );
+    }
+
+    #[test]
+    fn validate_needs_ten_participants() {
+        let mut short = game(1);
+        short["info"]["participants"].as_array_mut().unwrap().pop();
+        assert_eq!(
+            validate(&short, &default_fields()).unwrap_err(),
+            "expected 10 participants, found 9"
+        );
+    }
+
+    #[test]
+    fn validate_needs_five_a_side() {
+        let mut lopsided = game(1);
+        lopsided["info"]["participants"][5]["teamId"] = json!(100);
+        assert_eq!(
+            validate(&lopsided, &default_fields()).unwrap_err(),
+            "team 100 has 6 participants, expected 5"
+        );
+    }
+
+    #[test]
+    fn validate_needs_a_winner() {
+        let mut no_winner = game(1);
+        let info = no_winner["info"].as_object_mut().unwrap();
+        info.remove("winningTeamId");
+        info["teams"][0]["win"] = json!(false);
+        assert_eq!(
+            validate(&no_winner, &default_fields()).unwrap_err(),
+            "no winner, set winningTeamId or a team's win"
+        );
+
+        let mut wrong_team = game(1);
+        wrong_team["info"]["winningTeamId"] = json!(300);
+        assert_eq!(
+            validate(&wrong_team, &default_fields()).unwrap_err(),
+            "winner 300 is not team 100 or 200"
+        );
+    }
+
+    #[test]
+    fn validate_needs_the_template_fields() {
+        let fields = ["riotIdGameName", "kills", "goldEarned", "championName"].map(String::from);
+        assert_eq!(
+            validate(&game(1), &fields).unwrap_err(),
+            "participant 1 is missing goldEarned"
+        );
+
+        // Left as the template's null placeholder
+        let mut placeholder = game(1);
+        placeholder["info"]["participants"][3]["kills"] = Value::Null;
+        assert_eq!(
+            validate(&placeholder, &default_fields()).unwrap_err(),
+            "participant 4 is missing kills"
+        );
+    }
+
+    fn naming(patterns: &[&str]) -> FileNaming {
+        let teams = HashMap::from([
+            ("LT".to_string(), "Lightning Tigers".to_string()),
+            ("el".to_string(), "Eagle Legion".to_string()),
+        ]);
+        FileNaming::new(patterns.iter().map(|p| p.to_string()).collect(), &teams)
+    }
+
+    #[test]
+    fn tokenize_splits_placeholders_from_literals() {
+        assert_eq!(
+            tokenize("{team1}_vs_{team2}-g{game}"),
+            [
+                Token::Team1,
+                Token::Literal("_vs_".into()),
+                Token::Team2,
+                Token::Literal("-g".into()),
+                Token::Game,
+            ]
+        );
+        assert_eq!(tokenize("{team3}"), [Token::Literal("{team3}".into())]);
+        assert!(tokenize("").is_empty());
+    }
+
+    #[test]
+    fn match_tokens_takes_the_shortest_capture_that_fits() {
+        let mut captures = HashMap::new();
+        assert!(match_tokens(
+            &tokenize("{team1}_{team2}_{game}"),
+            "lt_el_b_2",
+            &mut captures
+        ));
+        assert_eq!(captures[&Token::Team1], "lt");
+        assert_eq!(captures[&Token::Team2], "el_b");
+        assert_eq!(captures[&Token::Game], "2");
+    }
+
+    #[test]
+    fn match_tokens_needs_digits_for_the_game_and_the_whole_text() {
+        let tokens = tokenize("{team1}_{team2}_{game}");
+        assert!(!match_tokens(&tokens, "lt_el_two", &mut HashMap::new()));
+        assert!(!match_tokens(&tokens, "lt_el_", &mut HashMap::new()));
+        assert!(!match_tokens(
+            &tokenize("g{game}"),
+            "g1x",
+            &mut HashMap::new()
+        ));
+    }
+
+    #[test]
+    fn assignment_looks_teams_up_ignoring_case() {
+        let assignment = naming(&["{team1}_{team2}_{game}"])
+            .assignment("lt_EL_3")
+            .unwrap();
+        assert_eq!(
+            assignment,
+            Assignment {
+                team_1_abbr: "lt".into(),
+                team_2_abbr: "EL".into(),
+                team_1: Some("Lightning Tigers".into()),
+                team_2: Some("Eagle Legion".into()),
+                game_number: Some(3),
+            }
+        );
+    }
+
+    #[test]
+    fn assignment_uses_the_first_pattern_that_fits() {
+        let naming = naming(&["{team1}_{team2}_{game}", "{team1}-{team2}"]);
+        let assignment = naming.assignment("lt-xx").unwrap();
+        assert_eq!(assignment.team_1.as_deref(), Some("Lightning Tigers"));
+        assert_eq!(assignment.team_2, None);
+        assert_eq!(assignment.game_number, None);
+        assert_eq!(naming.assignment("lt el"), None);
+    }
+
+    #[test]
+    fn assignment_needs_both_teams() {
+        assert_eq!(naming(&["{team1}_{game}"]).assignment("lt_1"), None);
+    }
+}
@@ -6,62 +6,61 @@ use rusqlite::Connection;
 use std::fs;
 use std::path::PathBuf;
 use std::time::Duration;
-use std::{
-    error::Error,
-    io::{self, Write},
-};
+use std::{error::Error, io};
 
 mod app;
 mod command;
+mod export;
+mod import;
 mod riot;
 mod sql;
 mod ui;
 mod update;
-use app::{App, CurrentScreen};
-use command::APP_NAME;
-use sql::repo;
+use app::{App, CurrentScreen, StatsTab};
+use clap::Parser;
+use command::{APP_NAME, Cli, Command};
+use sql::{migrations, repo};
 use ui::view;
 
 use crate::app::{AlertType, Config, Message, config_dir};
 use crate::ui::GameList;
 
-fn old_main() {
-    let mut state = command::AppState::new();
-
-    loop {
-        print!("> ");
-        io::stdout().flush().unwrap();
-
-        let mut input = String::new();
-        if io::stdin().read_line(&mut input).is_err() {
-            continue;
-        }
-
-        let input = input.trim();
-
-        if input == "quit" || input == "exit" {
-            println!("Goodbye!");
-            break;
-        }
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    init_config()?;
+    let config = read_config()?;
 
-        // Dispatch commands
-        let _ = command::handle_command(input, &mut state);
+    match cli.command {
+        None | Some(Command::Tui) => run_tui(config),
+        Some(command) => Ok(command::run(command, &config)?),
     }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    init_config()?;
+fn run_tui(config: Config) -> Result<(), Box<dyn Error>> {
     tui::install_panic_hook();
     let mut terminal = tui::init_terminal()?;
 
     let mut app = App::new();
-    app.config = read_config()?;
+    app.set_config(config);
     app.db_connection = Some(init_database()?);
     // app.game_count = repo::game_count(app.db_connection.as_ref().unwrap()).unwrap_or(0);
-    app.test_game = Some(repo::game_by_id(app.db_connection.as_ref().unwrap(), 1)?);
-    match repo::all_games(app.db_connection.as_ref().unwrap()) {
-        Ok(games) => {
-            app.db_games = GameList::from_iter(games);
+    match migrations::migrate(app.db_connection.as_mut().unwrap()) {
+        Ok(_) => {
+            app.test_game = repo::game_by_id(app.db_connection.as_ref().unwrap(), 1).ok();
+            match (
+                repo::all_games(app.db_connection.as_ref().unwrap(), &app.game_filter),
+                repo::all_series(app.db_connection.as_ref().unwrap()),
+            ) {
+                (Ok((games, unreadable)), Ok(series)) => {
+                    app.db_games = GameList::with_series(games, series);
+                    update::alert_unreadable(&mut app, &unreadable);
+                }
+                (Err(e), _) | (_, Err(e)) => {
+                    app.alert_type = AlertType::Error;
+                    app.alert_message = format!("{e}");
+                    app.show_alert = true;
+                }
+            }
         }
         Err(e) => {
             app.alert_type = AlertType::Error;
@@ -98,6 +97,16 @@ fn init_config() -> io::Result<PathBuf> {
         fs::write(
             &config_path,
             r#"api_key = ""
+key_type = "development"
+# Where stats exports are written, defaults to the exports folder next to this file
+# export_dir = ""
+# Manual file names, e.g. lt_el_1.json. {team1}, {team2} and {game} are read
+# from the name, everything else has to match exactly.
+file_patterns = ["{team1}_{team2}_{game}"]
+
+# Full team names for the abbreviations in manual file names
+[teams]
+# lt = "Lightning Tigers"
 "#,
         )?;
     }
@@ -171,6 +180,14 @@ fn handle_key(app: &App, key: event::KeyEvent) -> Option<Message> {
             }
         }
     }
+    // The match browser filter takes every key while it is being typed
+    if app.filtering {
+        return match key.code {
+            KeyCode::Esc => Some(Message::ClearFilter),
+            KeyCode::Enter => Some(Message::CloseFilter),
+            _ => Some(Message::FilterKey(Event::Key(key))),
+        };
+    }
     // Global key strokes
     match key.code {
         KeyCode::Char('q') => {
@@ -191,18 +208,59 @@ fn handle_key(app: &App, key: event::KeyEvent) -> Option<Message> {
             KeyCode::Char('i') => Some(Message::OpenImportManual),
             KeyCode::Char('f') => Some(Message::OpenSearch),
             KeyCode::Char('s') => Some(Message::OpenStats),
+            KeyCode::Char('r') => Some(Message::OpenRoster),
+            KeyCode::Char('e') => Some(Message::OpenEditGame),
+            KeyCode::Char('/') => Some(Message::OpenFilter),
+            KeyCode::Enter => Some(Message::OpenGameDetail),
+            KeyCode::Esc => Some(Message::ClearFilter),
+            _ => None,
+        },
+        CurrentScreen::GameDetail => match key.code {
+            KeyCode::Esc => Some(Message::OpenMain),
+            KeyCode::Char('d') => Some(Message::PromptDraft),
+            KeyCode::Char('[') => Some(Message::PrevTab),
+            KeyCode::Char(']') => Some(Message::NextTab),
+            _ => None,
+        },
+        CurrentScreen::EditGame => match key.code {
+            KeyCode::Esc => Some(Message::OpenMain),
+            KeyCode::Enter => Some(Message::EditGameField),
+            KeyCode::Char('s') => Some(Message::SwapGameSides),
+            KeyCode::Char('w') => Some(Message::SaveGame),
             _ => None,
         },
         CurrentScreen::ImportManual => match key.code {
             KeyCode::Esc => Some(Message::OpenMain),
+            KeyCode::Char(' ') => Some(Message::ToggleImportFile),
+            KeyCode::Char('a') => Some(Message::ToggleAllImportFiles),
+            KeyCode::Enter => Some(Message::ApplyImportManual),
             _ => None,
         },
         CurrentScreen::Stats => match key.code {
             KeyCode::Esc => Some(Message::OpenMain),
             KeyCode::Char('[') => Some(Message::PrevTab),
             KeyCode::Char(']') => Some(Message::NextTab),
+            KeyCode::Char('e') => Some(Message::ExportStats),
+            KeyCode::Char('v') => Some(Message::PromptHeadToHead(0)),
+            KeyCode::Char('c') if app.stats_tab == StatsTab::Player => {
+                Some(Message::PickVersusPlayer)
+            }
+            KeyCode::Char('y') if app.stats_tab == StatsTab::Player => Some(Message::OpenSynergy),
             _ => Some(Message::InputKey(Event::Key(key))),
         },
+        CurrentScreen::HeadToHead | CurrentScreen::PlayerVersus | CurrentScreen::Synergy => {
+            match key.code {
+                KeyCode::Esc => Some(Message::ReturnToStats),
+                _ => None,
+            }
+        }
+        CurrentScreen::Roster => match key.code {
+            KeyCode::Esc => Some(Message::OpenMain),
+            KeyCode::Char('n') => Some(Message::PromptRosterField(0)),
+            KeyCode::Delete => Some(Message::RemoveRosterEntry),
+            KeyCode::Char('a') => Some(Message::ApplyRoster),
+            _ => None,
+        },
         CurrentScreen::Search => match key.code {
             KeyCode::Enter => Some(Message::AddSearchGame),
             KeyCode::Esc => Some(Message::OpenMain),
@@ -0,0 +1,556 @@
+// riot/mod.rs
+pub mod model;
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use reqwest::{
+    StatusCode,
+    blocking::{Client, Response},
+    header::RETRY_AFTER,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Which kind of API key is in use. Riot gives development and production
+/// keys different app-wide limits.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyType {
+    #[default]
+    Development,
+    Production,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+    pub const fn new(requests: u32, per: Duration) -> Self {
+        Self { requests, per }
+    }
+}
+
+impl KeyType {
+    /// App-wide limits, applied per region.
+    pub fn app_limits(self) -> Vec<RateLimit> {
+        match self {
+            KeyType::Development => vec![
+                RateLimit::new(20, Duration::from_secs(1)),
+                RateLimit::new(100, Duration::from_secs(120)),
+            ],
+            KeyType::Production => vec![
+                RateLimit::new(500, Duration::from_secs(10)),
+                RateLimit::new(30_000, Duration::from_secs(600)),
+            ],
+        }
+    }
+}
+
+/// The API methods we call. Each has its own limit on top of the app-wide ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Route {
+    AccountByRiotId,
+    MatchById,
+    MatchIdsByPuuid,
+    MatchTimeline,
+}
+
+impl Route {
+    pub fn limits(self) -> Vec<RateLimit> {
+        match self {
+            Route::AccountByRiotId => vec![RateLimit::new(1000, Duration::from_secs(60))],
+            Route::MatchById => vec![RateLimit::new(2000, Duration::from_secs(10))],
+            Route::MatchIdsByPuuid => vec![RateLimit::new(2000, Duration::from_secs(10))],
+            Route::MatchTimeline => vec![RateLimit::new(2000, Duration::from_secs(10))],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RiotError {
+    /// The request never got a response.
+    Transport(reqwest::Error),
+    /// The response body wasn't the JSON we expected.
+    Decode(reqwest::Error),
+    NotFound,
+    /// 401/403, almost always a missing or expired API key.
+    Unauthorized(StatusCode),
+    /// Still rate limited after every retry.
+    RateLimited {
+        retry_after: Option<Duration>,
+    },
+    /// Still failing with a 5xx after every retry.
+    Server(StatusCode),
+    Status(StatusCode),
+}
+
+impl fmt::Display for RiotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiotError::Transport(e) => write!(f, "request failed: {e}"),
+            RiotError::Decode(e) => write!(f, "could not read response: {e}"),
+            RiotError::NotFound => write!(f, "not found"),
+            RiotError::Unauthorized(status) => {
+                write!(f, "{status}: check the api_key in config.toml")
+            }
+            RiotError::RateLimited {
+                retry_after: Some(d),
+            } => write!(f, "rate limited, retry in {}s", d.as_secs()),
+            RiotError::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            RiotError::Server(status) => write!(f, "Riot API error: {status}"),
+            RiotError::Status(status) => write!(f, "unexpected response: {status}"),
+        }
+    }
+}
+
+impl std::error::Error for RiotError {}
+
+/// Riot counts requests in fixed windows that open with the first request,
+/// e.g. 100 per 120s, so each limit is tracked the same way. A bucket that
+/// starts full and refills as it goes would let through twice the limit in
+/// the first window.
+struct Window {
+    limit: RateLimit,
+    sent: u32,
+    started: Option<Instant>,
+}
+
+impl Window {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            sent: 0,
+            started: None,
+        }
+    }
+
+    /// When the current window ends, or `None` if it's already over.
+    fn ends(&self, now: Instant) -> Option<Instant> {
+        self.started
+            .map(|started| started + self.limit.per)
+            .filter(|&end| end > now)
+    }
+
+    /// How long until a request fits in this window or the next.
+    fn wait_time(&self, now: Instant) -> Duration {
+        match self.ends(now) {
+            Some(end) if self.sent >= self.limit.requests => end - now,
+            _ => Duration::ZERO,
+        }
+    }
+
+    fn take(&mut self, now: Instant) {
+        if self.ends(now).is_none() {
+            self.started = Some(now);
+            self.sent = 0;
+        }
+        self.sent += 1;
+    }
+}
+
+#[derive(Default)]
+struct Buckets {
+    app: HashMap<String, Vec<Window>>,
+    route: HashMap<(String, Route), Vec<Window>>,
+}
+
+/// A Riot API client shared across the app. Requests wait for both the
+/// app-wide and per-route rate limit windows of their region, 429s are retried
+/// after `Retry-After` and 5xx responses are retried with backoff.
+pub struct RiotClient {
+    http: Client,
+    api_key: String,
+    base_url: Option<String>,
+    app_limits: Vec<RateLimit>,
+    route_limits: HashMap<Route, Vec<RateLimit>>,
+    max_retries: u32,
+    backoff: Duration,
+    buckets: Mutex<Buckets>,
+}
+
+impl RiotClient {
+    pub fn new(api_key: &str, key_type: KeyType) -> Self {
+        Self {
+            http: Client::new(),
+            api_key: api_key.to_string(),
+            base_url: None,
+            app_limits: key_type.app_limits(),
+            route_limits: [
+                Route::AccountByRiotId,
+                Route::MatchById,
+                Route::MatchIdsByPuuid,
+                Route::MatchTimeline,
+            ]
+            .into_iter()
+            .map(|r| (r, r.limits()))
+            .collect(),
+            max_retries: 3,
+            backoff: Duration::from_millis(500),
+            buckets: Mutex::new(Buckets::default()),
+        }
+    }
+
+    #[cfg(test)]
+    /// Send every request to `url` instead of the regional Riot hosts.
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = Some(url.trim_end_matches('/').to_string());
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_app_limits(mut self, limits: Vec<RateLimit>) -> Self {
+        self.app_limits = limits;
+        self
+    }
+
+    #[cfg(test)]
+    pub fn with_route_limits(mut self, route: Route, limits: Vec<RateLimit>) -> Self {
+        self.route_limits.insert(route, limits);
+        self
+    }
+
+    #[cfg(test)]
+    /// Retry 429s and 5xx responses up to `max_retries` times. Server errors
+    /// wait `backoff`, doubling on each attempt.
+    pub fn with_retries(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn fetch_match(&self, region: &str, match_id: &str) -> Result<Value, RiotError> {
+        self.get(
+            region,
+            Route::MatchById,
+            &format!("/lol/match/v5/matches/{match_id}"),
+            &[],
+        )
+    }
+
+    /// Per-minute snapshots of every player, plus the game's events.
+    pub fn fetch_timeline(&self, region: &str, match_id: &str) -> Result<Value, RiotError> {
+        self.get(
+            region,
+            Route::MatchTimeline,
+            &format!("/lol/match/v5/matches/{match_id}/timeline"),
+            &[],
+        )
+    }
+
+    pub fn fetch_account(
+        &self,
+        region: &str,
+        game_name: &str,
+        tag_line: &str,
+    ) -> Result<Value, RiotError> {
+        self.get(
+            region,
+            Route::AccountByRiotId,
+            &format!("/riot/account/v1/accounts/by-riot-id/{game_name}/{tag_line}"),
+            &[],
+        )
+    }
+
+    pub fn fetch_match_ids(&self, region: &str, puuid: &str) -> Result<Value, RiotError> {
+        self.get(
+            region,
+            Route::MatchIdsByPuuid,
+            &format!("/lol/match/v5/matches/by-puuid/{puuid}/ids"),
+            &[("type", "tourney"), ("count", "14")],
+        )
+    }
+
+    fn url(&self, region: &str, path: &str) -> String {
+        match &self.base_url {
+            Some(base) => format!("{base}{path}"),
+            None => format!("https://{region}.api.riotgames.com{path}"),
+        }
+    }
+
+    fn get(
+        &self,
+        region: &str,
+        route: Route,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Value, RiotError> {
+        let url = self.url(region, path);
+        let mut attempt = 0;
+
+        loop {
+            self.acquire(region, route);
+            let res = self
+                .http
+                .get(&url)
+                .query(query)
+                .header("X-Riot-Token", &self.api_key)
+                .send()
+                .map_err(RiotError::Transport)?;
+
+            let status = res.status();
+            if status.is_success() {
+                return res.json().map_err(RiotError::Decode);
+            }
+
+            let retry_after = retry_after(&res);
+            let retries_left = attempt < self.max_retries;
+            match status {
+                StatusCode::TOO_MANY_REQUESTS if retries_left => {
+                    thread::sleep(retry_after.unwrap_or(Duration::from_secs(1)));
+                }
+                StatusCode::TOO_MANY_REQUESTS => {
+                    return Err(RiotError::RateLimited { retry_after });
+                }
+                s if s.is_server_error() && retries_left => {
+                    thread::sleep(self.backoff * 2u32.pow(attempt));
+                }
+                s if s.is_server_error() => return Err(RiotError::Server(s)),
+                StatusCode::NOT_FOUND => return Err(RiotError::NotFound),
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    return Err(RiotError::Unauthorized(status));
+                }
+                s => return Err(RiotError::Status(s)),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Block until the region's app windows and the route's windows all have
+    /// room for a request, then count it in each.
+    fn acquire(&self, region: &str, route: Route) {
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().unwrap();
+                let Buckets { app, route: routes } = &mut *buckets;
+                let app = app
+                    .entry(region.to_string())
+                    .or_insert_with(|| self.app_limits.iter().copied().map(Window::new).collect());
+                let routes = routes
+                    .entry((region.to_string(), route))
+                    .or_insert_with(|| {
+                        self.route_limits
+                            .get(&route)
+                            .into_iter()
+                            .flatten()
+                            .copied()
+                            .map(Window::new)
+                            .collect()
+                    });
+
+                let now = Instant::now();
+                let wait = app
+                    .iter()
+                    .chain(routes.iter())
+                    .map(|w| w.wait_time(now))
+                    .max()
+                    .unwrap_or(Duration::ZERO);
+
+                if wait.is_zero() {
+                    app.iter_mut()
+                        .chain(routes.iter_mut())
+                        .for_each(|w| w.take(now));
+                    return;
+                }
+                wait
+            };
+            thread::sleep(wait);
+        }
+    }
+}
+
+fn retry_after(res: &Response) -> Option<Duration> {
+    res.headers()
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    fn client(server: &Server) -> RiotClient {
+        RiotClient::new("test-key", KeyType::Development)
+            .with_base_url(&server.url())
+            .with_retries(2, Duration::from_millis(10))
+    }
+
+    #[test]
+    fn fetch_match_sends_key_and_parses_json() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/lol/match/v5/matches/OC1_1")
+            .match_header("X-Riot-Token", "test-key")
+            .with_body(r#"{"info": {"gameId": 1}}"#)
+            .create();
+
+        let game = client(&server).fetch_match("sea", "OC1_1").unwrap();
+
+        mock.assert();
+        assert_eq!(game["info"]["gameId"], 1);
+    }
+
+    #[test]
+    fn fetch_timeline_uses_the_timeline_route() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/lol/match/v5/matches/OC1_1/timeline")
+            .with_body(r#"{"info": {"frames": []}}"#)
+            .create();
+
+        let timeline = client(&server).fetch_timeline("sea", "OC1_1").unwrap();
+
+        mock.assert();
+        assert!(timeline["info"]["frames"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn retries_after_rate_limit() {
+        let mut server = Server::new();
+        let limited = server
+            .mock("GET", "/lol/match/v5/matches/OC1_1")
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(1)
+            .create();
+        let ok = server
+            .mock("GET", "/lol/match/v5/matches/OC1_1")
+            .with_body("{}")
+            .expect(1)
+            .create();
+
+        assert!(client(&server).fetch_match("sea", "OC1_1").is_ok());
+        limited.assert();
+        ok.assert();
+    }
+
+    #[test]
+    fn gives_up_on_persistent_server_errors() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/lol/match/v5/matches/OC1_1")
+            .with_status(503)
+            .expect(3)
+            .create();
+
+        let err = client(&server).fetch_match("sea", "OC1_1").unwrap_err();
+
+        mock.assert();
+        assert!(matches!(
+            err,
+            RiotError::Server(StatusCode::SERVICE_UNAVAILABLE)
+        ));
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/riot/account/v1/accounts/by-riot-id/nobody/OCE")
+            .with_status(404)
+            .expect(1)
+            .create();
+
+        let err = client(&server)
+            .fetch_account("asia", "nobody", "OCE")
+            .unwrap_err();
+
+        mock.assert();
+        assert!(matches!(err, RiotError::NotFound));
+    }
+
+    #[test]
+    fn throttles_to_the_app_limit() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/lol/match/v5/matches/OC1_1")
+            .with_body("{}")
+            .expect(3)
+            .create();
+        let client =
+            client(&server).with_app_limits(vec![RateLimit::new(1, Duration::from_millis(200))]);
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            client.fetch_match("sea", "OC1_1").unwrap();
+        }
+
+        // One request a window, so the third waits for the third window
+        assert!(start.elapsed() >= Duration::from_millis(400));
+    }
+
+    #[test]
+    fn throttles_to_the_route_limit() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/lol/match/v5/matches/OC1_1")
+            .with_body("{}")
+            .expect(2)
+            .create();
+        let client = client(&server).with_route_limits(
+            Route::MatchById,
+            vec![RateLimit::new(1, Duration::from_millis(200))],
+        );
+
+        let start = Instant::now();
+        client.fetch_match("sea", "OC1_1").unwrap();
+        client.fetch_match("sea", "OC1_1").unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn holds_a_burst_to_the_limit_in_the_first_window() {
+        let mut server = Server::new();
+        server
+            .mock("GET", "/lol/match/v5/matches/OC1_1")
+            .with_body("{}")
+            .expect(3)
+            .create();
+        let client =
+            client(&server).with_app_limits(vec![RateLimit::new(2, Duration::from_millis(300))]);
+
+        let start = Instant::now();
+        let sent: Vec<Duration> = (0..3)
+            .map(|_| {
+                client.fetch_match("sea", "OC1_1").unwrap();
+                start.elapsed()
+            })
+            .collect();
+
+        assert!(sent[1] < Duration::from_millis(300));
+        assert!(sent[2] >= Duration::from_millis(300));
+    }
+
+    #[test]
+    fn window_opens_with_the_first_request() {
+        let mut window = Window::new(RateLimit::new(2, Duration::from_secs(120)));
+        let start = Instant::now();
+        assert_eq!(window.wait_time(start), Duration::ZERO);
+
+        window.take(start);
+        window.take(start + Duration::from_secs(1));
+        assert_eq!(
+            window.wait_time(start + Duration::from_secs(20)),
+            Duration::from_secs(100)
+        );
+
+        // The next window starts over from its own first request
+        let next = start + Duration::from_secs(120);
+        assert_eq!(window.wait_time(next), Duration::ZERO);
+        window.take(next);
+        window.take(next);
+        assert_eq!(window.wait_time(next), Duration::from_secs(120));
+    }
+}
@@ -0,0 +1,361 @@
+//! Match-V5 payloads.
+//!
+//! Only the fields we use are modelled, serde skips the rest. Manually entered
+//! games in `manual_data/` only fill in a handful of these, so anything they
+//! can leave out is either an `Option` or defaults to zero.
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchDto {
+    #[serde(default)]
+    pub metadata: Option<MetadataDto>,
+    pub info: InfoDto,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataDto {
+    pub match_id: String,
+    #[serde(default)]
+    pub participants: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InfoDto {
+    pub game_id: u64,
+    #[serde(default)]
+    pub game_duration: u64,
+    pub game_creation: Option<i64>,
+    pub game_start_timestamp: Option<i64>,
+    pub game_end_timestamp: Option<i64>,
+    /// `YYYY-MM-DD`, only set on manual games which have no timestamps.
+    pub game_end_date: Option<String>,
+    pub game_mode: Option<String>,
+    pub game_version: Option<String>,
+    /// Only set on manual games, API games mark the winner on each team.
+    pub winning_team_id: Option<u64>,
+    #[serde(default)]
+    pub teams: Vec<TeamDto>,
+    #[serde(default)]
+    pub participants: Vec<ParticipantDto>,
+}
+
+impl InfoDto {
+    /// The local date the game ended, as `YYYY-MM-DD`.
+    pub fn played_on(&self) -> Option<String> {
+        self.game_end_timestamp
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map(|d| {
+                d.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d")
+                    .to_string()
+            })
+            .or_else(|| self.game_end_date.clone())
+    }
+
+    pub fn winner(&self) -> Option<u64> {
+        self.winning_team_id
+            .or_else(|| self.teams.iter().find(|t| t.win).map(|t| t.team_id))
+            .or_else(|| self.participants.iter().find(|p| p.win).map(|p| p.team_id))
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamDto {
+    pub team_id: u64,
+    #[serde(default)]
+    pub win: bool,
+    #[serde(default)]
+    pub bans: Vec<BanDto>,
+    #[serde(default)]
+    pub objectives: ObjectivesDto,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BanDto {
+    pub champion_id: i64,
+    pub pick_turn: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectivesDto {
+    pub atakhan: Option<ObjectiveDto>,
+    pub baron: Option<ObjectiveDto>,
+    pub champion: Option<ObjectiveDto>,
+    pub dragon: Option<ObjectiveDto>,
+    pub horde: Option<ObjectiveDto>,
+    pub inhibitor: Option<ObjectiveDto>,
+    pub rift_herald: Option<ObjectiveDto>,
+    pub tower: Option<ObjectiveDto>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectiveDto {
+    /// Missing on manual games, which don't record who got there first.
+    pub first: Option<bool>,
+    #[serde(default)]
+    pub kills: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParticipantDto {
+    /// Empty on manual games.
+    #[serde(default)]
+    pub puuid: String,
+    pub riot_id_game_name: Option<String>,
+    #[serde(default, alias = "riotIdTagLine", deserialize_with = "lenient_string")]
+    pub riot_id_tagline: Option<String>,
+    #[serde(default)]
+    pub champion_name: String,
+    pub champion_id: Option<i64>,
+    #[serde(default)]
+    pub team_id: u64,
+    #[serde(default)]
+    pub team_position: String,
+    #[serde(default)]
+    pub win: bool,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub kills: u64,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub deaths: u64,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub assists: u64,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub total_minions_killed: u64,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub neutral_minions_killed: u64,
+    pub total_ally_jungle_minions_killed: Option<u64>,
+    pub total_enemy_jungle_minions_killed: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub gold_earned: u64,
+    pub total_damage_dealt_to_champions: Option<u64>,
+    pub vision_score: Option<u64>,
+    pub first_blood_kill: Option<bool>,
+    pub first_blood_assist: Option<bool>,
+    pub first_tower_kill: Option<bool>,
+    pub first_tower_assist: Option<bool>,
+    #[serde(alias = "wardPlaced")]
+    pub wards_placed: Option<u64>,
+    #[serde(alias = "wardKilled")]
+    pub wards_killed: Option<u64>,
+    #[serde(alias = "wardPlacedDetector")]
+    pub detector_wards_placed: Option<u64>,
+    #[serde(alias = "summonerSpell1")]
+    pub summoner1_id: Option<u64>,
+    #[serde(alias = "summonerSpell2")]
+    pub summoner2_id: Option<u64>,
+    pub item0: Option<u64>,
+    pub item1: Option<u64>,
+    pub item2: Option<u64>,
+    pub item3: Option<u64>,
+    pub item4: Option<u64>,
+    pub item5: Option<u64>,
+    /// The trinket slot.
+    pub item6: Option<u64>,
+    pub challenges: Option<ChallengesDto>,
+}
+
+impl ParticipantDto {
+    pub fn name(&self) -> &str {
+        self.riot_id_game_name.as_deref().unwrap_or("Unknown")
+    }
+
+    pub fn cs(&self) -> u64 {
+        self.total_minions_killed + self.neutral_minions_killed
+    }
+
+    /// Lane and jungle CS at 10 minutes, only on API games.
+    pub fn cs_at_10(&self) -> Option<u64> {
+        let c = self.challenges.as_ref()?;
+        match (c.lane_minions_first10_minutes, c.jungle_cs_before10_minutes) {
+            (None, None) => None,
+            (lane, jungle) => Some(lane.unwrap_or(0) + jungle.unwrap_or(0.0).round() as u64),
+        }
+    }
+
+    /// Item IDs in slot order, leaving out empty slots and the trinket.
+    pub fn items(&self) -> Vec<u64> {
+        [
+            self.item0, self.item1, self.item2, self.item3, self.item4, self.item5,
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|&id| id != 0)
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengesDto {
+    pub solo_kills: Option<u64>,
+    pub lane_minions_first10_minutes: Option<u64>,
+    pub jungle_cs_before10_minutes: Option<f64>,
+    pub kill_participation: Option<f64>,
+    pub team_damage_percentage: Option<f64>,
+    pub damage_per_minute: Option<f64>,
+    pub gold_per_minute: Option<f64>,
+    pub vision_score_per_minute: Option<f64>,
+}
+
+/// Match-V5 timeline payloads, from `/lol/match/v5/matches/{id}/timeline`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineDto {
+    pub info: TimelineInfoDto,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineInfoDto {
+    /// Milliseconds between frames, one minute in practice.
+    #[serde(default)]
+    pub frame_interval: u64,
+    #[serde(default)]
+    pub frames: Vec<FrameDto>,
+}
+
+/// A snapshot of the game, roughly on each minute. The last frame is taken
+/// when the game ends.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameDto {
+    pub timestamp: u64,
+    /// Keyed by participant ID, `"1"` to `"10"`, which is one more than the
+    /// player's position in `InfoDto::participants`.
+    #[serde(default)]
+    pub participant_frames: HashMap<String, ParticipantFrameDto>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParticipantFrameDto {
+    pub participant_id: u64,
+    #[serde(default)]
+    pub total_gold: u64,
+    #[serde(default)]
+    pub xp: u64,
+    #[serde(default)]
+    pub level: u64,
+    #[serde(default)]
+    pub minions_killed: u64,
+    #[serde(default)]
+    pub jungle_minions_killed: u64,
+}
+
+impl ParticipantFrameDto {
+    pub fn cs(&self) -> u64 {
+        self.minions_killed + self.jungle_minions_killed
+    }
+}
+
+impl TimelineDto {
+    pub fn from_value(value: &Value) -> serde_json::Result<Self> {
+        Self::deserialize(value)
+    }
+}
+
+impl MatchDto {
+    pub fn from_value(value: &Value) -> serde_json::Result<Self> {
+        Self::deserialize(value)
+    }
+}
+
+/// Manual games sometimes have numbers typed in as strings, e.g.
+/// `"totalMinionsKilled": "231"`.
+fn lenient_u64<'de, D: Deserializer<'de>>(de: D) -> Result<u64, D::Error> {
+    match Value::deserialize(de)? {
+        Value::Number(n) => n
+            .as_u64()
+            .ok_or_else(|| serde::de::Error::custom(format!("expected a count, got {n}"))),
+        Value::String(s) => s.trim().parse().map_err(serde::de::Error::custom),
+        Value::Null => Ok(0),
+        other => Err(serde::de::Error::custom(format!(
+            "expected a count, got {other}"
+        ))),
+    }
+}
+
+/// Tag lines that look like numbers come back as numbers in some payloads.
+fn lenient_string<'de, D: Deserializer<'de>>(de: D) -> Result<Option<String>, D::Error> {
+    match Value::deserialize(de)? {
+        Value::String(s) => Ok(Some(s)),
+        Value::Number(n) => Ok(Some(n.to_string())),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn template_participants_deserialize() {
+        let template: Value =
+            serde_json::from_str(include_str!("../../manual_data/template.json")).unwrap();
+        let participants =
+            Vec::<ParticipantDto>::deserialize(&template["info"]["participants"]).unwrap();
+        assert_eq!(participants.len(), 10);
+        assert_eq!(participants[0].name(), "ArcadeRetro");
+        assert_eq!(
+            (
+                participants[0].kills,
+                participants[0].deaths,
+                participants[0].assists
+            ),
+            (4, 5, 2)
+        );
+    }
+
+    #[test]
+    fn manual_games_deserialize() {
+        let value: Value =
+            serde_json::from_str(include_str!("../../manual_data/lt_el_1.json")).unwrap();
+        let game = MatchDto::from_value(&value).unwrap();
+        assert_eq!(game.info.game_end_date.as_deref(), Some("2025-11-26"));
+        assert_eq!(game.info.winner(), Some(100));
+        // Typed in as "231"
+        assert_eq!(game.info.participants[0].total_minions_killed, 231);
+    }
+
+    #[test]
+    fn counts_and_tag_lines_are_read_leniently() {
+        let participant = ParticipantDto::deserialize(json!({
+            "riotIdGameName": "Player",
+            "riotIdTagline": 1234,
+            "kills": "7",
+            "deaths": " 3 ",
+            "assists": null,
+            "goldEarned": 12000,
+        }))
+        .unwrap();
+        assert_eq!(participant.riot_id_tagline.as_deref(), Some("1234"));
+        assert_eq!(
+            (participant.kills, participant.deaths, participant.assists),
+            (7, 3, 0)
+        );
+        assert_eq!(participant.gold_earned, 12000);
+
+        let participant = ParticipantDto::deserialize(json!({"riotIdTagLine": "OCE"})).unwrap();
+        assert_eq!(participant.riot_id_tagline.as_deref(), Some("OCE"));
+    }
+
+    #[test]
+    fn counts_that_are_not_numbers_are_errors() {
+        assert!(ParticipantDto::deserialize(json!({"kills": "seven"})).is_err());
+        assert!(ParticipantDto::deserialize(json!({"kills": -1})).is_err());
+        assert!(ParticipantDto::deserialize(json!({"kills": [1]})).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use std::fmt;
+
+use crate::riot::model::InfoDto;
+use crate::sql::schema::DraftEntry;
+
+use DraftAction::{Ban, Pick};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DraftAction {
+    Ban,
+    Pick,
+}
+
+impl DraftAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ban => "ban",
+            Self::Pick => "pick",
+        }
+    }
+}
+
+impl fmt::Display for DraftAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Who bans or picks at each step of a tournament draft, blue side being
+/// team `100`.
+pub const TOURNAMENT_ORDER: [(u64, DraftAction); 20] = [
+    (100, Ban),
+    (200, Ban),
+    (100, Ban),
+    (200, Ban),
+    (100, Ban),
+    (200, Ban),
+    (100, Pick),
+    (200, Pick),
+    (200, Pick),
+    (100, Pick),
+    (100, Pick),
+    (200, Pick),
+    (200, Ban),
+    (100, Ban),
+    (200, Ban),
+    (100, Ban),
+    (200, Pick),
+    (100, Pick),
+    (100, Pick),
+    (200, Pick),
+];
+
+/// Build a game's draft from its 20 champions in tournament order, e.g.
+/// `Azir, Orianna, -, ...` where `-` is a team not banning.
+///
+/// Picks have to be champions that side played, and take the spelling from
+/// the game. Bans have to be one of the `known` champions and take its
+/// spelling, so a ban typed `kaisa` counts with the game's `Kaisa`.
+pub fn parse(input: &str, info: &InfoDto, known: &[String]) -> Result<Vec<DraftEntry>, String> {
+    let champions: Vec<&str> = input.split(',').map(str::trim).collect();
+    if champions.len() != TOURNAMENT_ORDER.len() {
+        return Err(format!(
+            "a draft is {} champions separated by commas, got {}",
+            TOURNAMENT_ORDER.len(),
+            champions.len()
+        ));
+    }
+
+    let mut draft = Vec::new();
+    for (i, (&(team_id, action), champion)) in TOURNAMENT_ORDER.iter().zip(champions).enumerate() {
+        let champion = match (action, champion) {
+            (Ban, "" | "-") => None,
+            (Pick, "" | "-") => return Err(format!("pick {} is missing", i + 1)),
+            (Ban, name) => Some(
+                known
+                    .iter()
+                    .find(|c| same_champion(c, name))
+                    .cloned()
+                    .ok_or_else(|| format!("{name} isn't a champion"))?,
+            ),
+            (Pick, name) => Some(
+                info.participants
+                    .iter()
+                    .filter(|p| p.team_id == team_id)
+                    .find(|p| same_champion(&p.champion_name, name))
+                    .map(|p| p.champion_name.clone())
+                    .ok_or_else(|| format!("{name} wasn't played on {}", side_name(team_id)))?,
+            ),
+        };
+        draft.push(DraftEntry {
+            seq: i as u64 + 1,
+            team_id,
+            action,
+            champion,
+            manual: true,
+        });
+    }
+    Ok(draft)
+}
+
+pub fn side_name(team_id: u64) -> &'static str {
+    match team_id {
+        100 => "blue side",
+        200 => "red side",
+        _ => "neither side",
+    }
+}
+
+/// Ignore case and punctuation, so `kaisa` matches `Kai'Sa`.
+pub fn same_champion(a: &str, b: &str) -> bool {
+    let key = |s: &str| {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    key(a) == key(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::riot::model::ParticipantDto;
+
+    /// Blue plays Ahri and four others, red plays Kaisa and four others.
+    fn info() -> InfoDto {
+        let participant = |team_id, champion: &str| ParticipantDto {
+            team_id,
+            champion_name: champion.to_string(),
+            ..Default::default()
+        };
+        InfoDto {
+            participants: vec![
+                participant(100, "Ahri"),
+                participant(100, "LeeSin"),
+                participant(100, "Ornn"),
+                participant(100, "Jinx"),
+                participant(100, "Thresh"),
+                participant(200, "Kaisa"),
+                participant(200, "Viego"),
+                participant(200, "Azir"),
+                participant(200, "Gnar"),
+                participant(200, "Nautilus"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn champions() -> Vec<String> {
+        ["Kaisa", "Orianna", "MonkeyKing", "Rell", "Varus", "Sejuani"]
+            .map(str::to_string)
+            .to_vec()
+    }
+
+    /// Bans, then picks, in tournament order with `picks` swapped in.
+    fn input(bans: [&str; 10], picks: [&str; 10]) -> String {
+        let (mut bans, mut picks) = (bans.into_iter(), picks.into_iter());
+        TOURNAMENT_ORDER
+            .iter()
+            .map(|(_, action)| match action {
+                Ban => bans.next().unwrap(),
+                Pick => picks.next().unwrap(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    const BANS: [&str; 10] = [
+        "kaisa", "-", "Orianna", "", "rell", "Varus", "sejuani", "-", "-", "-",
+    ];
+    const PICKS: [&str; 10] = [
+        "ahri", "azir", "viego", "lee sin", "ornn", "gnar", "kai'sa", "jinx", "thresh", "nautilus",
+    ];
+
+    #[test]
+    fn parse_follows_tournament_order() {
+        let draft = parse(&input(BANS, PICKS), &info(), &champions()).unwrap();
+        assert_eq!(draft.len(), 20);
+        for (entry, (i, &(team_id, action))) in
+            draft.iter().zip(TOURNAMENT_ORDER.iter().enumerate())
+        {
+            assert_eq!(entry.seq, i as u64 + 1);
+            assert_eq!((entry.team_id, entry.action), (team_id, action));
+            assert!(entry.manual);
+        }
+    }
+
+    #[test]
+    fn parse_takes_the_stored_spelling() {
+        let draft = parse(&input(BANS, PICKS), &info(), &champions()).unwrap();
+        let champions: Vec<Option<&str>> = draft.iter().map(|d| d.champion.as_deref()).collect();
+        assert_eq!(
+            &champions[..6],
+            [
+                Some("Kaisa"),
+                None,
+                Some("Orianna"),
+                None,
+                Some("Rell"),
+                Some("Varus")
+            ]
+        );
+        assert_eq!(
+            &champions[6..12],
+            [
+                Some("Ahri"),
+                Some("Azir"),
+                Some("Viego"),
+                Some("LeeSin"),
+                Some("Ornn"),
+                Some("Gnar")
+            ]
+        );
+        assert_eq!(champions[12], Some("Sejuani"));
+        assert_eq!(champions[16], Some("Kaisa"));
+    }
+
+    #[test]
+    fn parse_rejects_the_wrong_number_of_champions() {
+        let err = parse("Ahri, Azir", &info(), &champions()).unwrap_err();
+        assert_eq!(err, "a draft is 20 champions separated by commas, got 2");
+    }
+
+    #[test]
+    fn parse_rejects_missing_picks() {
+        let mut picks = PICKS;
+        // Red side's first pick, the eighth step
+        picks[1] = "-";
+        let err = parse(&input(BANS, picks), &info(), &champions()).unwrap_err();
+        assert_eq!(err, "pick 8 is missing");
+    }
+
+    #[test]
+    fn parse_rejects_picks_the_side_did_not_play() {
+        let mut picks = PICKS;
+        // Red's first pick, but Ahri was on blue side
+        picks[1] = "Ahri";
+        let err = parse(&input(BANS, picks), &info(), &champions()).unwrap_err();
+        assert_eq!(err, "Ahri wasn't played on red side");
+    }
+
+    #[test]
+    fn parse_rejects_bans_that_are_not_champions() {
+        let mut bans = BANS;
+        bans[0] = "Kaisaa";
+        let err = parse(&input(bans, PICKS), &info(), &champions()).unwrap_err();
+        assert_eq!(err, "Kaisaa isn't a champion");
+    }
+
+    #[test]
+    fn same_champion_ignores_case_and_punctuation() {
+        assert!(same_champion("Kai'Sa", "kaisa"));
+        assert!(same_champion("Dr. Mundo", "DrMundo"));
+        assert!(!same_champion("Kaisa", "Kassadin"));
+    }
+}
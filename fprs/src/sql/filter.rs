@@ -0,0 +1,253 @@
+//! The filter typed into the match browser, e.g.
+//! `team:tigers champ:ahri from:2025-01-01 winner:blue manual`.
+//!
+//! Words without a `key:` match a team, player or champion, and a game has
+//! to match every one of them. Text matches are case-insensitive substrings,
+//! and values with spaces can be quoted.
+use std::fmt;
+
+use chrono::NaiveDate;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameFilter {
+    /// Each matched on its own against teams, players and champions.
+    pub words: Vec<String>,
+    pub team: Option<String>,
+    pub player: Option<String>,
+    pub champion: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// `100` for blue, `200` for red.
+    pub winner: Option<u64>,
+    pub manual: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterError(String);
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+impl GameFilter {
+    pub fn parse(query: &str) -> Result<Self, FilterError> {
+        let mut filter = Self::default();
+        for token in split(query) {
+            let Some((key, value)) = token.split_once(':') else {
+                match token.to_lowercase().as_str() {
+                    "manual" => filter.manual = Some(true),
+                    "api" => filter.manual = Some(false),
+                    _ => filter.words.push(token),
+                }
+                continue;
+            };
+            if value.is_empty() {
+                // Still being typed
+                continue;
+            }
+            let value = value.to_string();
+            match key.to_lowercase().as_str() {
+                "team" | "t" => filter.team = Some(value),
+                "player" | "p" => filter.player = Some(value),
+                "champ" | "champion" | "c" => filter.champion = Some(value),
+                "from" => filter.from = Some(date(&value)?),
+                "to" => filter.to = Some(date(&value)?),
+                "winner" | "win" => {
+                    filter.winner = Some(match value.to_lowercase().as_str() {
+                        "blue" | "100" => 100,
+                        "red" | "200" => 200,
+                        _ => {
+                            return Err(FilterError(format!("winner is blue or red, not {value}")));
+                        }
+                    })
+                }
+                "source" => {
+                    filter.manual = Some(match value.to_lowercase().as_str() {
+                        "manual" => true,
+                        "api" => false,
+                        _ => {
+                            return Err(FilterError(format!(
+                                "source is manual or api, not {value}"
+                            )));
+                        }
+                    })
+                }
+                _ => return Err(FilterError(format!("unknown filter {key}:"))),
+            }
+        }
+        Ok(filter)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The parameters of `queries/games.sql`, in order. The words go in as a
+    /// JSON array, and text is escaped so `_` and `%` in a Riot ID match
+    /// themselves rather than anything.
+    pub fn params(&self) -> [Option<String>; 8] {
+        let date = |d: Option<NaiveDate>| d.map(|d| d.to_string());
+        let text = |t: &Option<String>| t.as_deref().map(like_escape);
+        let words: Vec<String> = self.words.iter().map(|w| like_escape(w)).collect();
+        [
+            (!words.is_empty()).then(|| serde_json::json!(words).to_string()),
+            text(&self.team),
+            text(&self.player),
+            text(&self.champion),
+            date(self.from),
+            date(self.to),
+            self.winner.map(|w| w.to_string()),
+            self.manual.map(|m| (m as u8).to_string()),
+        ]
+    }
+}
+
+/// Escape `LIKE`'s wildcards, with `\` as the escape character.
+fn like_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn date(value: &str) -> Result<NaiveDate, FilterError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| FilterError(format!("{value} is not a YYYY-MM-DD date")))
+}
+
+/// Split on whitespace, keeping double-quoted runs together.
+fn split(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+
+    use super::*;
+    use crate::sql::{migrations, repo};
+
+    #[test]
+    fn split_keeps_quoted_runs_together() {
+        assert_eq!(
+            split(r#"team:"Lightning Tigers"  ahri "lee sin""#),
+            ["team:Lightning Tigers", "ahri", "lee sin"]
+        );
+        assert!(split("   ").is_empty());
+    }
+
+    #[test]
+    fn split_runs_an_unclosed_quote_to_the_end() {
+        assert_eq!(split(r#"player:"faker an"#), ["player:faker an"]);
+    }
+
+    #[test]
+    fn parse_keeps_each_word_separate() {
+        let filter = GameFilter::parse(r#"tigers ahri "lee sin""#).unwrap();
+        assert_eq!(filter.words, ["tigers", "ahri", "lee sin"]);
+        assert_eq!(
+            filter.params()[0].as_deref(),
+            Some(r#"["tigers","ahri","lee sin"]"#)
+        );
+    }
+
+    #[test]
+    fn parse_reads_keys() {
+        let filter = GameFilter::parse(
+            r#"t:tigers champ:"Lee Sin" from:2025-01-01 to:2025-02-01 winner:Red manual"#,
+        )
+        .unwrap();
+        assert_eq!(
+            filter,
+            GameFilter {
+                team: Some("tigers".to_string()),
+                champion: Some("Lee Sin".to_string()),
+                from: NaiveDate::from_ymd_opt(2025, 1, 1),
+                to: NaiveDate::from_ymd_opt(2025, 2, 1),
+                winner: Some(200),
+                manual: Some(true),
+                ..Default::default()
+            }
+        );
+        assert_eq!(GameFilter::parse("win:100").unwrap().winner, Some(100));
+        assert_eq!(GameFilter::parse("source:api").unwrap().manual, Some(false));
+    }
+
+    #[test]
+    fn parse_rejects_bad_values() {
+        assert!(GameFilter::parse("winner:purple").is_err());
+        assert!(GameFilter::parse("from:yesterday").is_err());
+        assert!(GameFilter::parse("from:2025-13-01").is_err());
+        assert_eq!(
+            GameFilter::parse("patch:14.1").unwrap_err().to_string(),
+            "unknown filter patch:"
+        );
+    }
+
+    #[test]
+    fn parse_ignores_a_key_still_being_typed() {
+        let filter = GameFilter::parse("ahri from:").unwrap();
+        assert_eq!(filter.words, ["ahri"]);
+        assert_eq!(filter.from, None);
+        assert!(GameFilter::parse("winner:").unwrap().is_empty());
+    }
+
+    #[test]
+    fn params_escape_like_wildcards() {
+        let filter = GameFilter::parse(r"100% team:a_b player:c\d").unwrap();
+        let params = filter.params();
+        assert_eq!(params[0].as_deref(), Some(r#"["100\\%"]"#));
+        assert_eq!(params[1].as_deref(), Some(r"a\_b"));
+        assert_eq!(params[2].as_deref(), Some(r"c\\d"));
+    }
+
+    #[test]
+    fn wildcards_in_the_filter_match_themselves() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrations::migrate(&mut conn).unwrap();
+        for (id, team) in [(1, "Team_One"), (2, "TeamXOne"), (3, "100% Tigers")] {
+            conn.execute(
+                "INSERT INTO game (id, data, team_1, team_2) VALUES (?1, '{}', ?2, 'Eagles')",
+                (id, team),
+            )
+            .unwrap();
+        }
+        let matches = |query: &str| -> Vec<u64> {
+            let (games, unreadable) =
+                repo::all_games(&conn, &GameFilter::parse(query).unwrap()).unwrap();
+            let mut ids: Vec<u64> = games
+                .iter()
+                .map(|g| g.id)
+                .chain(unreadable.iter().map(|g| g.id))
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(matches("team:m_o"), [1]);
+        assert_eq!(matches("team_one"), [1]);
+        assert_eq!(matches("100%"), [3]);
+        assert_eq!(matches("_"), [1]);
+        assert_eq!(matches("team:eagles"), [1, 2, 3]);
+    }
+}
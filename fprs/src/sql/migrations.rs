@@ -0,0 +1,262 @@
+use std::fmt;
+
+use rusqlite::Connection;
+
+pub struct Migration {
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// Every schema change, oldest first. A migration's version is its position
+/// in this list plus one, and is what `PRAGMA user_version` holds once it has
+/// been applied. Only ever append to this list.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "create game table",
+        sql: include_str!("schema.sql"),
+    },
+    Migration {
+        description: "add series",
+        sql: include_str!("migrations/0002_series.sql"),
+    },
+    Migration {
+        description: "add participant and team_side tables",
+        sql: include_str!("migrations/0003_participants.sql"),
+    },
+    Migration {
+        description: "add player and player_alias tables",
+        sql: include_str!("migrations/0004_players.sql"),
+    },
+    Migration {
+        description: "add roster table",
+        sql: include_str!("migrations/0005_rosters.sql"),
+    },
+    Migration {
+        description: "add game patch and notes",
+        sql: include_str!("migrations/0006_game_metadata.sql"),
+    },
+    Migration {
+        description: "add draft table",
+        sql: include_str!("migrations/0007_drafts.sql"),
+    },
+    Migration {
+        description: "add timeline tables",
+        sql: include_str!("migrations/0008_timelines.sql"),
+    },
+    Migration {
+        description: "add rating table",
+        sql: include_str!("migrations/0009_ratings.sql"),
+    },
+    Migration {
+        description: "add champion table",
+        sql: include_str!("migrations/0010_champions.sql"),
+    },
+    Migration {
+        description: "leave unknown series formats unset",
+        sql: include_str!("migrations/0011_series_best_of.sql"),
+    },
+    Migration {
+        description: "clear ratings when a game is renumbered",
+        sql: include_str!("migrations/0012_rating_game_number.sql"),
+    },
+];
+
+#[derive(Debug)]
+pub enum MigrationError {
+    Sqlite(rusqlite::Error),
+    Failed {
+        version: usize,
+        description: &'static str,
+        source: rusqlite::Error,
+    },
+    TooNew {
+        found: usize,
+        supported: usize,
+    },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::Sqlite(e) => write!(f, "failed to read database version: {e}"),
+            MigrationError::Failed {
+                version,
+                description,
+                source,
+            } => write!(
+                f,
+                "database migration {version} ({description}) failed: {source}"
+            ),
+            MigrationError::TooNew { found, supported } => write!(
+                f,
+                "database is at schema version {found} but this build only knows up to {supported}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<rusqlite::Error> for MigrationError {
+    fn from(e: rusqlite::Error) -> Self {
+        MigrationError::Sqlite(e)
+    }
+}
+
+/// Bring the database up to the latest schema, applying each outstanding
+/// migration in its own transaction.
+pub fn migrate(conn: &mut Connection) -> Result<(), MigrationError> {
+    let mut version = schema_version(conn)?;
+    if version == 0 {
+        version = legacy_version(conn)?;
+    }
+    if version > MIGRATIONS.len() {
+        return Err(MigrationError::TooNew {
+            found: version,
+            supported: MIGRATIONS.len(),
+        });
+    }
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        apply(conn, i + 1, migration).map_err(|source| MigrationError::Failed {
+            version: i + 1,
+            description: migration.description,
+            source,
+        })?;
+    }
+
+    Ok(())
+}
+
+fn apply(conn: &mut Connection, version: usize, migration: &Migration) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute_batch(migration.sql)?;
+    tx.pragma_update(None, "user_version", version)?;
+    tx.commit()
+}
+
+fn schema_version(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+/// Databases created before migrations existed are at `user_version` 0 but
+/// may already hold part of the schema. Work out which migration they match
+/// so it isn't applied twice.
+fn legacy_version(conn: &Connection) -> rusqlite::Result<usize> {
+    let columns: Vec<String> = conn
+        .prepare("SELECT name FROM pragma_table_info('game')")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    if columns.is_empty() {
+        Ok(0)
+    } else if columns.iter().any(|c| c == "series_id") {
+        Ok(2)
+    } else {
+        Ok(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables(conn: &Connection) -> Vec<String> {
+        conn.prepare("SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    fn games(conn: &Connection) -> Vec<(i64, Option<String>)> {
+        conn.prepare("SELECT id, team_1 FROM game ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn migrate_builds_a_fresh_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(legacy_version(&conn).unwrap(), 0);
+
+        migrate(&mut conn).unwrap();
+
+        assert_eq!(schema_version(&conn).unwrap(), MIGRATIONS.len());
+        let tables = tables(&conn);
+        for table in ["game", "series", "participant", "player", "draft", "rating"] {
+            assert!(
+                tables.iter().any(|t| t == table),
+                "no {table} in {tables:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn migrate_picks_up_databases_from_before_migrations() {
+        // Only the game table, as the first release left it
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0].sql).unwrap();
+        conn.execute(
+            "INSERT INTO game (data, team_1) VALUES ('{}', 'Tigers')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(legacy_version(&conn).unwrap(), 1);
+
+        migrate(&mut conn).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), MIGRATIONS.len());
+        assert_eq!(games(&conn), [(1, Some("Tigers".to_string()))]);
+
+        // Series were added before migrations too, and would fail if applied again
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0].sql).unwrap();
+        conn.execute_batch(MIGRATIONS[1].sql).unwrap();
+        assert_eq!(legacy_version(&conn).unwrap(), 2);
+
+        migrate(&mut conn).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn migrate_does_nothing_at_the_latest_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        conn.execute(
+            "INSERT INTO game (data, team_1) VALUES ('{}', 'Tigers')",
+            [],
+        )
+        .unwrap();
+        let schema = |conn: &Connection| -> Vec<String> {
+            conn.prepare("SELECT sql FROM sqlite_master WHERE sql IS NOT NULL ORDER BY name")
+                .unwrap()
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<rusqlite::Result<_>>()
+                .unwrap()
+        };
+        let before = schema(&conn);
+
+        migrate(&mut conn).unwrap();
+
+        assert_eq!(schema_version(&conn).unwrap(), MIGRATIONS.len());
+        assert_eq!(schema(&conn), before);
+        assert_eq!(games(&conn), [(1, Some("Tigers".to_string()))]);
+    }
+
+    #[test]
+    fn migrate_refuses_newer_databases() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", MIGRATIONS.len() + 1)
+            .unwrap();
+        assert!(matches!(
+            migrate(&mut conn),
+            Err(MigrationError::TooNew { found, supported })
+                if found == MIGRATIONS.len() + 1 && supported == MIGRATIONS.len()
+        ));
+    }
+}
@@ -0,0 +1,11 @@
+CREATE TABLE series (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    team_1 TEXT NOT NULL,
+    team_2 TEXT NOT NULL,
+    best_of INTEGER NOT NULL DEFAULT 3,
+    scheduled_date TEXT,
+    stage TEXT
+);
+
+ALTER TABLE game ADD COLUMN series_id INTEGER REFERENCES series(id);
+ALTER TABLE game ADD COLUMN game_number INTEGER;
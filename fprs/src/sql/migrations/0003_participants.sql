@@ -0,0 +1,114 @@
+-- Per-game, per-side and per-player values pulled out of `game.data` so the
+-- stats queries don't have to re-parse every payload. `repo::index_game`
+-- keeps these in sync for new games, the inserts below backfill old ones.
+
+ALTER TABLE game ADD COLUMN duration INTEGER;
+ALTER TABLE game ADD COLUMN end_timestamp INTEGER;
+ALTER TABLE game ADD COLUMN end_date TEXT;
+
+CREATE TABLE team_side (
+    game_id INTEGER NOT NULL REFERENCES game(id) ON DELETE CASCADE,
+    team_id INTEGER NOT NULL,
+    win INTEGER NOT NULL DEFAULT 0,
+    champion_kills INTEGER,
+    first_blood INTEGER,
+    tower_kills INTEGER,
+    first_tower INTEGER,
+    dragon_kills INTEGER,
+    first_dragon INTEGER,
+    baron_kills INTEGER,
+    horde_kills INTEGER,
+    rift_herald_kills INTEGER,
+    inhibitor_kills INTEGER,
+    atakhan_kills INTEGER,
+    PRIMARY KEY (game_id, team_id)
+);
+
+CREATE TABLE participant (
+    game_id INTEGER NOT NULL REFERENCES game(id) ON DELETE CASCADE,
+    slot INTEGER NOT NULL,
+    puuid TEXT,
+    riot_name TEXT,
+    tag_line TEXT,
+    champion TEXT,
+    team_id INTEGER NOT NULL,
+    role TEXT,
+    win INTEGER NOT NULL DEFAULT 0,
+    kills INTEGER NOT NULL DEFAULT 0,
+    deaths INTEGER NOT NULL DEFAULT 0,
+    assists INTEGER NOT NULL DEFAULT 0,
+    minions_killed INTEGER NOT NULL DEFAULT 0,
+    neutral_minions_killed INTEGER NOT NULL DEFAULT 0,
+    ally_jungle_minions_killed INTEGER,
+    enemy_jungle_minions_killed INTEGER,
+    gold INTEGER NOT NULL DEFAULT 0,
+    damage INTEGER,
+    vision_score INTEGER,
+    first_blood_kill INTEGER,
+    first_blood_assist INTEGER,
+    first_tower_kill INTEGER,
+    first_tower_assist INTEGER,
+    solo_kills INTEGER,
+    lane_minions_first_10 INTEGER,
+    jungle_cs_before_10 REAL,
+    PRIMARY KEY (game_id, slot)
+);
+
+CREATE INDEX participant_riot_name ON participant(riot_name);
+CREATE INDEX participant_champion ON participant(champion);
+
+UPDATE game SET
+    duration = json_extract(data, '$.info.gameDuration'),
+    end_timestamp = json_extract(data, '$.info.gameEndTimestamp'),
+    end_date = json_extract(data, '$.info.gameEndDate');
+
+INSERT INTO team_side
+SELECT
+    g.id,
+    json_extract(t.value, '$.teamId'),
+    COALESCE(json_extract(t.value, '$.win'), 0),
+    json_extract(t.value, '$.objectives.champion.kills'),
+    json_extract(t.value, '$.objectives.champion.first'),
+    json_extract(t.value, '$.objectives.tower.kills'),
+    json_extract(t.value, '$.objectives.tower.first'),
+    json_extract(t.value, '$.objectives.dragon.kills'),
+    json_extract(t.value, '$.objectives.dragon.first'),
+    json_extract(t.value, '$.objectives.baron.kills'),
+    json_extract(t.value, '$.objectives.horde.kills'),
+    json_extract(t.value, '$.objectives.riftHerald.kills'),
+    json_extract(t.value, '$.objectives.inhibitor.kills'),
+    json_extract(t.value, '$.objectives.atakhan.kills')
+FROM game g
+JOIN json_each(g.data, '$.info.teams') t;
+
+INSERT INTO participant
+SELECT
+    g.id,
+    p.key,
+    json_extract(p.value, '$.puuid'),
+    json_extract(p.value, '$.riotIdGameName'),
+    CAST(COALESCE(json_extract(p.value, '$.riotIdTagline'), json_extract(p.value, '$.riotIdTagLine')) AS TEXT),
+    json_extract(p.value, '$.championName'),
+    COALESCE(json_extract(p.value, '$.teamId'), 0),
+    COALESCE(json_extract(p.value, '$.teamPosition'), ''),
+    COALESCE(json_extract(p.value, '$.win'), 0),
+    COALESCE(json_extract(p.value, '$.kills'), 0),
+    COALESCE(json_extract(p.value, '$.deaths'), 0),
+    COALESCE(json_extract(p.value, '$.assists'), 0),
+    -- Manual games sometimes store counts as strings
+    CAST(COALESCE(json_extract(p.value, '$.totalMinionsKilled'), 0) AS INTEGER),
+    CAST(COALESCE(json_extract(p.value, '$.neutralMinionsKilled'), 0) AS INTEGER),
+    json_extract(p.value, '$.totalAllyJungleMinionsKilled'),
+    json_extract(p.value, '$.totalEnemyJungleMinionsKilled'),
+    COALESCE(json_extract(p.value, '$.goldEarned'), 0),
+    json_extract(p.value, '$.totalDamageDealtToChampions'),
+    json_extract(p.value, '$.visionScore'),
+    json_extract(p.value, '$.firstBloodKill'),
+    json_extract(p.value, '$.firstBloodAssist'),
+    json_extract(p.value, '$.firstTowerKill'),
+    json_extract(p.value, '$.firstTowerAssist'),
+    json_extract(p.value, '$.challenges.soloKills'),
+    json_extract(p.value, '$.challenges.laneMinionsFirst10Minutes'),
+    json_extract(p.value, '$.challenges.jungleCsBefore10Minutes')
+FROM game g
+JOIN json_each(g.data, '$.info.participants') p;
@@ -0,0 +1,23 @@
+-- Players are identified by puuid. Manual games don't record one, so
+-- `participant.player_puuid` holds the resolved identity: the game's own
+-- puuid, one from the alias file, or `name:<riot name>` as a last resort.
+-- `repo::refresh_players` rebuilds all of this from `participant`.
+
+ALTER TABLE participant ADD COLUMN player_puuid TEXT;
+CREATE INDEX participant_player ON participant(player_puuid);
+
+CREATE TABLE player (
+    puuid TEXT PRIMARY KEY,
+    riot_name TEXT NOT NULL,
+    tag_line TEXT
+);
+
+CREATE TABLE player_alias (
+    puuid TEXT NOT NULL REFERENCES player(puuid),
+    riot_name TEXT NOT NULL,
+    tag_line TEXT NOT NULL DEFAULT '',
+    games INTEGER NOT NULL,
+    first_seen TEXT,
+    last_seen TEXT,
+    PRIMARY KEY (puuid, riot_name, tag_line)
+);
@@ -0,0 +1,13 @@
+-- Who played for which team and when, so games can be given their teams
+-- from the players in them. `player` is either a puuid or a Riot ID
+-- (`name` or `name#tag`). A NULL date leaves that end of the range open.
+
+CREATE TABLE roster (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    team TEXT NOT NULL,
+    player TEXT NOT NULL,
+    start_date TEXT,
+    end_date TEXT
+);
+
+CREATE INDEX roster_team ON roster(team);
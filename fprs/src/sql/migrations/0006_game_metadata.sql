@@ -0,0 +1,5 @@
+-- Hand-entered details for a game. `patch` overrides the one derived from
+-- the game version, which manual games often don't have.
+
+ALTER TABLE game ADD COLUMN patch TEXT;
+ALTER TABLE game ADD COLUMN notes TEXT;
@@ -0,0 +1,38 @@
+-- Champion select for each game, one row per ban or pick in the order they
+-- were made. API payloads only record bans, in `info.teams[].bans`, so their
+-- `seq` is the ban's pick turn. A full draft entered by hand follows the
+-- tournament order (see `draft::TOURNAMENT_ORDER`) and is marked `manual`.
+-- `repo::index_game` refreshes the payload's bans but keeps a manual draft.
+
+-- Bans only carry a champion ID, so names are looked up from picks
+ALTER TABLE participant ADD COLUMN champion_id INTEGER;
+
+UPDATE participant SET champion_id = (
+    SELECT json_extract(g.data, '$.info.participants[' || participant.slot || '].championId')
+    FROM game g
+    WHERE g.id = participant.game_id
+);
+
+CREATE TABLE draft (
+    game_id INTEGER NOT NULL REFERENCES game(id) ON DELETE CASCADE,
+    seq INTEGER NOT NULL,
+    team_id INTEGER NOT NULL,
+    action TEXT NOT NULL CHECK (action IN ('ban', 'pick')),
+    champion TEXT,
+    champion_id INTEGER,
+    manual INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (game_id, seq)
+);
+
+-- A ban of -1 means the team didn't ban
+INSERT OR IGNORE INTO draft (game_id, seq, team_id, action, champion_id)
+SELECT
+    g.id,
+    json_extract(b.value, '$.pickTurn'),
+    json_extract(t.value, '$.teamId'),
+    'ban',
+    json_extract(b.value, '$.championId')
+FROM game g
+JOIN json_each(g.data, '$.info.teams') t
+JOIN json_each(t.value, '$.bans') b
+WHERE json_extract(b.value, '$.championId') > 0;
@@ -0,0 +1,21 @@
+-- Match-V5 timelines for API games. Manual games never have one. `data` is
+-- the payload as fetched, and `timeline_frame` pulls each player's totals out
+-- of its frames, one row per player per minute, for the lane diff and gold
+-- lead queries. `repo::insert_timeline` keeps the two in sync.
+
+CREATE TABLE timeline (
+    game_id INTEGER PRIMARY KEY REFERENCES game(id) ON DELETE CASCADE,
+    data TEXT CHECK (json_valid(data))
+);
+
+-- `slot` matches `participant.slot`
+CREATE TABLE timeline_frame (
+    game_id INTEGER NOT NULL REFERENCES game(id) ON DELETE CASCADE,
+    minute INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    total_gold INTEGER NOT NULL,
+    xp INTEGER NOT NULL,
+    level INTEGER NOT NULL,
+    cs INTEGER NOT NULL,
+    PRIMARY KEY (game_id, minute, slot)
+);
@@ -0,0 +1,49 @@
+-- Glicko-2 ratings after every game a team or player was rated in, worked
+-- out by `rating::rate`. The history is derived from the games, so anything
+-- that changes which games there are, who played them or which teams they
+-- were between clears it, and `repo::ratings` rebuilds it on the next read.
+
+CREATE TABLE rating (
+    kind TEXT NOT NULL CHECK (kind IN ('team', 'player')),
+    -- Team name or player puuid
+    subject TEXT NOT NULL,
+    game_id INTEGER NOT NULL REFERENCES game(id) ON DELETE CASCADE,
+    -- Position of the game in rating order, from 0
+    seq INTEGER NOT NULL,
+    rating REAL NOT NULL,
+    deviation REAL NOT NULL,
+    volatility REAL NOT NULL,
+    PRIMARY KEY (kind, subject, game_id)
+);
+
+CREATE TRIGGER rating_game_insert AFTER INSERT ON game
+BEGIN
+    DELETE FROM rating;
+END;
+
+CREATE TRIGGER rating_game_delete AFTER DELETE ON game
+BEGIN
+    DELETE FROM rating;
+END;
+
+CREATE TRIGGER rating_game_teams AFTER UPDATE OF team_1, team_2, end_timestamp, end_date ON game
+WHEN OLD.team_1 IS NOT NEW.team_1
+  OR OLD.team_2 IS NOT NEW.team_2
+  OR OLD.end_timestamp IS NOT NEW.end_timestamp
+  OR OLD.end_date IS NOT NEW.end_date
+BEGIN
+    DELETE FROM rating;
+END;
+
+-- Reindexing a game rewrites who won
+CREATE TRIGGER rating_team_side_insert AFTER INSERT ON team_side
+BEGIN
+    DELETE FROM rating;
+END;
+
+-- `refresh_players` rewrites every row, most of them to the same value
+CREATE TRIGGER rating_participant_player AFTER UPDATE OF player_puuid ON participant
+WHEN OLD.player_puuid IS NOT NEW.player_puuid
+BEGIN
+    DELETE FROM rating;
+END;
@@ -0,0 +1,19 @@
+-- The importer used to guess a series' format from its highest game number,
+-- which can't tell a 2-0 Bo3 from a Bo5 that ended 3-0. It now leaves it
+-- NULL when it isn't known, and the score decides. Stored series keep their
+-- format, as there's no telling a guess from one entered on purpose.
+
+CREATE TABLE series_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    team_1 TEXT NOT NULL,
+    team_2 TEXT NOT NULL,
+    best_of INTEGER,
+    scheduled_date TEXT,
+    stage TEXT
+);
+
+INSERT INTO series_new (id, team_1, team_2, best_of, scheduled_date, stage)
+SELECT id, team_1, team_2, best_of, scheduled_date, stage FROM series;
+
+DROP TABLE series;
+ALTER TABLE series_new RENAME TO series;
@@ -0,0 +1,15 @@
+-- Games on the same day are rated in game number order, so renumbering one
+-- changes the history too.
+
+DROP TRIGGER rating_game_teams;
+
+CREATE TRIGGER rating_game_teams
+AFTER UPDATE OF team_1, team_2, end_timestamp, end_date, game_number ON game
+WHEN OLD.team_1 IS NOT NEW.team_1
+  OR OLD.team_2 IS NOT NEW.team_2
+  OR OLD.end_timestamp IS NOT NEW.end_timestamp
+  OR OLD.end_date IS NOT NEW.end_date
+  OR OLD.game_number IS NOT NEW.game_number
+BEGIN
+    DELETE FROM rating;
+END;
@@ -1,2 +1,9 @@
+pub mod draft;
+pub mod filter;
+pub mod migrations;
+pub mod rating;
 pub mod repo;
+pub mod roster;
 pub mod schema;
+pub mod series;
+pub mod synergy;
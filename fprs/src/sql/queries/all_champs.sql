@@ -1,75 +1,116 @@
 WITH
 teams AS (
   SELECT
-    g.id AS game_id,
-    json_extract(p.value, '$.teamId') as team_id,
-    SUM(json_extract(p.value, '$.kills') ) AS kills,
-    SUM(json_extract(p.value, '$.deaths') ) AS deaths,
-    SUM(json_extract(p.value, '$.goldEarned') ) AS gold
-    -- json_extract(p.value, '$.totalDamageDealtToChampions') AS damage
-  FROM game g JOIN json_each(g.data, '$.info.participants') p
+    game_id,
+    team_id,
+    SUM(kills) AS kills,
+    SUM(deaths) AS deaths,
+    SUM(gold) AS gold
+  FROM participant
   GROUP BY game_id, team_id
 ),
+drafted AS (
+  -- Rates are out of the games with a draft, as the rest can't show a ban
+  SELECT DISTINCT game_id FROM draft
+),
 participants AS (
   SELECT
-        g.id AS game_id,
-        json_extract(p.value, '$.riotIdGameName') AS player_name,
-        json_extract(p.value, '$.championName') AS champion,
-        json_extract(p.value, '$.teamPosition') AS role,
-        CASE WHEN json_extract(p.value, '$.win') = true THEN 1 ELSE 0 END AS win,
-        CASE WHEN json_extract(p.value, '$.win') = false THEN 1 ELSE 0 END AS loss,
-        json_extract(p.value, '$.teamId') AS team_id,
-        json_extract(p.value, '$.kills') AS kills,
-        json_extract(p.value, '$.deaths') AS deaths,
-        json_extract(p.value, '$.assists') AS assists,
-        json_extract(p.value, '$.goldEarned') AS gold,
-        json_extract(p.value, '$.totalDamageDealtToChampions') AS damage,
-        (json_extract(p.value, '$.totalMinionsKilled') +
-          COALESCE( json_extract(p.value, '$.totalAllyJungleMinionsKilled'), 0 ) +
-          COALESCE( json_extract(p.value, '$.totalEnemyJungleMinionsKilled'), 0 )
+        p.game_id,
+        p.player_puuid AS puuid,
+        p.champion,
+        p.role,
+        CASE WHEN p.win = true THEN 1 ELSE 0 END AS win,
+        CASE WHEN p.win = false THEN 1 ELSE 0 END AS loss,
+        p.team_id,
+        p.kills,
+        p.deaths,
+        p.assists,
+        p.gold,
+        p.damage,
+        (p.minions_killed +
+          COALESCE( p.ally_jungle_minions_killed, 0 ) +
+          COALESCE( p.enemy_jungle_minions_killed, 0 )
         ) AS cs,
-        (CASE
-          WHEN json_extract(p.value, '$.totalDamageDealtToChampions') IS NULL THEN 0 
-          ELSE json_extract(g.data, '$.info.gameDuration')
-        END) AS game_length_dmg,
-        json_extract(p.value, '$.visionScore')     AS vis,
-        (CASE
-          WHEN json_extract(p.value, '$.visionScore') IS NULL THEN 0 
-          ELSE json_extract(g.data, '$.info.gameDuration')
-        END) AS game_length_vis,
-        json_extract(g.data, '$.info.gameDuration') AS game_length
-
-    FROM game g
-    JOIN json_each(g.data, '$.info.participants') p
+        (CASE WHEN p.damage IS NULL THEN 0 ELSE g.duration END) AS game_length_dmg,
+        p.vision_score AS vis,
+        (CASE WHEN p.vision_score IS NULL THEN 0 ELSE g.duration END) AS game_length_vis,
+        g.duration AS game_length,
+        p.game_id IN (SELECT game_id FROM drafted) AS drafted
+    FROM participant p
+    JOIN game g ON g.id = p.game_id
+    LEFT JOIN player pl ON pl.puuid = p.player_puuid
+),
+bans AS (
+  -- Bans from the API only have an ID, so look the name up
+  SELECT
+    COALESCE(d.champion, c.name) AS champion,
+    COUNT(DISTINCT d.game_id) AS bans
+  FROM draft d
+  LEFT JOIN champion c ON c.id = d.champion_id
+  WHERE d.action = 'ban'
+  GROUP BY 1
+),
+first_picks AS (
+  -- Only drafts entered by hand record the order of picks
+  SELECT
+    d.champion,
+    SUM(d.seq = (
+      SELECT MIN(f.seq) FROM draft f
+      WHERE f.game_id = d.game_id AND f.team_id = d.team_id AND f.action = 'pick'
+    )) AS first_picks,
+    COUNT(*) AS picks
+  FROM draft d
+  WHERE d.action = 'pick'
+  GROUP BY d.champion
+),
+champions AS (
+  -- A champion can be banned in every game it's in without being picked
+  SELECT champion FROM participants
+  UNION
+  SELECT champion FROM bans WHERE champion IS NOT NULL
 )
 
 SELECT
-  p.champion as champion,
+  c.champion as champion,
   COUNT(DISTINCT p.game_id) as games,
-  COUNT(*)*100.0/(SELECT COUNT(*) from game) as pick_percentage,
-  COUNT(DISTINCT p.player_name) as unique_players,
-  SUM(p.win) AS wins,
-  SUM(p.loss) AS losses,
-  SUM(p.win)*100.0 / (COUNT(DISTINCT p.game_id)) as win_percentage,
-  AVG(p.kills) as kills,
-  AVG(p.deaths) as deaths,
-  AVG(p.assists) as assists,
-  (SUM(p.kills)+SUM(p.assists)) * 1.0 / 
+  COALESCE(
+    COUNT(DISTINCT CASE WHEN p.drafted THEN p.game_id END)*100.0
+      / (SELECT NULLIF(COUNT(*), 0) FROM drafted), 0
+  ) as pick_percentage,
+  COALESCE(MAX(b.bans)*100.0 / (SELECT NULLIF(COUNT(*), 0) FROM drafted), 0) AS ban_percentage,
+  COALESCE(
+    (COUNT(DISTINCT CASE WHEN p.drafted THEN p.game_id END) + COALESCE(MAX(b.bans), 0))*100.0
+      / (SELECT NULLIF(COUNT(*), 0) FROM drafted), 0
+  ) AS presence_percentage,
+  MAX(fp.first_picks)*100.0 / MAX(fp.picks) AS first_pick_percentage,
+  COUNT(DISTINCT p.puuid) as unique_players,
+  COALESCE(SUM(p.win), 0) AS wins,
+  COALESCE(SUM(p.loss), 0) AS losses,
+  COALESCE(SUM(p.win)*100.0 / (COUNT(DISTINCT p.game_id)), 0) as win_percentage,
+  COALESCE(AVG(p.kills), 0) as kills,
+  COALESCE(AVG(p.deaths), 0) as deaths,
+  COALESCE(AVG(p.assists), 0) as assists,
+  COALESCE((SUM(p.kills)+SUM(p.assists)) * 1.0 / 
   (CASE
     WHEN SUM(p.deaths) = 0 THEN 1 ELSE SUM(p.deaths)
-  END) as kda,
-  AVG(p.cs) as cs,
-  SUM(p.cs)*1.0 / (SUM(p.game_length) / 60) AS csm,
+  END), 0) as kda,
+  COALESCE(AVG(p.cs), 0) as cs,
+  COALESCE(SUM(p.cs)*1.0 / (SUM(p.game_length) / 60), 0) AS csm,
   AVG(p.vis) as vs,
   SUM(p.vis)*1.0 / (SUM(p.game_length_vis) / 60) AS vsm,
-  AVG(p.gold) as gold,
-  SUM(p.gold)*1.0 / (SUM(p.game_length) / 60) AS goldm,
+  COALESCE(AVG(p.gold), 0) as gold,
+  COALESCE(SUM(p.gold)*1.0 / (SUM(p.game_length) / 60), 0) AS goldm,
   AVG(p.damage) AS damage,
   SUM(p.damage)*1.0 / (SUM(p.game_length_dmg) / 60) AS damagem,
-  SUM(p.kills+p.assists)*100.0 / SUM(t.kills) AS kill_percentage,
-  SUM(p.kills)*100.0 / SUM(t.kills) AS kill_share,
-  SUM(p.gold)*100.0 / SUM(t.gold) AS gold_share,
-  group_concat(DISTINCT p.role) as roles
-FROM participants p JOIN teams t ON p.game_id = t.game_id AND p.team_id = t.team_id
-GROUP BY champion
-ORDER BY games DESC;
+  COALESCE(SUM(p.kills+p.assists)*100.0 / SUM(t.kills), 0) AS kill_percentage,
+  COALESCE(SUM(p.kills)*100.0 / SUM(t.kills), 0) AS kill_share,
+  COALESCE(SUM(p.gold)*100.0 / SUM(t.gold), 0) AS gold_share,
+  COALESCE(group_concat(DISTINCT p.role), '') as roles
+FROM champions c
+LEFT JOIN (
+  participants p JOIN teams t ON p.game_id = t.game_id AND p.team_id = t.team_id
+) ON p.champion = c.champion
+LEFT JOIN bans b ON b.champion = c.champion
+LEFT JOIN first_picks fp ON fp.champion = c.champion
+GROUP BY c.champion
+ORDER BY games DESC, ban_percentage DESC;
@@ -1,46 +1,40 @@
 WITH
 teams AS (
   SELECT
-    g.id AS game_id,
-    json_extract(p.value, '$.teamId') as team_id,
-    SUM(json_extract(p.value, '$.kills') ) AS kills,
-    SUM(json_extract(p.value, '$.deaths') ) AS deaths,
-    SUM(json_extract(p.value, '$.goldEarned') ) AS gold
-    -- json_extract(p.value, '$.totalDamageDealtToChampions') AS damage
-  FROM game g JOIN json_each(g.data, '$.info.participants') p
+    game_id,
+    team_id,
+    SUM(kills) AS kills,
+    SUM(deaths) AS deaths,
+    SUM(gold) AS gold
+  FROM participant
   GROUP BY game_id, team_id
 ),
 participants AS (
   SELECT
-        g.id AS game_id,
-        json_extract(p.value, '$.riotIdGameName') AS player_name,
-        json_extract(p.value, '$.championName') AS champion,
-        json_extract(p.value, '$.teamPosition') AS role,
-        CASE WHEN json_extract(p.value, '$.win') = true THEN 1 ELSE 0 END AS win,
-        CASE WHEN json_extract(p.value, '$.win') = false THEN 1 ELSE 0 END AS loss,
-        json_extract(p.value, '$.teamId') AS team_id,
-        json_extract(p.value, '$.kills') AS kills,
-        json_extract(p.value, '$.deaths') AS deaths,
-        json_extract(p.value, '$.assists') AS assists,
-        json_extract(p.value, '$.goldEarned') AS gold,
-        json_extract(p.value, '$.totalDamageDealtToChampions') AS damage,
-        (json_extract(p.value, '$.totalMinionsKilled') +
-          COALESCE( json_extract(p.value, '$.totalAllyJungleMinionsKilled'), 0 ) +
-          COALESCE( json_extract(p.value, '$.totalEnemyJungleMinionsKilled'), 0 )
+        p.game_id,
+        p.player_puuid AS puuid,
+        COALESCE(pl.riot_name, p.riot_name) AS player_name,
+        p.champion,
+        p.role,
+        CASE WHEN p.win = true THEN 1 ELSE 0 END AS win,
+        CASE WHEN p.win = false THEN 1 ELSE 0 END AS loss,
+        p.team_id,
+        p.kills,
+        p.deaths,
+        p.assists,
+        p.gold,
+        p.damage,
+        (p.minions_killed +
+          COALESCE( p.ally_jungle_minions_killed, 0 ) +
+          COALESCE( p.enemy_jungle_minions_killed, 0 )
         ) AS cs,
-        (CASE
-          WHEN json_extract(p.value, '$.totalDamageDealtToChampions') IS NULL THEN 0 
-          ELSE json_extract(g.data, '$.info.gameDuration')
-        END) AS game_length_dmg,
-        json_extract(p.value, '$.visionScore')     AS vis,
-        (CASE
-          WHEN json_extract(p.value, '$.visionScore') IS NULL THEN 0 
-          ELSE json_extract(g.data, '$.info.gameDuration')
-        END) AS game_length_vis,
-        json_extract(g.data, '$.info.gameDuration') AS game_length
-
-    FROM game g
-    JOIN json_each(g.data, '$.info.participants') p
+        (CASE WHEN p.damage IS NULL THEN 0 ELSE g.duration END) AS game_length_dmg,
+        p.vision_score AS vis,
+        (CASE WHEN p.vision_score IS NULL THEN 0 ELSE g.duration END) AS game_length_vis,
+        g.duration AS game_length
+    FROM participant p
+    JOIN game g ON g.id = p.game_id
+    LEFT JOIN player pl ON pl.puuid = p.player_puuid
 )
 
 SELECT
@@ -72,6 +66,6 @@ SELECT
   (p.gold)*100.0/ t.gold as gold_share
 
 FROM participants p JOIN teams t ON p.game_id = t.game_id AND p.team_id = t.team_id 
-JOIN participants p2 on p2.game_id=p.game_id AND p.role=p2.role AND p.player_name != p2.player_name
+JOIN participants p2 on p2.game_id=p.game_id AND p.role=p2.role AND p.puuid != p2.puuid
 ORDER BY p.game_id DESC;
 
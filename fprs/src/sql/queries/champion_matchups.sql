@@ -0,0 +1,62 @@
+-- Each champion's record against the champions it laned against, by role
+WITH
+at_10 AS (
+    SELECT game_id, slot, cs
+    FROM timeline_frame
+    WHERE minute = 10
+),
+
+-- Every player against the other side's player in the same role
+lanes AS (
+    SELECT
+        p.player_puuid AS puuid,
+        p.champion,
+        p.role,
+        CASE WHEN p.win = TRUE THEN 1 ELSE 0 END AS win,
+        p.kills,
+        p.deaths,
+        p.assists,
+        p.gold - o.gold AS gold_diff,
+        ( p.minions_killed + COALESCE(p.ally_jungle_minions_killed, 0) + COALESCE(p.enemy_jungle_minions_killed, 0) )
+      - ( o.minions_killed + COALESCE(o.ally_jungle_minions_killed, 0) + COALESCE(o.enemy_jungle_minions_killed, 0) )
+        AS cs_diff,
+        COALESCE(
+            tp.cs - top.cs,
+            -- Manual games don't record CS at 10
+            CASE WHEN p.lane_minions_first_10 IS NOT NULL AND o.lane_minions_first_10 IS NOT NULL THEN
+                ( p.lane_minions_first_10 + CAST(COALESCE(p.jungle_cs_before_10, 0) AS INTEGER) )
+              - ( o.lane_minions_first_10 + CAST(COALESCE(o.jungle_cs_before_10, 0) AS INTEGER) )
+            END
+        ) AS cd10,
+        o.player_puuid AS opponent_puuid,
+        COALESCE(opl.riot_name, o.riot_name) AS opponent_name,
+        o.champion AS opponent_champion
+    FROM participant p
+    JOIN participant o
+      ON o.game_id = p.game_id
+     AND o.role = p.role
+     AND o.team_id != p.team_id
+    LEFT JOIN player opl ON opl.puuid = o.player_puuid
+    LEFT JOIN at_10 tp ON tp.game_id = p.game_id AND tp.slot = p.slot
+    LEFT JOIN at_10 top ON top.game_id = o.game_id AND top.slot = o.slot
+    WHERE COALESCE(p.role, '') != ''
+)
+
+SELECT
+    champion          AS subject,
+    role,
+    opponent_champion AS opponent,
+    COUNT(*)              AS games,
+    SUM(win)              AS wins,
+    COUNT(*) - SUM(win)   AS losses,
+    ROUND(
+        (SUM(kills) + SUM(assists)) * 1.0 /
+        CASE WHEN SUM(deaths) = 0 THEN 1 ELSE SUM(deaths) END,
+        2
+    ) AS kda,
+    AVG(gold_diff)        AS gold_diff,
+    AVG(cs_diff)          AS cs_diff,
+    AVG(cd10)             AS cd10
+FROM lanes
+GROUP BY champion, role, opponent_champion
+ORDER BY champion, role, games DESC, wins DESC;
@@ -0,0 +1,56 @@
+-- One row per team and champion for each kind of draft tendency, plus a
+-- `games` row per team counting the games it has draft data for.
+WITH
+drafts AS (
+  SELECT
+    d.game_id,
+    d.team_id,
+    d.action,
+    d.seq,
+    -- Bans from the API only have an ID, so look the name up
+    COALESCE(d.champion, c.name) AS champion,
+    CASE d.team_id WHEN 100 THEN g.team_1 ELSE g.team_2 END AS team,
+    CASE d.team_id WHEN 100 THEN g.team_2 ELSE g.team_1 END AS opponent
+  FROM draft d
+  JOIN game g ON g.id = d.game_id
+  LEFT JOIN champion c ON c.id = d.champion_id
+),
+first_picks AS (
+  SELECT game_id, team_id, MIN(seq) AS seq
+  FROM draft
+  WHERE action = 'pick'
+  GROUP BY game_id, team_id
+)
+
+SELECT team, 'games' AS kind, NULL AS champion, COUNT(DISTINCT game_id) AS times
+FROM (
+  SELECT team, game_id FROM drafts
+  UNION
+  SELECT opponent, game_id FROM drafts
+)
+WHERE team <> ''
+GROUP BY team
+
+UNION ALL
+
+SELECT team, 'ban', champion, COUNT(*)
+FROM drafts
+WHERE action = 'ban' AND team <> '' AND champion IS NOT NULL
+GROUP BY team, champion
+
+UNION ALL
+
+SELECT opponent, 'banned_against', champion, COUNT(*)
+FROM drafts
+WHERE action = 'ban' AND opponent <> '' AND champion IS NOT NULL
+GROUP BY opponent, champion
+
+UNION ALL
+
+SELECT d.team, 'first_pick', d.champion, COUNT(*)
+FROM drafts d
+JOIN first_picks f ON f.game_id = d.game_id AND f.team_id = d.team_id AND f.seq = d.seq
+WHERE d.team <> ''
+GROUP BY d.team, d.champion
+
+ORDER BY 1, 2, 4 DESC, 3;
@@ -0,0 +1,55 @@
+-- Stored games matching a `GameFilter`, newest first. Each parameter is
+-- skipped when NULL:
+--   ?1 words, as a JSON array     ?2 team   ?3 player   ?4 champion
+--   ?5 played on or after         ?6 played on or before
+--   ?7 winning side               ?8 manual
+-- Text parameters come with `\`, `%` and `_` escaped by `GameFilter::params`.
+WITH
+names AS (
+    SELECT p.game_id, p.riot_name, p.champion, pl.riot_name AS player_name
+    FROM participant p
+    LEFT JOIN player pl ON pl.puuid = p.player_puuid
+)
+
+SELECT g.*
+FROM game g
+-- Every word has to match something in the game. Unset teams compare as NULL,
+-- which would otherwise let a word that matches nothing through.
+WHERE ( ?1 IS NULL OR NOT EXISTS (
+            SELECT 1 FROM json_each( ?1 ) w
+            WHERE NOT COALESCE(
+                g.team_1 LIKE '%' || w.value || '%' ESCAPE '\'
+                OR g.team_2 LIKE '%' || w.value || '%' ESCAPE '\'
+                OR EXISTS (
+                    SELECT 1 FROM names n
+                    WHERE n.game_id = g.id
+                      AND ( n.riot_name LIKE '%' || w.value || '%' ESCAPE '\'
+                            OR n.player_name LIKE '%' || w.value || '%' ESCAPE '\'
+                            OR n.champion LIKE '%' || w.value || '%' ESCAPE '\' )
+                ),
+                FALSE
+            )
+        ) )
+  AND ( ?2 IS NULL OR g.team_1 LIKE '%' || ?2 || '%' ESCAPE '\' OR g.team_2 LIKE '%' || ?2 || '%' ESCAPE '\' )
+  AND ( ?3 IS NULL OR EXISTS (
+            SELECT 1 FROM names n
+            WHERE n.game_id = g.id
+              AND ( n.riot_name LIKE '%' || ?3 || '%' ESCAPE '\' OR n.player_name LIKE '%' || ?3 || '%' ESCAPE '\' )
+        ) )
+  AND ( ?4 IS NULL OR EXISTS (
+            SELECT 1 FROM names n
+            WHERE n.game_id = g.id AND n.champion LIKE '%' || ?4 || '%' ESCAPE '\'
+        ) )
+  -- API games carry an end timestamp, manual games only the date they were played
+  AND ( ?5 IS NULL OR COALESCE(
+            date(g.end_timestamp / 1000, 'unixepoch', 'localtime'), g.end_date
+        ) >= ?5 )
+  AND ( ?6 IS NULL OR COALESCE(
+            date(g.end_timestamp / 1000, 'unixepoch', 'localtime'), g.end_date
+        ) <= ?6 )
+  AND ( ?7 IS NULL OR EXISTS (
+            SELECT 1 FROM team_side s
+            WHERE s.game_id = g.id AND s.win = TRUE AND s.team_id = CAST( ?7 AS INTEGER )
+        ) )
+  AND ( ?8 IS NULL OR g.manual = CAST( ?8 AS INTEGER ) )
+ORDER BY COALESCE(end_timestamp / 1000, CAST(strftime('%s', end_date) AS INTEGER)) DESC, id DESC
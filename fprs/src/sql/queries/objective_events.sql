@@ -0,0 +1,32 @@
+-- Dragon, baron, herald and tower kills from every stored timeline, in the
+-- order they happened. A tower's event names the side that lost it.
+WITH
+events AS (
+    SELECT t.game_id, e.value AS event
+    FROM timeline t
+    JOIN json_each(t.data, '$.info.frames') f
+    JOIN json_each(f.value, '$.events') e
+    WHERE json_extract(e.value, '$.type') IN ('ELITE_MONSTER_KILL', 'BUILDING_KILL')
+),
+
+objectives AS (
+    SELECT
+        game_id,
+        json_extract(event, '$.timestamp') / 60000.0 AS minute,
+        CASE json_extract(event, '$.type')
+            WHEN 'BUILDING_KILL' THEN 300 - json_extract(event, '$.teamId')
+            ELSE json_extract(event, '$.killerTeamId')
+        END AS team_id,
+        CASE
+            WHEN json_extract(event, '$.buildingType') = 'TOWER_BUILDING' THEN 'tower'
+            WHEN json_extract(event, '$.monsterType') = 'DRAGON' THEN 'dragon'
+            WHEN json_extract(event, '$.monsterType') = 'BARON_NASHOR' THEN 'baron'
+            WHEN json_extract(event, '$.monsterType') = 'RIFTHERALD' THEN 'herald'
+        END AS kind
+    FROM events
+)
+
+SELECT game_id, minute, team_id, kind
+FROM objectives
+WHERE kind IS NOT NULL AND team_id IN (100, 200)
+ORDER BY game_id, minute
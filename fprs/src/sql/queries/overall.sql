@@ -1,18 +1,13 @@
 SELECT
   COUNT(*) / 2 AS games,
-    SUM(
-      CASE 
-        WHEN json_extract(t.value, '$.win') = TRUE 
-          AND json_extract(t.value, '$.teamId') = 100 
-        THEN 1 ELSE 0
-      END) AS blue_wins,
-    SUM(CASE WHEN json_extract(t.value, '$.win') = true AND json_extract(t.value, '$.teamId') = 200 THEN 1 ELSE 0 END) AS red_wins,
-    CAST( 
-      ROUND( 
-        AVG(json_extract(m.data, '$.info.gameDuration')),
+    SUM(CASE WHEN t.win = TRUE AND t.team_id = 100 THEN 1 ELSE 0 END) AS blue_wins,
+    SUM(CASE WHEN t.win = TRUE AND t.team_id = 200 THEN 1 ELSE 0 END) AS red_wins,
+    CAST(
+      ROUND(
+        AVG(m.duration),
       0)
     AS INTEGER) as game_length_avg,
-    MIN(json_extract(m.data, '$.info.gameDuration')) as game_length_min,
-    MAX(json_extract(m.data, '$.info.gameDuration')) as game_length_max
+    MIN(m.duration) as game_length_min,
+    MAX(m.duration) as game_length_max
 FROM game m
-JOIN json_each(m.data, '$.info.teams') t;
+JOIN team_side t ON t.game_id = m.id;
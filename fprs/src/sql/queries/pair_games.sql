@@ -0,0 +1,41 @@
+-- Every pair of players on the same side of a game, one row per pair.
+-- Players `refresh_players` hasn't reached yet get the key it would give them.
+-- Games come in the order they were played, so names are from the latest.
+WITH
+team_kills AS (
+    SELECT game_id, team_id, SUM(kills) AS kills
+    FROM participant
+    GROUP BY game_id, team_id
+)
+
+SELECT
+    CASE WHEN a.win = TRUE THEN 1 ELSE 0 END AS win,
+    g.duration,
+    tk.kills                              AS team_kills,
+    COALESCE(a.player_puuid, NULLIF(a.puuid, ''), 'name:' || COALESCE(a.riot_name, '')) AS puuid_a,
+    COALESCE(pa.riot_name, a.riot_name)   AS name_a,
+    a.champion                            AS champion_a,
+    COALESCE(a.role, '')                  AS role_a,
+    COALESCE(b.player_puuid, NULLIF(b.puuid, ''), 'name:' || COALESCE(b.riot_name, '')) AS puuid_b,
+    COALESCE(pb.riot_name, b.riot_name)   AS name_b,
+    b.champion                            AS champion_b,
+    COALESCE(b.role, '')                  AS role_b,
+    a.kills + b.kills                     AS kills,
+    a.deaths + b.deaths                   AS deaths,
+    a.assists + b.assists                 AS assists,
+    a.gold + b.gold                       AS gold
+FROM participant a
+JOIN participant b
+  ON b.game_id = a.game_id
+ AND b.team_id = a.team_id
+ AND b.slot > a.slot
+JOIN game g ON g.id = a.game_id
+JOIN team_kills tk ON tk.game_id = a.game_id AND tk.team_id = a.team_id
+LEFT JOIN player pa ON pa.puuid = a.player_puuid
+LEFT JOIN player pb ON pb.puuid = b.player_puuid
+ORDER BY
+    COALESCE(g.end_timestamp / 1000, CAST(strftime('%s', g.end_date) AS INTEGER)),
+    g.game_number,
+    g.id,
+    a.slot,
+    b.slot;
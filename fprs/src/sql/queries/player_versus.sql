@@ -0,0 +1,45 @@
+-- Games two players were both in, first player's view. Lane differences
+-- prefer the stored timeline and fall back to the challenge's CS at 10.
+WITH
+at_10 AS (
+    SELECT game_id, slot, total_gold, cs
+    FROM timeline_frame
+    WHERE minute = 10
+)
+
+SELECT
+    g.id AS game_id,
+    COALESCE(
+        date(g.end_timestamp / 1000, 'unixepoch', 'localtime'),
+        g.end_date
+    ) AS game_date,
+    a.team_id = b.team_id AS same_team,
+    a.win         AS win,
+    a.champion    AS champion_a,
+    b.champion    AS champion_b,
+    COALESCE(a.role, '') AS role_a,
+    COALESCE(b.role, '') AS role_b,
+    a.kills       AS kills_a,
+    a.deaths      AS deaths_a,
+    a.assists     AS assists_a,
+    b.kills       AS kills_b,
+    b.deaths      AS deaths_b,
+    b.assists     AS assists_b,
+    COALESCE(
+        ta.cs - tb.cs,
+        -- Manual games don't record CS at 10
+        CASE WHEN a.lane_minions_first_10 IS NOT NULL AND b.lane_minions_first_10 IS NOT NULL THEN
+            ( a.lane_minions_first_10 + CAST(COALESCE(a.jungle_cs_before_10, 0) AS INTEGER) )
+          - ( b.lane_minions_first_10 + CAST(COALESCE(b.jungle_cs_before_10, 0) AS INTEGER) )
+        END
+    ) AS cd10,
+    ta.total_gold - tb.total_gold AS gd10
+FROM participant a
+JOIN participant b
+  ON b.game_id = a.game_id
+ AND b.player_puuid = ?2
+JOIN game g ON g.id = a.game_id
+LEFT JOIN at_10 ta ON ta.game_id = a.game_id AND ta.slot = a.slot
+LEFT JOIN at_10 tb ON tb.game_id = b.game_id AND tb.slot = b.slot
+WHERE a.player_puuid = ?1
+ORDER BY game_date DESC, g.end_timestamp DESC, g.id DESC;
@@ -2,57 +2,56 @@ WITH
 games AS (
     SELECT
         id,
-        json_extract(data, '$.info.gameDuration') AS duration
+        duration
     FROM game
 ),
 
 participants AS (
     SELECT
-        g.id AS game_id,
-        json_extract(p.value, '$.riotIdGameName') AS name,
-        NULLIF(json_extract(p.value, '$.riotIdTagline'), 'null') AS tag,
-        json_extract(p.value, '$.teamId')         AS team_id,
-        (CASE WHEN json_extract(p.value, '$.teamId') = 100 THEN g.team_1 ELSE g.team_2 END ) AS team_name,
-        json_extract(p.value, '$.teamPosition')  AS role,
-        json_extract(p.value, '$.soloKills')          AS solos,
-        json_extract(p.value, '$.kills')          AS kills,
-        json_extract(p.value, '$.deaths')         AS deaths,
-        json_extract(p.value, '$.assists')        AS assists,
-        json_extract(p.value, '$.goldEarned')     AS gold,
-        json_extract(p.value, '$.visionScore')     AS vis,
-    ( CASE WHEN json_extract(p.value, '$.visionScore') IS NULL THEN 0 ELSE json_extract(g.data, '$.info.gameDuration') END ) AS game_length_vis,
-        json_extract(p.value, '$.totalDamageDealtToChampions') AS damage,
-    ( CASE WHEN json_extract(p.value, '$.totalDamageDealtToChampions') IS NULL THEN 0 ELSE json_extract(g.data, '$.info.gameDuration') END ) AS game_length_dmg,
+        p.game_id,
+        p.slot,
+        p.player_puuid AS puuid,
+        COALESCE(pl.riot_name, p.riot_name) AS name,
+        COALESCE(pl.tag_line, NULLIF(p.tag_line, 'null')) AS tag,
+        p.team_id,
+        (CASE WHEN p.team_id = 100 THEN g.team_1 ELSE g.team_2 END ) AS team_name,
+        p.role,
+        p.solo_kills AS solos,
+        p.kills,
+        p.deaths,
+        p.assists,
+        p.gold,
+        p.vision_score AS vis,
+        ( CASE WHEN p.vision_score IS NULL THEN 0 ELSE g.duration END ) AS game_length_vis,
+        p.damage,
+        ( CASE WHEN p.damage IS NULL THEN 0 ELSE g.duration END ) AS game_length_dmg,
         CASE
-    WHEN json_extract(p.value, '$.teamPosition') = 'JUNGLE' THEN
-        CAST( json_extract(p.value, '$.challenges.jungleCsBefore10Minutes') AS INTEGER) 
-      ELSE
-       CAST( json_extract(p.value, '$.challenges.laneMinionsFirst10Minutes') AS INTEGER)
-    END AS cs10,
-        -- json_extract(p.value, '$.challenges.laneMinionsFirst10Minutes') + CAST(COALESCE( json_extract(p.value, '$.challenges.jungleCsBefore10Minutes'), 0 ) AS INTEGER)     AS cs10,
-       (  json_extract(p.value, '$.totalMinionsKilled') +
-    COALESCE( json_extract(p.value, '$.totalAllyJungleMinionsKilled'), 0 ) + COALESCE( json_extract(p.value, '$.totalEnemyJungleMinionsKilled'), 0 ))
-    AS cs,
-        CASE WHEN json_extract(p.value, '$.firstBloodAssist') = TRUE THEN 1 ELSE 0 END AS fba,
-        CASE WHEN json_extract(p.value, '$.firstBloodKill') = TRUE THEN 1 ELSE 0 END AS fbk,
-        CASE WHEN json_extract(p.value, '$.firstTowerAssist') = TRUE THEN 1 ELSE 0 END AS fta,
-        CASE WHEN json_extract(p.value, '$.firstTowerKill') = TRUE THEN 1 ELSE 0 END AS ftk
-    FROM game g
-    JOIN json_each(g.data, '$.info.participants') p
+            WHEN p.role = 'JUNGLE' THEN CAST(p.jungle_cs_before_10 AS INTEGER)
+            ELSE CAST(p.lane_minions_first_10 AS INTEGER)
+        END AS cs10,
+        ( p.minions_killed +
+          COALESCE( p.ally_jungle_minions_killed, 0 ) + COALESCE( p.enemy_jungle_minions_killed, 0 ))
+        AS cs,
+        CASE WHEN p.first_blood_assist = TRUE THEN 1 ELSE 0 END AS fba,
+        CASE WHEN p.first_blood_kill = TRUE THEN 1 ELSE 0 END AS fbk,
+        CASE WHEN p.first_tower_assist = TRUE THEN 1 ELSE 0 END AS fta,
+        CASE WHEN p.first_tower_kill = TRUE THEN 1 ELSE 0 END AS ftk
+    FROM participant p
+    JOIN game g ON g.id = p.game_id
+    LEFT JOIN player pl ON pl.puuid = p.player_puuid
 ),
 
 teams AS (
     SELECT
-        g.id AS game_id,
-        json_extract(t.value, '$.teamId') AS team_id,
-        json_extract(t.value, '$.objectives.champion.kills') AS ckill,
-        CASE WHEN json_extract(t.value, '$.objectives.champion.first') = TRUE THEN 1 ELSE 0 END AS fb,
-        CASE WHEN json_extract(t.value, '$.objectives.tower.first') = TRUE THEN 1 ELSE 0 END AS ft,
-        CASE WHEN json_extract(t.value, '$.objectives.dragon.first') = TRUE THEN 1 ELSE 0 END AS fd,
-        json_extract(t.value, '$.objectives.dragon.kills') AS td,
-        json_extract(t.value, '$.objectives.horde.kills') AS grubs
-    FROM game g
-    JOIN json_each(g.data, '$.info.teams') t
+        game_id,
+        team_id,
+        champion_kills AS ckill,
+        CASE WHEN first_blood = TRUE THEN 1 ELSE 0 END AS fb,
+        CASE WHEN first_tower = TRUE THEN 1 ELSE 0 END AS ft,
+        CASE WHEN first_dragon = TRUE THEN 1 ELSE 0 END AS fd,
+        dragon_kills AS td,
+        horde_kills AS grubs
+    FROM team_side
 ),
 
 team_damage AS (
@@ -67,14 +66,41 @@ team_damage AS (
 cs_diff AS (
   SELECT
     p.game_id,
-    p.name,
+    p.puuid,
     (p.cs10 - opp.cs10) AS cd10
   FROM participants p
   JOIN participants opp
     ON p.game_id = opp.game_id
    AND p.role = opp.role
    AND p.team_id != opp.team_id
-  -- GROUP BY p.game_id, p.name
+  -- GROUP BY p.game_id, p.puuid
+),
+
+-- Lane opponent differences from stored timelines, at 10 and 15 minutes
+frames AS (
+  SELECT f.game_id, f.minute, f.slot, p.team_id, p.role, f.total_gold, f.xp, f.cs
+  FROM timeline_frame f
+  JOIN participant p ON p.game_id = f.game_id AND p.slot = f.slot
+  WHERE f.minute IN (10, 15) AND p.role != ''
+),
+
+lane_diff AS (
+  SELECT
+    me.game_id,
+    me.slot,
+    MAX(CASE WHEN me.minute = 10 THEN me.total_gold - opp.total_gold END) AS gd10,
+    MAX(CASE WHEN me.minute = 10 THEN me.xp - opp.xp END) AS xpd10,
+    MAX(CASE WHEN me.minute = 10 THEN me.cs - opp.cs END) AS cd10,
+    MAX(CASE WHEN me.minute = 15 THEN me.total_gold - opp.total_gold END) AS gd15,
+    MAX(CASE WHEN me.minute = 15 THEN me.xp - opp.xp END) AS xpd15,
+    MAX(CASE WHEN me.minute = 15 THEN me.cs - opp.cs END) AS cd15
+  FROM frames me
+  JOIN frames opp
+    ON opp.game_id = me.game_id
+   AND opp.minute = me.minute
+   AND opp.role = me.role
+   AND opp.team_id != me.team_id
+  GROUP BY me.game_id, me.slot
 ),
 
 enemy_team AS (
@@ -86,6 +112,7 @@ enemy_team AS (
 )
 
 SELECT
+        p.puuid AS puuid,
         p.name AS riot_id,
         COALESCE(p.tag, "") AS tag_line,
        p.team_name AS team_name,
@@ -106,7 +133,14 @@ SELECT
             (SUM(g.duration) / 60.0),
             1
         ) AS cspm,
-  ROUND( AVG(c.cd10), 1 ) AS cd10,
+        ROUND( AVG(c.cd10), 1 ) AS cd10,
+        ROUND( AVG(ld.gd10) ) AS gd10,
+        ROUND( AVG(ld.xpd10) ) AS xpd10,
+        -- Every minion and monster by the timeline, unlike `cd10`'s lane minions
+        ROUND( AVG(ld.cd10), 1 ) AS timeline_cd10,
+        ROUND( AVG(ld.gd15) ) AS gd15,
+        ROUND( AVG(ld.xpd15) ) AS xpd15,
+        ROUND( AVG(ld.cd15), 1 ) AS cd15,
 
         100*( SUM(p.kills) + SUM(p.assists) )/SUM(t.ckill) AS kill_participation,
         100*( SUM(p.deaths) )/SUM(ot.enemy_kills) AS death_participation,
@@ -131,8 +165,9 @@ JOIN enemy_team ot
       JOIN team_damage td
         ON td.game_id = p.game_id
         AND td.team_id = p.team_id
-LEFT JOIN ( SELECT DISTINCT game_id, name, cd10 FROM cs_diff ) c ON c.name = p.name AND c.game_id = p.game_id
+LEFT JOIN ( SELECT DISTINCT game_id, puuid, cd10 FROM cs_diff ) c ON c.puuid = p.puuid AND c.game_id = p.game_id
+LEFT JOIN lane_diff ld ON ld.game_id = p.game_id AND ld.slot = p.slot
       WHERE ( ?1 IS NULL OR p.team_name = ?1) AND (?2 IS NULL OR p.role = ?2 )
-      GROUP BY p.name, p.role
+      GROUP BY p.puuid, p.role
       HAVING ( ?3 IS NULL OR games >= CAST( ?3 AS INTEGER ) )
 
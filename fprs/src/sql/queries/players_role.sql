@@ -1,34 +1,35 @@
 WITH participants AS (
     SELECT
-        g.id AS game_id,
-        json_extract(p.value, '$.riotIdGameName') AS player_name,
-        json_extract(p.value, '$.championName') AS champion,
-        json_extract(p.value, '$.teamPosition') AS role,
-        json_extract(p.value, '$.win') AS win,
-        json_extract(p.value, '$.teamId') AS team_id,
-        json_extract(p.value, '$.kills') AS kills,
-        json_extract(p.value, '$.deaths') AS deaths,
-        json_extract(p.value, '$.assists') AS assists,
-        json_extract(p.value, '$.goldEarned') AS gold,
-        json_extract(p.value, '$.totalDamageDealtToChampions') AS damage,
-    ( CASE WHEN json_extract(p.value, '$.totalDamageDealtToChampions') IS NULL THEN 0 ELSE json_extract(g.data, '$.info.gameDuration') END ) AS game_length_dmg,
-        COALESCE( json_extract(p.value, '$.challenges.laneMinionsFirst10Minutes'), 0 ) + CAST(COALESCE( json_extract(p.value, '$.challenges.jungleCsBefore10Minutes') , 0) AS INTEGER)     AS cs10,
-        json_extract(g.data, '$.info.gameDuration') AS game_length
-
-    FROM game g
-    JOIN json_each(g.data, '$.info.participants') p
+        p.game_id,
+        p.player_puuid AS puuid,
+        COALESCE(pl.riot_name, p.riot_name) AS player_name,
+        p.champion,
+        p.role,
+        p.win,
+        p.team_id,
+        p.kills,
+        p.deaths,
+        p.assists,
+        p.gold,
+        p.damage,
+        ( CASE WHEN p.damage IS NULL THEN 0 ELSE g.duration END ) AS game_length_dmg,
+        COALESCE( p.lane_minions_first_10, 0 ) + CAST(COALESCE( p.jungle_cs_before_10, 0) AS INTEGER) AS cs10,
+        g.duration AS game_length
+    FROM participant p
+    JOIN game g ON g.id = p.game_id
+    LEFT JOIN player pl ON pl.puuid = p.player_puuid
 ),
 cs_diff AS (
   SELECT
     p.game_id,
-    p.player_name,
+    p.puuid,
     AVG(p.cs10 - opp.cs10) AS cd10
   FROM participants p
   JOIN participants opp
     ON p.game_id = opp.game_id
    AND p.role = opp.role
    AND p.team_id != opp.team_id
-  GROUP BY p.game_id, p.player_name
+  GROUP BY p.game_id, p.puuid
 )
 
 
@@ -43,6 +44,7 @@ SELECT
   ROW_NUMBER() OVER (PARTITION BY role ORDER BY game_length DESC) AS gln, 
   COUNT(*) OVER (PARTITION BY role) as role_total,
   * FROM ( SELECT
+  p.puuid as puuid,
   p.player_name as player_name,
   p.role as role,
   COUNT(*) as games,
@@ -67,7 +69,7 @@ SELECT
       CAST( CAST(SUM(COALESCE( p.damage, 0 )) AS INTEGER)/       ( SUM(p.game_length_dmg) / 60) AS INTEGER ) as dpm,
 ROUND( AVG(c.cd10) , 1) AS cd10,
         printf('%d:%02d', AVG(p.game_length) / 60, AVG(p.game_length) % 60) AS game_length
-FROM participants p LEFT JOIN cs_diff c ON c.player_name = p.player_name AND c.game_id = p.game_id
-GROUP BY p.player_name, p.role HAVING games >= 4 
+FROM participants p LEFT JOIN cs_diff c ON c.puuid = p.puuid AND c.game_id = p.game_id
+GROUP BY p.puuid, p.role HAVING games >= 4 
 ORDER BY games DESC, win_percent DESC 
  );
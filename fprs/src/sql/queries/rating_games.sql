@@ -0,0 +1,17 @@
+-- Every player in every game, in the order games are rated: by end
+-- timestamp, falling back on the date for manual games. Players
+-- `refresh_players` hasn't reached yet get the key it would give them.
+SELECT
+    g.id                                AS game_id,
+    p.team_id                           AS team_id,
+    NULLIF(CASE WHEN p.team_id = 100 THEN g.team_1 ELSE g.team_2 END, '') AS team,
+    COALESCE(p.player_puuid, NULLIF(p.puuid, ''), 'name:' || COALESCE(p.riot_name, '')) AS puuid,
+    COALESCE(p.role, '')                AS role,
+    CASE WHEN p.win = TRUE THEN 1 ELSE 0 END AS win
+FROM participant p
+JOIN game g ON g.id = p.game_id
+ORDER BY
+    COALESCE(g.end_timestamp / 1000, CAST(strftime('%s', g.end_date) AS INTEGER)),
+    g.game_number,
+    g.id,
+    p.slot;
@@ -0,0 +1,34 @@
+UPDATE participant SET player_puuid = CASE
+    WHEN puuid IS NOT NULL AND puuid != '' THEN puuid
+    ELSE COALESCE(
+        (SELECT a.puuid FROM temp.alias_file a WHERE a.riot_name = participant.riot_name),
+        'name:' || COALESCE(riot_name, '')
+    )
+END;
+
+DELETE FROM player_alias;
+DELETE FROM player;
+
+CREATE TEMP VIEW IF NOT EXISTS player_game AS
+SELECT
+    p.player_puuid,
+    p.riot_name,
+    COALESCE(NULLIF(p.tag_line, 'null'), '') AS tag_line,
+    COALESCE(date(g.end_timestamp / 1000, 'unixepoch', 'localtime'), g.end_date) AS game_date,
+    ROW_NUMBER() OVER (
+        PARTITION BY p.player_puuid
+        ORDER BY COALESCE(g.end_timestamp / 1000, CAST(strftime('%s', g.end_date) AS INTEGER)) DESC, g.id DESC
+    ) AS recency
+FROM participant p
+JOIN game g ON g.id = p.game_id
+WHERE p.riot_name IS NOT NULL;
+
+INSERT INTO player (puuid, riot_name, tag_line)
+SELECT player_puuid, riot_name, NULLIF(tag_line, '')
+FROM temp.player_game
+WHERE recency = 1;
+
+INSERT INTO player_alias (puuid, riot_name, tag_line, games, first_seen, last_seen)
+SELECT player_puuid, riot_name, tag_line, COUNT(*), MIN(game_date), MAX(game_date)
+FROM temp.player_game
+GROUP BY player_puuid, riot_name, tag_line;
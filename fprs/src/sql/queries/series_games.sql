@@ -0,0 +1,31 @@
+WITH
+winners AS (
+    SELECT game_id, team_id
+    FROM team_side
+    WHERE win = TRUE
+)
+
+SELECT
+    g.id     AS game_id,
+    g.team_1 AS team_1,
+    g.team_2 AS team_2,
+    CASE w.team_id
+        WHEN 100 THEN g.team_1
+        WHEN 200 THEN g.team_2
+    END AS winner,
+    -- API games carry an end timestamp, manual games only the date they were played
+    COALESCE(
+        date(g.end_timestamp / 1000, 'unixepoch', 'localtime'),
+        g.end_date
+    ) AS game_date,
+    g.end_timestamp    AS end_timestamp,
+    g.series_id        AS series_id,
+    g.game_number      AS game_number,
+    s.best_of          AS best_of,
+    s.scheduled_date   AS series_date,
+    s.stage            AS stage
+FROM game g
+JOIN winners w ON w.game_id = g.id
+LEFT JOIN series s ON s.id = g.series_id
+WHERE g.team_1 IS NOT NULL AND g.team_2 IS NOT NULL
+ORDER BY game_date, end_timestamp, g.game_number, g.id;
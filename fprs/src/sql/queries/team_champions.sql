@@ -0,0 +1,11 @@
+-- Champions a team has picked in each role, most picked first
+SELECT
+    p.role     AS role,
+    p.champion AS champion,
+    COUNT(*)   AS picks
+FROM participant p
+JOIN game g ON g.id = p.game_id
+WHERE (CASE WHEN p.team_id = 100 THEN g.team_1 ELSE g.team_2 END) = ?1
+  AND p.role != ''
+GROUP BY p.role, p.champion
+ORDER BY picks DESC, p.champion;
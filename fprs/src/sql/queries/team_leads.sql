@@ -0,0 +1,11 @@
+-- Blue side's gold and XP lead over red on each minute of every stored
+-- timeline.
+SELECT
+    f.game_id,
+    f.minute,
+    SUM(CASE WHEN p.team_id = 100 THEN f.total_gold ELSE -f.total_gold END) AS gold,
+    SUM(CASE WHEN p.team_id = 100 THEN f.xp ELSE -f.xp END) AS xp
+FROM timeline_frame f
+JOIN participant p ON p.game_id = f.game_id AND p.slot = f.slot
+GROUP BY f.game_id, f.minute
+ORDER BY f.game_id, f.minute
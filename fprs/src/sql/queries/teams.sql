@@ -0,0 +1,58 @@
+WITH
+team_totals AS (
+    SELECT
+        game_id,
+        team_id,
+        SUM(kills)   AS kills,
+        SUM(deaths)  AS deaths,
+        SUM(assists) AS assists,
+        SUM(gold)    AS gold
+    FROM participant
+    GROUP BY game_id, team_id
+),
+
+teams AS (
+    SELECT
+        g.id AS game_id,
+        t.team_id,
+        (CASE WHEN t.team_id = 100 THEN g.team_1 ELSE g.team_2 END) AS team_name,
+        t.win,
+        g.duration AS game_length,
+        t.first_blood  AS fb,
+        t.first_tower  AS ft,
+        t.first_dragon AS fd,
+        t.horde_kills  AS grubs
+    FROM game g
+    JOIN team_side t ON t.game_id = g.id
+)
+
+SELECT
+    t.team_name AS team_name,
+    COUNT(*)                                          AS games,
+    SUM(t.win)                                        AS wins,
+    COUNT(*) - SUM(t.win)                             AS losses,
+    SUM(t.win) * 100.0 / COUNT(*)                     AS win_percentage,
+    SUM(CASE WHEN t.team_id = 100 THEN 1 ELSE 0 END)  AS blue_games,
+    SUM(CASE WHEN t.team_id = 100 THEN t.win ELSE 0 END) AS blue_wins,
+    SUM(CASE WHEN t.team_id = 200 THEN 1 ELSE 0 END)  AS red_games,
+    SUM(CASE WHEN t.team_id = 200 THEN t.win ELSE 0 END) AS red_wins,
+    CAST(ROUND(AVG(t.game_length), 0) AS INTEGER)     AS game_length_avg,
+    AVG(tt.kills)                                     AS kills,
+    AVG(tt.deaths)                                    AS deaths,
+    SUM(tt.kills) * 1.0 /
+    (CASE
+        WHEN SUM(tt.deaths) = 0 THEN 1 ELSE SUM(tt.deaths)
+    END)                                              AS kd,
+    SUM(tt.gold) * 1.0 / (SUM(t.game_length) / 60.0)  AS gpm,
+    -- Objective flags are missing from manual games, so rates only count games that have them
+    SUM(CASE WHEN t.fb = TRUE THEN 1 ELSE 0 END) * 100.0 / NULLIF(COUNT(t.fb), 0) AS fb_percentage,
+    SUM(CASE WHEN t.ft = TRUE THEN 1 ELSE 0 END) * 100.0 / NULLIF(COUNT(t.ft), 0) AS ft_percentage,
+    SUM(CASE WHEN t.fd = TRUE THEN 1 ELSE 0 END) * 100.0 / NULLIF(COUNT(t.fd), 0) AS fd_percentage,
+    AVG(t.grubs)                                      AS grubs
+FROM teams t
+JOIN team_totals tt
+  ON tt.game_id = t.game_id
+ AND tt.team_id = t.team_id
+WHERE t.team_name IS NOT NULL
+GROUP BY t.team_name
+ORDER BY win_percentage DESC, games DESC;
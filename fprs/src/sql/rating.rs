@@ -0,0 +1,322 @@
+use std::{collections::HashMap, f64::consts::PI};
+
+use crate::sql::schema::{Rating, RatingParticipant};
+
+/// Glicko-2's scale factor between its internal scale and the familiar
+/// 1500-centred one.
+const SCALE: f64 = 173.7178;
+/// How much volatility can change each game. Glickman suggests 0.3 to 1.2,
+/// lower for more predictable competitions.
+const TAU: f64 = 0.5;
+const EPSILON: f64 = 0.000001;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RatingKind {
+    Team,
+    Player,
+}
+
+impl RatingKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Team => "team",
+            Self::Player => "player",
+        }
+    }
+}
+
+/// A Glicko-2 rating on the 1500-centred scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glicko {
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+}
+
+impl Default for Glicko {
+    fn default() -> Self {
+        Self {
+            rating: 1500.0,
+            deviation: 350.0,
+            volatility: 0.06,
+        }
+    }
+}
+
+impl Glicko {
+    /// The rating after a period against `results`, each an opponent's rating
+    /// going in and the score against them: 1 for a win, 0 for a loss.
+    ///
+    /// Follows Glickman's "Example of the Glicko-2 system", steps 2 to 8.
+    pub fn update(self, results: &[(Glicko, f64)]) -> Glicko {
+        let mu = (self.rating - 1500.0) / SCALE;
+        let phi = self.deviation / SCALE;
+        if results.is_empty() {
+            let phi = (phi.powi(2) + self.volatility.powi(2)).sqrt();
+            return Glicko {
+                deviation: phi * SCALE,
+                ..self
+            };
+        }
+
+        let g = |phi: f64| 1.0 / (1.0 + 3.0 * phi.powi(2) / PI.powi(2)).sqrt();
+        let outcomes: Vec<(f64, f64, f64)> = results
+            .iter()
+            .map(|(opponent, score)| {
+                let g = g(opponent.deviation / SCALE);
+                let e = 1.0 / (1.0 + (-g * (mu - (opponent.rating - 1500.0) / SCALE)).exp());
+                (g, e, *score)
+            })
+            .collect();
+        let v = 1.0
+            / outcomes
+                .iter()
+                .map(|(g, e, _)| g * g * e * (1.0 - e))
+                .sum::<f64>();
+        let improvement: f64 = outcomes.iter().map(|(g, e, s)| g * (s - e)).sum();
+        let delta = v * improvement;
+
+        // Step 5, the new volatility by the Illinois algorithm
+        let a = self.volatility.powi(2).ln();
+        let f = |x: f64| {
+            let ex = x.exp();
+            ex * (delta.powi(2) - phi.powi(2) - v - ex) / (2.0 * (phi.powi(2) + v + ex).powi(2))
+                - (x - a) / TAU.powi(2)
+        };
+        let mut lower = a;
+        let mut upper = match delta.powi(2) > phi.powi(2) + v {
+            true => (delta.powi(2) - phi.powi(2) - v).ln(),
+            false => {
+                let mut k = 1.0;
+                while f(a - k * TAU) < 0.0 {
+                    k += 1.0;
+                }
+                a - k * TAU
+            }
+        };
+        let (mut f_lower, mut f_upper) = (f(lower), f(upper));
+        while (upper - lower).abs() > EPSILON {
+            let c = lower + (lower - upper) * f_lower / (f_upper - f_lower);
+            let f_c = f(c);
+            if f_c * f_upper <= 0.0 {
+                lower = upper;
+                f_lower = f_upper;
+            } else {
+                f_lower /= 2.0;
+            }
+            upper = c;
+            f_upper = f_c;
+        }
+        let volatility = (lower / 2.0).exp();
+
+        let phi_star = (phi.powi(2) + volatility.powi(2)).sqrt();
+        let phi = 1.0 / (1.0 / phi_star.powi(2) + 1.0 / v).sqrt();
+        let mu = mu + phi.powi(2) * improvement;
+        Glicko {
+            rating: mu * SCALE + 1500.0,
+            deviation: phi * SCALE,
+            volatility,
+        }
+    }
+}
+
+/// Rate every game in order, `participants` being each game's players one
+/// after another as `rating_games.sql` gives them. Each game is its own
+/// rating period, so the same games always give the same history.
+///
+/// Teams are rated when both sides have one. Players are rated against the
+/// player in the same role on the other side, so each role is rated among
+/// itself and a support's rating is measured against other supports.
+pub fn rate(participants: &[RatingParticipant]) -> Vec<Rating> {
+    let mut current: HashMap<(RatingKind, String), Glicko> = HashMap::new();
+    let mut history = Vec::new();
+
+    for (seq, game) in participants
+        .chunk_by(|a, b| a.game_id == b.game_id)
+        .enumerate()
+    {
+        let mut results: Vec<((RatingKind, String), Glicko, f64)> = Vec::new();
+        let rating = |key: &(RatingKind, String)| current.get(key).copied().unwrap_or_default();
+
+        let team = |team_id: u64| {
+            game.iter()
+                .find(|p| p.team_id == team_id)
+                .and_then(|p| p.team.clone())
+        };
+        if let (Some(blue), Some(red)) = (team(100), team(200))
+            && blue != red
+        {
+            let blue_won = game.iter().any(|p| p.team_id == 100 && p.win);
+            let blue = (RatingKind::Team, blue);
+            let red = (RatingKind::Team, red);
+            let (blue_rating, red_rating) = (rating(&blue), rating(&red));
+            results.push((blue, red_rating, blue_won as u8 as f64));
+            results.push((red, blue_rating, !blue_won as u8 as f64));
+        }
+
+        for player in game {
+            let Some(puuid) = &player.puuid else {
+                continue;
+            };
+            let opponent = game.iter().find(|o| {
+                o.team_id != player.team_id && !o.role.is_empty() && o.role == player.role
+            });
+            if let Some(Some(opponent)) = opponent.map(|o| &o.puuid) {
+                let opponent = rating(&(RatingKind::Player, opponent.clone()));
+                results.push((
+                    (RatingKind::Player, puuid.clone()),
+                    opponent,
+                    player.win as u8 as f64,
+                ));
+            }
+        }
+
+        // Everyone is updated from the ratings going into the game
+        let updates: Vec<_> = results
+            .into_iter()
+            .map(|(key, opponent, score)| {
+                let updated = rating(&key).update(&[(opponent, score)]);
+                (key, updated)
+            })
+            .collect();
+        for (key, updated) in updates {
+            history.push(Rating {
+                kind: key.0,
+                subject: key.1.clone(),
+                game_id: game[0].game_id,
+                seq: seq as u64,
+                rating: updated.rating,
+                deviation: updated.deviation,
+                volatility: updated.volatility,
+            });
+            current.insert(key, updated);
+        }
+    }
+
+    history
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+
+    use super::*;
+    use crate::sql::{migrations, repo};
+
+    fn glicko(rating: f64, deviation: f64) -> Glicko {
+        Glicko {
+            rating,
+            deviation,
+            volatility: 0.06,
+        }
+    }
+
+    #[test]
+    fn update_matches_glickmans_example() {
+        let updated = glicko(1500.0, 200.0).update(&[
+            (glicko(1400.0, 30.0), 1.0),
+            (glicko(1550.0, 100.0), 0.0),
+            (glicko(1700.0, 300.0), 0.0),
+        ]);
+        assert!((updated.rating - 1464.06).abs() < 0.01, "{updated:?}");
+        assert!((updated.deviation - 151.52).abs() < 0.01, "{updated:?}");
+        assert!(
+            (updated.volatility - 0.05999).abs() < 0.00001,
+            "{updated:?}"
+        );
+    }
+
+    #[test]
+    fn update_without_games_only_widens_the_deviation() {
+        let updated = glicko(1600.0, 50.0).update(&[]);
+        assert_eq!(updated.rating, 1600.0);
+        assert_eq!(updated.volatility, 0.06);
+        let phi = ((50.0 / SCALE).powi(2) + 0.06f64.powi(2)).sqrt();
+        assert!((updated.deviation - phi * SCALE).abs() < 1e-9);
+    }
+
+    /// Tigers beat Eagles in game 1 and lose game 2, both ending at the same
+    /// moment, stored in `order`.
+    fn games(order: &[u64]) -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrations::migrate(&mut conn).unwrap();
+        for &id in order {
+            conn.execute(
+                "INSERT INTO game (id, data, team_1, team_2, end_timestamp)
+                 VALUES (?1, '{}', 'Tigers', 'Eagles', 1700000000000)",
+                [id],
+            )
+            .unwrap();
+            for (slot, team_id, puuid) in [(0, 100, "tiger-mid"), (1, 200, "eagle-mid")] {
+                conn.execute(
+                    "INSERT INTO participant (game_id, slot, puuid, team_id, role, win)
+                     VALUES (?1, ?2, ?3, ?4, 'MIDDLE', ?5)",
+                    (id, slot, puuid, team_id, (team_id == 100) == (id == 1)),
+                )
+                .unwrap();
+            }
+        }
+        conn
+    }
+
+    fn rated(order: &[u64]) -> Vec<(RatingKind, String, u64, u64, f64, f64)> {
+        let conn = games(order);
+        let participants: Vec<RatingParticipant> = conn
+            .prepare(include_str!("queries/rating_games.sql"))
+            .unwrap()
+            .query_map([], |row| RatingParticipant::try_from(row))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        rate(&participants)
+            .into_iter()
+            .map(|r| (r.kind, r.subject, r.game_id, r.seq, r.rating, r.deviation))
+            .collect()
+    }
+
+    #[test]
+    fn rate_breaks_ties_by_game_id_whatever_order_games_are_stored_in() {
+        let forwards = rated(&[1, 2]);
+        let backwards = rated(&[2, 1]);
+        assert_eq!(forwards, backwards);
+        assert_eq!(forwards, rated(&[1, 2]));
+
+        let games: Vec<(u64, u64)> = forwards.iter().map(|r| (r.2, r.3)).collect();
+        assert_eq!(
+            games,
+            [
+                (1, 0),
+                (1, 0),
+                (1, 0),
+                (1, 0),
+                (2, 1),
+                (2, 1),
+                (2, 1),
+                (2, 1)
+            ]
+        );
+        // Teams then players, each in slot order
+        let subjects: Vec<&str> = forwards[..4].iter().map(|r| r.1.as_str()).collect();
+        assert_eq!(subjects, ["Tigers", "Eagles", "tiger-mid", "eagle-mid"]);
+        // Tigers won the first game
+        assert!(forwards[0].4 > 1500.0 && forwards[1].4 < 1500.0);
+    }
+
+    #[test]
+    fn renumbering_a_game_clears_the_stored_ratings() {
+        let conn = games(&[1, 2]);
+        let stored = || -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM rating", [], |row| row.get(0))
+                .unwrap()
+        };
+        repo::recompute_ratings(&conn).unwrap();
+        assert_eq!(stored(), 8);
+
+        conn.execute("UPDATE game SET game_number = game_number WHERE id = 1", [])
+            .unwrap();
+        assert_eq!(stored(), 8);
+        conn.execute("UPDATE game SET game_number = 3 WHERE id = 1", [])
+            .unwrap();
+        assert_eq!(stored(), 0);
+    }
+}
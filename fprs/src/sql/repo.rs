@@ -1,53 +1,54 @@
+use crate::riot::model::{InfoDto, MatchDto, ObjectiveDto, TimelineDto};
+use crate::sql::filter::GameFilter;
+use crate::sql::rating::{self, RatingKind};
+use crate::sql::roster::{self, Sides};
 use crate::sql::schema::{
-    self, ChampionHistory, ChampionStats, Game, OverallStats, PlayerChampionStats,
-    PlayerOverallStats, PlayerRoleStats, PlayerStats,
+    self, ChampionHistory, ChampionStats, DraftEntry, Game, GameMetadata, GameTimeline, HeadToHead,
+    MatchSeries, Matchup, ObjectiveEvent, OverallStats, PairGame, PlayerChampionStats,
+    PlayerOverallStats, PlayerRoleStats, PlayerStats, Rating, RatingParticipant, RosterEntry,
+    Series, SeriesGame, SharedGame, StoredGame, TeamDraftTendency, TeamLead, TeamProfile,
+    TeamRoleLine, TeamStats, UnreadableGame,
 };
-use rusqlite::{Connection, Result};
+use crate::sql::series;
+use rusqlite::{Connection, OptionalExtension, Result};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+/// Open the database, creating its directory if needed. Run
+/// `migrations::migrate` on the connection before using it.
 pub fn init_db(path: &Path) -> Result<Connection> {
-    if path.exists() {
-        return Connection::open(path);
-    }
-
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).expect("Failed to create config directory");
     }
 
-    let conn = Connection::open(path)?;
-
-    conn.execute_batch(
-        r#"
-        CREATE TABLE IF NOT EXISTS game (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            data TEXT CHECK (json_valid(data)),
-            manual INTEGER NOT NULL DEFAULT 0,
-            team_1 TEXT,
-            2 TEXT,
-        )
-        "#,
-    )?;
-
-    Ok(conn)
+    Connection::open(path)
 }
 
 pub fn delete_game(conn: &Connection, game_id: u64) -> Result<()> {
-    conn.execute("DELETE FROM game WHERE id=?1", [game_id])?;
-    Ok(())
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM participant WHERE game_id=?1", [game_id])?;
+    tx.execute("DELETE FROM team_side WHERE game_id=?1", [game_id])?;
+    tx.execute("DELETE FROM draft WHERE game_id=?1", [game_id])?;
+    tx.execute("DELETE FROM timeline_frame WHERE game_id=?1", [game_id])?;
+    tx.execute("DELETE FROM timeline WHERE game_id=?1", [game_id])?;
+    tx.execute("DELETE FROM game WHERE id=?1", [game_id])?;
+    tx.commit()
 }
 
 pub fn insert_game(conn: &Connection, game_id: &str, json: &Value) -> Result<()> {
-    conn.execute(
+    let game = parse_match(json)?;
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
         r#"
     INSERT INTO game (id, data)
     VALUES (?1, ?2)
     "#,
         (game_id, json.to_string()),
     )?;
-    Ok(())
+    index_game(&tx, tx.last_insert_rowid(), &game.info)?;
+    tx.commit()
 }
 
 pub fn update_team(conn: &Connection, game_id: u64, team_name: &str, team_no: u8) -> Result<()> {
@@ -63,6 +64,29 @@ pub fn update_team(conn: &Connection, game_id: u64, team_name: &str, team_no: u8
     Ok(())
 }
 
+/// Overwrite the hand-editable details of a stored game.
+pub fn update_game_metadata(conn: &Connection, game_id: u64, meta: &GameMetadata) -> Result<()> {
+    let blank_to_null = |s: &str| Some(s.trim()).filter(|s| !s.is_empty()).map(String::from);
+    conn.execute(
+        r#"
+    UPDATE game
+    SET team_1=?1, team_2=?2, manual=?3, series_id=?4, game_number=?5, patch=?6, notes=?7
+    WHERE id=?8
+    "#,
+        (
+            blank_to_null(&meta.team_1),
+            blank_to_null(&meta.team_2),
+            meta.manual,
+            meta.series_id,
+            meta.game_number,
+            meta.patch.as_deref().and_then(blank_to_null),
+            meta.notes.as_deref().and_then(blank_to_null),
+            game_id,
+        ),
+    )?;
+    Ok(())
+}
+
 pub fn insert_game_with_teams(
     conn: &Connection,
     game_id: &str,
@@ -70,16 +94,483 @@ pub fn insert_game_with_teams(
     team_1: &str,
     team_2: &str,
 ) -> Result<()> {
-    conn.execute(
+    let game = parse_match(json)?;
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
         r#"
     INSERT INTO game (id, data, team_1, team_2)
     VALUES (?1, ?2, ?3, ?4)
     "#,
         (game_id, json.to_string(), team_1, team_2),
     )?;
+    index_game(&tx, tx.last_insert_rowid(), &game.info)?;
+    tx.commit()
+}
+
+/// Insert or replace a manually entered game, keeping its teams and series.
+pub fn upsert_manual_game(
+    conn: &Connection,
+    game_id: u64,
+    json: &Value,
+    info: &InfoDto,
+) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        r#"
+    INSERT INTO game (id, data, manual)
+    VALUES (?1, ?2, 1)
+    ON CONFLICT(id) DO UPDATE
+    SET data = excluded.data
+    "#,
+        (game_id, json.to_string()),
+    )?;
+    index_game(&tx, game_id as i64, info)?;
+    tx.commit()
+}
+
+/// The stored copy of a game, or `None` if there's no such game.
+pub fn stored_game(conn: &Connection, game_id: u64) -> Result<Option<StoredGame>> {
+    conn.query_row(
+        "SELECT manual, data, team_1, team_2, series_id FROM game WHERE id=?1",
+        [game_id],
+        |row| StoredGame::try_from(row),
+    )
+    .optional()
+}
+
+/// Set a game's teams, leaving any already assigned alone.
+pub fn fill_teams(
+    conn: &Connection,
+    game_id: u64,
+    team_1: Option<&str>,
+    team_2: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        r#"
+    UPDATE game SET
+        team_1 = COALESCE(NULLIF(team_1, ''), ?1),
+        team_2 = COALESCE(NULLIF(team_2, ''), ?2)
+    WHERE id=?3
+    "#,
+        (team_1, team_2, game_id),
+    )?;
+    Ok(())
+}
+
+/// The latest series between two teams, in either order, on a date.
+pub fn find_series(
+    conn: &Connection,
+    team_1: &str,
+    team_2: &str,
+    scheduled_date: Option<&str>,
+) -> Result<Option<i64>> {
+    conn.query_row(
+        r#"
+    SELECT id FROM series
+    WHERE ((team_1=?1 AND team_2=?2) OR (team_1=?2 AND team_2=?1))
+        AND scheduled_date IS ?3
+    ORDER BY id DESC
+    LIMIT 1
+    "#,
+        (team_1, team_2, scheduled_date),
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+fn parse_match(json: &Value) -> Result<MatchDto> {
+    MatchDto::from_value(json).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+/// Fill the `participant` and `team_side` rows and the game's summary columns
+/// from its payload, replacing whatever was there. Call this whenever
+/// `game.data` is written.
+pub fn index_game(conn: &Connection, game_id: i64, info: &InfoDto) -> Result<()> {
+    conn.execute(
+        "UPDATE game SET duration=?1, end_timestamp=?2, end_date=?3 WHERE id=?4",
+        (
+            info.game_duration,
+            info.game_end_timestamp,
+            &info.game_end_date,
+            game_id,
+        ),
+    )?;
+    conn.execute("DELETE FROM team_side WHERE game_id=?1", [game_id])?;
+    conn.execute("DELETE FROM participant WHERE game_id=?1", [game_id])?;
+
+    let mut insert_team = conn.prepare(
+        r#"
+    INSERT INTO team_side (
+        game_id, team_id, win,
+        champion_kills, first_blood, tower_kills, first_tower, dragon_kills, first_dragon,
+        baron_kills, horde_kills, rift_herald_kills, inhibitor_kills, atakhan_kills
+    )
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+    "#,
+    )?;
+    for team in &info.teams {
+        let o = &team.objectives;
+        let kills = |obj: &Option<ObjectiveDto>| obj.as_ref().map(|o| o.kills);
+        let first = |obj: &Option<ObjectiveDto>| obj.as_ref().and_then(|o| o.first);
+        insert_team.execute(rusqlite::params![
+            game_id,
+            team.team_id,
+            team.win,
+            kills(&o.champion),
+            first(&o.champion),
+            kills(&o.tower),
+            first(&o.tower),
+            kills(&o.dragon),
+            first(&o.dragon),
+            kills(&o.baron),
+            kills(&o.horde),
+            kills(&o.rift_herald),
+            kills(&o.inhibitor),
+            kills(&o.atakhan),
+        ])?;
+    }
+
+    let mut insert_participant = conn.prepare(
+        r#"
+    INSERT INTO participant (
+        game_id, slot, puuid, riot_name, tag_line, champion, team_id, role, win,
+        kills, deaths, assists, minions_killed, neutral_minions_killed,
+        ally_jungle_minions_killed, enemy_jungle_minions_killed, gold, damage, vision_score,
+        first_blood_kill, first_blood_assist, first_tower_kill, first_tower_assist,
+        solo_kills, lane_minions_first_10, jungle_cs_before_10, champion_id
+    )
+    VALUES (
+        ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19,
+        ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27
+    )
+    "#,
+    )?;
+    // Keep the names bans are shown with in step with the ones picks use
+    let mut insert_champion =
+        conn.prepare("INSERT OR REPLACE INTO champion (id, name) VALUES (?1, ?2)")?;
+    for (slot, p) in info.participants.iter().enumerate() {
+        if let Some(id) = p.champion_id {
+            insert_champion.execute((id, &p.champion_name))?;
+        }
+        let challenges = p.challenges.as_ref();
+        insert_participant.execute(rusqlite::params![
+            game_id,
+            slot,
+            p.puuid,
+            p.riot_id_game_name,
+            p.riot_id_tagline,
+            p.champion_name,
+            p.team_id,
+            p.team_position,
+            p.win,
+            p.kills,
+            p.deaths,
+            p.assists,
+            p.total_minions_killed,
+            p.neutral_minions_killed,
+            p.total_ally_jungle_minions_killed,
+            p.total_enemy_jungle_minions_killed,
+            p.gold_earned,
+            p.total_damage_dealt_to_champions,
+            p.vision_score,
+            p.first_blood_kill,
+            p.first_blood_assist,
+            p.first_tower_kill,
+            p.first_tower_assist,
+            challenges.and_then(|c| c.solo_kills),
+            challenges.and_then(|c| c.lane_minions_first10_minutes),
+            challenges.and_then(|c| c.jungle_cs_before10_minutes),
+            p.champion_id,
+        ])?;
+    }
+
+    // A draft entered by hand has the bans already, and in order
+    conn.execute("DELETE FROM draft WHERE game_id=?1 AND manual=0", [game_id])?;
+    let manual_draft: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM draft WHERE game_id=?1)",
+        [game_id],
+        |row| row.get(0),
+    )?;
+    if !manual_draft {
+        let mut insert_ban = conn.prepare(
+            r#"
+    INSERT OR IGNORE INTO draft (game_id, seq, team_id, action, champion_id)
+    VALUES (?1, ?2, ?3, 'ban', ?4)
+    "#,
+        )?;
+        for team in &info.teams {
+            // A ban of -1 means the team didn't ban
+            for ban in team.bans.iter().filter(|b| b.champion_id > 0) {
+                insert_ban.execute((game_id, ban.pick_turn, team.team_id, ban.champion_id))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A game's champion select in order, empty if nothing was recorded.
+pub fn game_draft(conn: &Connection, game_id: u64) -> Result<Vec<DraftEntry>> {
+    let mut stmt = conn.prepare(
+        r#"
+SELECT
+    d.seq, d.team_id, d.action, d.manual,
+    COALESCE(d.champion, c.name) AS champion
+FROM draft d
+LEFT JOIN champion c ON c.id = d.champion_id
+WHERE d.game_id = ?1
+ORDER BY d.seq"#,
+    )?;
+
+    stmt.query_map([game_id], |row| DraftEntry::try_from(row))?
+        .collect()
+}
+
+/// Every champion name, spelled the way games store them.
+pub fn champion_names(conn: &Connection) -> Result<Vec<String>> {
+    conn.prepare("SELECT name FROM champion ORDER BY name")?
+        .query_map([], |row| row.get(0))?
+        .collect()
+}
+
+/// Replace a game's draft with one entered by hand.
+pub fn set_draft(conn: &Connection, game_id: u64, draft: &[DraftEntry]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM draft WHERE game_id=?1", [game_id])?;
+    let mut insert = tx.prepare(
+        r#"
+    INSERT INTO draft (game_id, seq, team_id, action, champion, manual)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+    "#,
+    )?;
+    for entry in draft {
+        insert.execute((
+            game_id,
+            entry.seq,
+            entry.team_id,
+            entry.action.as_str(),
+            &entry.champion,
+            entry.manual,
+        ))?;
+    }
+    drop(insert);
+    tx.commit()
+}
+
+/// Store a game's timeline and its per-minute frames, replacing any already
+/// stored.
+pub fn insert_timeline(conn: &Connection, game_id: u64, json: &Value) -> Result<()> {
+    let timeline = TimelineDto::from_value(json)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "INSERT OR REPLACE INTO timeline (game_id, data) VALUES (?1, ?2)",
+        (game_id, json.to_string()),
+    )?;
+    tx.execute("DELETE FROM timeline_frame WHERE game_id=?1", [game_id])?;
+    // The closing frame can land in the same minute as the one before it,
+    // keep the one on the minute
+    let mut insert = tx.prepare(
+        r#"
+    INSERT OR IGNORE INTO timeline_frame (game_id, minute, slot, total_gold, xp, level, cs)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+    "#,
+    )?;
+    for frame in &timeline.info.frames {
+        for p in frame.participant_frames.values() {
+            insert.execute((
+                game_id,
+                frame.timestamp / 60_000,
+                p.participant_id.saturating_sub(1),
+                p.total_gold,
+                p.xp,
+                p.level,
+                p.cs(),
+            ))?;
+        }
+    }
+    drop(insert);
+    tx.commit()
+}
+
+/// API games without a stored timeline, with the match ID to fetch it by.
+pub fn games_missing_timeline(conn: &Connection) -> Result<Vec<(u64, String)>> {
+    let mut stmt = conn.prepare(
+        r#"
+SELECT g.id, json_extract(g.data, '$.metadata.matchId') AS match_id
+FROM game g
+LEFT JOIN timeline t ON t.game_id = g.id
+WHERE g.manual = 0 AND t.game_id IS NULL AND match_id IS NOT NULL
+ORDER BY g.id"#,
+    )?;
+
+    stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect()
+}
+
+/// The lead curves and objectives of every game with a stored timeline, by
+/// game ID.
+pub fn all_timelines(conn: &Connection) -> Result<HashMap<u64, GameTimeline>> {
+    let mut timelines: HashMap<u64, GameTimeline> = HashMap::new();
+
+    let mut leads = conn.prepare(include_str!("queries/team_leads.sql"))?;
+    let rows = leads.query_map([], |row| {
+        Ok((row.get("game_id")?, TeamLead::try_from(row)?))
+    })?;
+    for row in rows {
+        let (game_id, lead) = row?;
+        timelines.entry(game_id).or_default().leads.push(lead);
+    }
+
+    let mut objectives = conn.prepare(include_str!("queries/objective_events.sql"))?;
+    let rows = objectives.query_map([], |row| {
+        Ok((row.get("game_id")?, ObjectiveEvent::try_from(row)?))
+    })?;
+    for row in rows {
+        let (game_id, objective) = row?;
+        timelines
+            .entry(game_id)
+            .or_default()
+            .objectives
+            .push(objective);
+    }
+
+    Ok(timelines)
+}
+
+/// Resolve every participant to a player and rebuild `player` and
+/// `player_alias` from scratch. Participants without a puuid are looked up in
+/// `aliases` (game name to puuid) and otherwise fall back to their name. A
+/// player's current name is the one from their most recent game.
+pub fn refresh_players(conn: &Connection, aliases: &HashMap<String, String>) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute_batch(
+        r#"
+    CREATE TEMP TABLE IF NOT EXISTS alias_file (
+        riot_name TEXT PRIMARY KEY COLLATE NOCASE,
+        puuid TEXT NOT NULL
+    );
+    DELETE FROM temp.alias_file;
+    "#,
+    )?;
+    {
+        let mut insert = tx.prepare("INSERT OR REPLACE INTO temp.alias_file VALUES (?1, ?2)")?;
+        for (name, puuid) in aliases {
+            insert.execute((name, puuid))?;
+        }
+    }
+    tx.execute_batch(include_str!("queries/refresh_players.sql"))?;
+    tx.commit()
+}
+
+pub fn insert_series(
+    conn: &Connection,
+    team_1: &str,
+    team_2: &str,
+    best_of: Option<u64>,
+    scheduled_date: Option<&str>,
+    stage: Option<&str>,
+) -> Result<i64> {
+    conn.execute(
+        r#"
+    INSERT INTO series (team_1, team_2, best_of, scheduled_date, stage)
+    VALUES (?1, ?2, ?3, ?4, ?5)
+    "#,
+        (team_1, team_2, best_of, scheduled_date, stage),
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn attach_game_to_series(
+    conn: &Connection,
+    game_id: u64,
+    series_id: Option<i64>,
+    game_number: Option<u64>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE game SET series_id=?1, game_number=?2 WHERE id=?3",
+        (series_id, game_number, game_id),
+    )?;
     Ok(())
 }
 
+pub fn all_roster(conn: &Connection) -> Result<Vec<RosterEntry>> {
+    let mut stmt =
+        conn.prepare("SELECT * FROM roster ORDER BY team, start_date, player COLLATE NOCASE")?;
+
+    stmt.query_map([], |row| RosterEntry::try_from(row))?
+        .collect()
+}
+
+pub fn insert_roster_entry(
+    conn: &Connection,
+    team: &str,
+    player: &str,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO roster (team, player, start_date, end_date) VALUES (?1, ?2, ?3, ?4)",
+        (team, player, start_date, end_date),
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn delete_roster_entry(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM roster WHERE id=?1", [id])?;
+    Ok(())
+}
+
+/// Give a game whichever teams the rosters settle, leaving any already
+/// assigned alone. Returns what the rosters said.
+pub fn assign_teams_from_roster(
+    conn: &Connection,
+    game_id: u64,
+    info: &InfoDto,
+    aliases: &HashMap<String, String>,
+) -> Result<Sides> {
+    let sides = roster::sides(info, &all_roster(conn)?, aliases);
+    fill_teams(conn, game_id, sides.blue.as_deref(), sides.red.as_deref())?;
+    Ok(sides)
+}
+
+/// Fill in teams from the rosters on every game missing one. Returns how
+/// many games were given a team.
+pub fn assign_all_teams_from_roster(
+    conn: &Connection,
+    aliases: &HashMap<String, String>,
+) -> Result<usize> {
+    let roster = all_roster(conn)?;
+    let tx = conn.unchecked_transaction()?;
+    let mut assigned = 0;
+    // Games that can't be read are reported by the game list
+    for game in all_games(&tx, &GameFilter::default())?.0 {
+        if !game.team_1.is_empty() && !game.team_2.is_empty() {
+            continue;
+        }
+        let sides = roster::sides(&game.info, &roster, aliases);
+        let blue = sides.blue.filter(|_| game.team_1.is_empty());
+        let red = sides.red.filter(|_| game.team_2.is_empty());
+        if blue.is_some() || red.is_some() {
+            fill_teams(&tx, game.id, blue.as_deref(), red.as_deref())?;
+            assigned += 1;
+        }
+    }
+    tx.commit()?;
+    Ok(assigned)
+}
+
+pub fn all_series(conn: &Connection) -> Result<Vec<MatchSeries>> {
+    let mut stmt = conn.prepare(
+        r#"
+SELECT * FROM series
+ORDER BY scheduled_date DESC, id DESC"#,
+    )?;
+
+    stmt.query_map([], |row| MatchSeries::try_from(row))?
+        .collect()
+}
+
 pub fn game_kda(conn: &Connection) -> Result<()> {
     conn.execute(
         r#"
@@ -113,21 +604,33 @@ pub fn game_count(conn: &Connection) -> Result<i64> {
     conn.query_row("SELECT COUNT(*) FROM game", [], |row| row.get(0))
 }
 
-pub fn all_games(conn: &Connection) -> rusqlite::Result<Vec<Game>> {
-    let mut stmt = conn.prepare(
-        r#"
-SELECT * FROM game
-ORDER BY json_extract(game.data, "$.info.gameEndTimestamp") DESC"#,
-    )?;
-
-    let rows = stmt.query_map([], |row| {
-        let stats = Game::try_from(row)?;
-        Ok(stats)
-    });
+/// Stored games matching `filter`, with any whose payload can't be read
+/// set aside so one bad game doesn't hide the rest.
+pub fn all_games(
+    conn: &Connection,
+    filter: &GameFilter,
+) -> rusqlite::Result<(Vec<Game>, Vec<UnreadableGame>)> {
+    let mut stmt = conn.prepare(include_str!("queries/games.sql"))?;
+
+    let rows = stmt.query_map(filter.params(), |row| match Game::try_from(row) {
+        Ok(game) => Ok(Ok(game)),
+        Err(rusqlite::Error::FromSqlConversionFailure(_, _, e)) => Ok(Err(UnreadableGame {
+            id: row.get("id")?,
+            error: e.to_string(),
+        })),
+        Err(e) => Err(e),
+    })?;
 
-    let items: Vec<Game> = rows?.collect::<Result<_>>()?;
+    let mut games = Vec::new();
+    let mut unreadable = Vec::new();
+    for row in rows {
+        match row? {
+            Ok(game) => games.push(game),
+            Err(game) => unreadable.push(game),
+        }
+    }
 
-    Ok(items)
+    Ok((games, unreadable))
 }
 
 pub fn game_by_id(conn: &Connection, id: u64) -> Result<Value> {
@@ -164,6 +667,133 @@ pub fn stats_players(
         .collect()
 }
 
+pub fn stats_teams(conn: &Connection) -> Result<Vec<schema::TeamStats>> {
+    let query_str = include_str!("queries/teams.sql");
+    let mut q = conn.prepare(query_str)?;
+
+    q.query_map([], |row| TeamStats::try_from(row))?.collect()
+}
+
+fn series_games(conn: &Connection) -> Result<Vec<SeriesGame>> {
+    let query_str = include_str!("queries/series_games.sql");
+    let mut q = conn.prepare(query_str)?;
+
+    q.query_map([], |row| SeriesGame::try_from(row))?.collect()
+}
+
+pub fn stats_series(conn: &Connection) -> Result<Vec<Series>> {
+    Ok(series::group_series(&series_games(conn)?))
+}
+
+/// Compare two teams: their meetings, their stats against everyone and a
+/// line per role for their most played player and most picked champion.
+pub fn head_to_head(conn: &Connection, teams: [&str; 2]) -> Result<HeadToHead> {
+    let team_stats = stats_teams(conn)?;
+    let role_stats = stats_player_role(conn)?;
+    let mut champions = conn.prepare(include_str!("queries/team_champions.sql"))?;
+
+    let mut profile = |team: &str| -> Result<TeamProfile> {
+        let stats = team_stats
+            .iter()
+            .find(|t| t.team_name == team)
+            .cloned()
+            .unwrap_or_else(|| TeamStats {
+                team_name: team.to_string(),
+                ..Default::default()
+            });
+        let players = stats_players(conn, [Some(team.to_string()), None, None])?;
+        let picks = champions
+            .query_map([team], |row| {
+                Ok((
+                    row.get::<_, String>("role")?,
+                    row.get::<_, String>("champion")?,
+                    row.get::<_, u64>("picks")?,
+                ))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        let roles = ["TOP", "JUNGLE", "MIDDLE", "BOTTOM", "UTILITY"]
+            .into_iter()
+            .map(|role| {
+                let player = players
+                    .iter()
+                    .filter(|p| p.role == role)
+                    .max_by_key(|p| p.games);
+                TeamRoleLine {
+                    role: role.to_string(),
+                    player: player.map(|p| p.riot_id.clone()),
+                    stats: player
+                        .and_then(|p| role_stats.get(&p.puuid))
+                        .and_then(|lines| lines.iter().find(|l| l.role == role))
+                        .cloned(),
+                    // Picks come most picked first
+                    champion: picks
+                        .iter()
+                        .find(|(r, _, _)| r == role)
+                        .map(|(_, champion, n)| (champion.clone(), *n)),
+                }
+            })
+            .collect();
+        Ok(TeamProfile { stats, roles })
+    };
+    let profiles = [profile(teams[0])?, profile(teams[1])?];
+
+    let games: Vec<SeriesGame> = series_games(conn)?
+        .into_iter()
+        .filter(|g| {
+            (g.team_1 == teams[0] && g.team_2 == teams[1])
+                || (g.team_1 == teams[1] && g.team_2 == teams[0])
+        })
+        .collect();
+
+    Ok(HeadToHead {
+        teams: profiles,
+        series: series::group_series(&games),
+        games,
+    })
+}
+
+/// Games both players were in, from the first player's side.
+pub fn shared_games(conn: &Connection, puuids: [&str; 2]) -> Result<Vec<SharedGame>> {
+    let mut q = conn.prepare(include_str!("queries/player_versus.sql"))?;
+
+    q.query_map(puuids, |row| SharedGame::try_from(row))?
+        .collect()
+}
+
+pub fn stats_draft_tendencies(conn: &Connection) -> Result<Vec<TeamDraftTendency>> {
+    let mut stmt = conn.prepare(include_str!("queries/draft_tendencies.sql"))?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, u64>(3)?,
+        ))
+    })?;
+
+    let mut tendencies: Vec<TeamDraftTendency> = Vec::new();
+    for row in rows {
+        let (team, kind, champion, times) = row?;
+        if tendencies.last().is_none_or(|t| t.team != team) {
+            tendencies.push(TeamDraftTendency {
+                team,
+                ..Default::default()
+            });
+        }
+        let tendency = tendencies.last_mut().unwrap();
+        let champion = champion.unwrap_or_default();
+        match kind.as_str() {
+            "games" => tendency.games = times,
+            "ban" => tendency.bans.push((champion, times)),
+            "banned_against" => tendency.banned_against.push((champion, times)),
+            "first_pick" => tendency.first_picks.push((champion, times)),
+            _ => {}
+        }
+    }
+    Ok(tendencies)
+}
+
 pub fn stats_all_champions(conn: &Connection) -> Result<Vec<schema::ChampionStats>> {
     let query_str = include_str!("queries/all_champs.sql");
     let mut q = conn.prepare(&(query_str.to_owned()))?;
@@ -183,13 +813,95 @@ pub fn stats_champion_history(conn: &Connection) -> Result<HashMap<String, Vec<C
     .try_fold(
         HashMap::<String, Vec<ChampionHistory>>::new(),
         |mut acc, row| {
-            let (player_name, stats) = row?;
-            acc.entry(player_name).or_default().push(stats);
+            let (puuid, stats) = row?;
+            acc.entry(puuid).or_default().push(stats);
+            Ok::<_, rusqlite::Error>(acc)
+        },
+    )
+}
+
+/// Work the rating history out again from every game and store it.
+pub fn recompute_ratings(conn: &Connection) -> Result<()> {
+    let participants = conn
+        .prepare(include_str!("queries/rating_games.sql"))?
+        .query_map([], |row| RatingParticipant::try_from(row))?
+        .collect::<Result<Vec<_>>>()?;
+    let ratings = rating::rate(&participants);
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM rating", [])?;
+    {
+        let mut insert = tx.prepare(
+            "INSERT INTO rating (kind, subject, game_id, seq, rating, deviation, volatility)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+        for r in &ratings {
+            insert.execute((
+                r.kind.as_str(),
+                &r.subject,
+                r.game_id,
+                r.seq,
+                r.rating,
+                r.deviation,
+                r.volatility,
+            ))?;
+        }
+    }
+    tx.commit()
+}
+
+/// Each team's or player's rating history, oldest first. Adding, removing
+/// or changing games clears the stored history (see the `rating` triggers),
+/// so it's worked out again here when there isn't one.
+pub fn ratings(conn: &Connection, kind: RatingKind) -> Result<HashMap<String, Vec<Rating>>> {
+    let stored: i64 = conn.query_row("SELECT COUNT(*) FROM rating", [], |row| row.get(0))?;
+    if stored == 0 {
+        recompute_ratings(conn)?;
+    }
+
+    let mut q = conn.prepare("SELECT * FROM rating WHERE kind = ?1 ORDER BY subject, seq")?;
+    q.query_map([kind.as_str()], |row| Rating::try_from(row))?
+        .try_fold(HashMap::<String, Vec<Rating>>::new(), |mut acc, row| {
+            let rating = row?;
+            acc.entry(rating.subject.clone()).or_default().push(rating);
+            Ok::<_, rusqlite::Error>(acc)
+        })
+}
+
+/// Every pair of teammates in every game, for [`crate::sql::synergy`].
+pub fn pair_games(conn: &Connection) -> Result<Vec<PairGame>> {
+    let mut q = conn.prepare(include_str!("queries/pair_games.sql"))?;
+
+    q.query_map([], |row| PairGame::try_from(row))?.collect()
+}
+
+/// Lane matchups keyed by their subject, from `champion_matchups.sql` or
+/// `player_matchups.sql`.
+fn matchups(conn: &Connection, query_str: &str) -> Result<HashMap<String, Vec<Matchup>>> {
+    let mut q = conn.prepare(query_str)?;
+
+    q.query_map([], |row| Matchup::try_from(row))?.try_fold(
+        HashMap::<String, Vec<Matchup>>::new(),
+        |mut acc, row| {
+            let matchup = row?;
+            acc.entry(matchup.subject.clone())
+                .or_default()
+                .push(matchup);
             Ok::<_, rusqlite::Error>(acc)
         },
     )
 }
 
+/// Each champion's record against each opposing champion, keyed by champion.
+pub fn stats_champion_matchups(conn: &Connection) -> Result<HashMap<String, Vec<Matchup>>> {
+    matchups(conn, include_str!("queries/champion_matchups.sql"))
+}
+
+/// Each player's record against each lane opponent, keyed by puuid.
+pub fn stats_player_matchups(conn: &Connection) -> Result<HashMap<String, Vec<Matchup>>> {
+    matchups(conn, include_str!("queries/player_matchups.sql"))
+}
+
 pub fn stats_player_overall(
     conn: &Connection,
 ) -> Result<HashMap<String, schema::PlayerOverallStats>> {
@@ -198,7 +910,7 @@ pub fn stats_player_overall(
 
     q.query_map([], |row| {
         let stats = PlayerOverallStats::try_from(row)?;
-        Ok((stats.player_name.clone(), stats))
+        Ok((stats.puuid.clone(), stats))
     })?
     .collect()
 }
@@ -211,13 +923,13 @@ pub fn stats_player_role(
 
     q.query_map([], |row| {
         let stats = PlayerRoleStats::try_from(row)?;
-        Ok((stats.player_name.clone(), stats))
+        Ok((stats.puuid.clone(), stats))
     })?
     .try_fold(
         HashMap::<String, Vec<PlayerRoleStats>>::new(),
         |mut acc, row| {
-            let (player_name, stats) = row?;
-            acc.entry(player_name).or_default().push(stats);
+            let (puuid, stats) = row?;
+            acc.entry(puuid).or_default().push(stats);
             Ok::<_, rusqlite::Error>(acc)
         },
     )
@@ -231,13 +943,13 @@ pub fn stats_player_champion(
 
     q.query_map([], |row| {
         let stats = PlayerChampionStats::try_from(row)?;
-        Ok((stats.player_name.clone(), stats))
+        Ok((stats.puuid.clone(), stats))
     })?
     .try_fold(
         HashMap::<String, Vec<PlayerChampionStats>>::new(),
         |mut acc, row| {
-            let (player_name, stats) = row?;
-            acc.entry(player_name).or_default().push(stats);
+            let (puuid, stats) = row?;
+            acc.entry(puuid).or_default().push(stats);
             Ok::<_, rusqlite::Error>(acc)
         },
     )
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use crate::riot::model::{InfoDto, ParticipantDto};
+use crate::sql::schema::RosterEntry;
+
+/// How many players on a side have to be rostered to the same team before
+/// the side is given that team.
+const MIN_PLAYERS: usize = 3;
+
+/// The teams on each side of a game, `None` where the rosters don't settle it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sides {
+    pub blue: Option<String>,
+    pub red: Option<String>,
+}
+
+/// Work out which team played on each side of a game from who was in it.
+///
+/// Each participant counts towards every team they were rostered on when the
+/// game was played. A side goes to the team with the most players, as long
+/// as that's at least [`MIN_PLAYERS`] and no other team ties it. If both
+/// sides come out as the same team neither is assigned.
+///
+/// `aliases` maps game names to puuids (see `app::Aliases`), so manual games
+/// without puuids still match puuid roster entries.
+pub fn sides(info: &InfoDto, roster: &[RosterEntry], aliases: &HashMap<String, String>) -> Sides {
+    let date = info.played_on();
+    let side = |team_id: u64| {
+        let mut votes: HashMap<&str, usize> = HashMap::new();
+        for p in info.participants.iter().filter(|p| p.team_id == team_id) {
+            let mut teams: Vec<&str> = roster
+                .iter()
+                .filter(|e| on_date(e, date.as_deref()) && is_player(e, p, aliases))
+                .map(|e| e.team.as_str())
+                .collect();
+            teams.sort();
+            teams.dedup();
+            for team in teams {
+                *votes.entry(team).or_default() += 1;
+            }
+        }
+        let best = votes.values().copied().max().unwrap_or(0);
+        let mut leaders = votes.into_iter().filter(|(_, n)| *n == best);
+        match (leaders.next(), leaders.next()) {
+            (Some((team, n)), None) if n >= MIN_PLAYERS => Some(team.to_string()),
+            _ => None,
+        }
+    };
+
+    let (blue, red) = (side(100), side(200));
+    if blue.is_some() && blue == red {
+        return Sides::default();
+    }
+    Sides { blue, red }
+}
+
+/// Whether a game played on `date` falls in the entry's range. Games without
+/// a date only match entries with no range.
+fn on_date(entry: &RosterEntry, date: Option<&str>) -> bool {
+    match date {
+        Some(date) => {
+            entry
+                .start_date
+                .as_deref()
+                .is_none_or(|start| start <= date)
+                && entry.end_date.as_deref().is_none_or(|end| date <= end)
+        }
+        None => entry.start_date.is_none() && entry.end_date.is_none(),
+    }
+}
+
+fn is_player(entry: &RosterEntry, p: &ParticipantDto, aliases: &HashMap<String, String>) -> bool {
+    let player = entry.player.as_str();
+    if !p.puuid.is_empty() && p.puuid == player {
+        return true;
+    }
+    if aliases.get(p.name()).is_some_and(|puuid| puuid == player) {
+        return true;
+    }
+    match player.split_once('#') {
+        Some((name, tag)) => {
+            name.eq_ignore_ascii_case(p.name())
+                && p.riot_id_tagline
+                    .as_deref()
+                    .is_some_and(|t| t.eq_ignore_ascii_case(tag))
+        }
+        None => player.eq_ignore_ascii_case(p.name()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Blue side is `Blue 0` to `Blue 4` and red side `Red 0` to `Red 4`,
+    /// all tagged `OCE` and without puuids, as on manual games.
+    fn game(date: Option<&str>) -> InfoDto {
+        let participants = [(100, "Blue"), (200, "Red")]
+            .into_iter()
+            .flat_map(|(team_id, side)| {
+                (0..5).map(move |i| ParticipantDto {
+                    team_id,
+                    riot_id_game_name: Some(format!("{side} {i}")),
+                    riot_id_tagline: Some("OCE".into()),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        InfoDto {
+            game_end_date: date.map(String::from),
+            participants,
+            ..Default::default()
+        }
+    }
+
+    fn entry(team: &str, player: &str) -> RosterEntry {
+        dated(team, player, None, None)
+    }
+
+    fn dated(team: &str, player: &str, start: Option<&str>, end: Option<&str>) -> RosterEntry {
+        RosterEntry {
+            id: 0,
+            team: team.into(),
+            player: player.into(),
+            start_date: start.map(String::from),
+            end_date: end.map(String::from),
+        }
+    }
+
+    fn team(team: &str, players: &[&str]) -> Vec<RosterEntry> {
+        players.iter().map(|p| entry(team, p)).collect()
+    }
+
+    fn sides_of(info: &InfoDto, roster: &[RosterEntry]) -> Sides {
+        sides(info, roster, &HashMap::new())
+    }
+
+    fn blue(team: &str) -> Sides {
+        Sides {
+            blue: Some(team.into()),
+            red: None,
+        }
+    }
+
+    #[test]
+    fn sides_need_enough_players_on_the_roster() {
+        let game = game(None);
+        let three = team("Tigers", &["Blue 0", "Blue 1", "Blue 2"]);
+        assert_eq!(sides_of(&game, &three), blue("Tigers"));
+        assert_eq!(sides_of(&game, &three[..2]), Sides::default());
+    }
+
+    #[test]
+    fn sides_go_to_the_team_with_the_most_players() {
+        let mut roster = team("Tigers", &["Blue 0", "Blue 1", "Blue 2", "Blue 3"]);
+        roster.extend(team("Eagles", &["Blue 3", "Blue 4", "Red 0"]));
+        roster.extend(team("Eagles", &["Red 1", "Red 2"]));
+        assert_eq!(
+            sides_of(&game(None), &roster),
+            Sides {
+                blue: Some("Tigers".into()),
+                red: Some("Eagles".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn sides_leave_ties_unassigned() {
+        let mut roster = team("Tigers", &["Blue 0", "Blue 1", "Blue 2"]);
+        roster.extend(team("Eagles", &["Blue 2", "Blue 3", "Blue 4"]));
+        assert_eq!(sides_of(&game(None), &roster), Sides::default());
+    }
+
+    #[test]
+    fn sides_count_a_player_once_per_team() {
+        let roster = team("Tigers", &["Blue 0", "Blue 0", "Blue 0", "Blue 1"]);
+        assert_eq!(sides_of(&game(None), &roster), Sides::default());
+    }
+
+    #[test]
+    fn sides_are_not_assigned_when_both_are_the_same_team() {
+        let roster = team(
+            "Tigers",
+            &["Blue 0", "Blue 1", "Blue 2", "Red 0", "Red 1", "Red 2"],
+        );
+        assert_eq!(sides_of(&game(None), &roster), Sides::default());
+    }
+
+    #[test]
+    fn sides_only_count_entries_covering_the_game_date() {
+        let game = game(Some("2025-06-01"));
+        let roster = [
+            dated("Tigers", "Blue 0", Some("2025-06-01"), None),
+            dated("Tigers", "Blue 1", None, Some("2025-06-01")),
+            dated("Tigers", "Blue 2", Some("2025-01-01"), Some("2025-12-31")),
+            entry("Tigers", "Blue 3"),
+            dated("Eagles", "Blue 0", None, Some("2025-05-31")),
+            dated("Eagles", "Blue 1", Some("2025-06-02"), None),
+            dated("Eagles", "Blue 2", Some("2024-01-01"), Some("2024-12-31")),
+        ];
+        assert_eq!(sides_of(&game, &roster), blue("Tigers"));
+    }
+
+    #[test]
+    fn undated_games_only_match_undated_entries() {
+        let mut roster = vec![
+            dated("Tigers", "Blue 0", Some("2025-01-01"), None),
+            dated("Tigers", "Blue 1", None, Some("2025-12-31")),
+        ];
+        roster.extend(team("Tigers", &["Blue 2", "Blue 3"]));
+        assert_eq!(sides_of(&game(None), &roster), Sides::default());
+
+        roster.push(entry("Tigers", "Blue 4"));
+        assert_eq!(sides_of(&game(None), &roster), blue("Tigers"));
+    }
+
+    #[test]
+    fn players_match_by_puuid_alias_or_riot_id() {
+        let mut game = game(None);
+        game.participants[0].puuid = "puuid-0".into();
+        let aliases = HashMap::from([("Blue 1".to_string(), "puuid-1".to_string())]);
+        let roster = team("Tigers", &["puuid-0", "puuid-1", "blue 2#oce", "BLUE 3"]);
+        assert_eq!(
+            sides(&game, &roster, &aliases).blue.as_deref(),
+            Some("Tigers")
+        );
+
+        // Riot IDs with the wrong tag don't count, leaving one player
+        let roster = team("Tigers", &["puuid-0", "Blue 2#EUW", "Blue 3#NA1"]);
+        assert_eq!(sides(&game, &roster, &aliases), Sides::default());
+    }
+}
@@ -1,14 +1,80 @@
 use std::collections::HashMap;
 
 use rusqlite::Row;
+use serde::Serialize;
 use serde_json::Value;
 
+use crate::riot::model::{InfoDto, MatchDto};
+use crate::sql::draft::DraftAction;
+use crate::sql::rating::RatingKind;
+
 pub struct Game {
     pub id: u64,
     pub team_1: String,
     pub team_2: String,
-    // pub manual: bool,
+    pub manual: bool,
+    pub series_id: Option<i64>,
+    pub game_number: Option<u64>,
+    /// Set by hand, otherwise the patch comes from the game version.
+    pub patch: Option<String>,
+    pub notes: Option<String>,
     pub data: Value,
+    pub info: InfoDto,
+}
+
+impl Game {
+    /// Wrap a match payload that hasn't been stored yet, e.g. a search result.
+    pub fn from_data(data: Value, team_1: &str, team_2: &str) -> serde_json::Result<Self> {
+        let info = MatchDto::from_value(&data)?.info;
+        Ok(Self {
+            id: info.game_id,
+            team_1: team_1.to_string(),
+            team_2: team_2.to_string(),
+            manual: false,
+            series_id: None,
+            game_number: None,
+            patch: None,
+            notes: None,
+            data,
+            info,
+        })
+    }
+
+    /// The patch the game was played on, e.g. `14.23`.
+    pub fn patch(&self) -> Option<String> {
+        self.patch.clone().or_else(|| {
+            let version = self.info.game_version.as_deref()?;
+            let mut parts = version.split('.');
+            match (parts.next(), parts.next()) {
+                (Some(major), Some(minor)) => Some(format!("{major}.{minor}")),
+                _ => Some(version.to_string()),
+            }
+        })
+    }
+
+    pub fn metadata(&self) -> GameMetadata {
+        GameMetadata {
+            team_1: self.team_1.clone(),
+            team_2: self.team_2.clone(),
+            manual: self.manual,
+            series_id: self.series_id,
+            game_number: self.game_number,
+            patch: self.patch.clone(),
+            notes: self.notes.clone(),
+        }
+    }
+}
+
+/// The parts of a stored game that can be corrected by hand.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameMetadata {
+    pub team_1: String,
+    pub team_2: String,
+    pub manual: bool,
+    pub series_id: Option<i64>,
+    pub game_number: Option<u64>,
+    pub patch: Option<String>,
+    pub notes: Option<String>,
 }
 
 impl TryFrom<&Row<'_>> for Game {
@@ -16,23 +82,230 @@ impl TryFrom<&Row<'_>> for Game {
 
     fn try_from(row: &Row) -> Result<Self, Self::Error> {
         let data_str = row.get::<_, String>("data")?;
+        let to_sql_err = |e: serde_json::Error| {
+            rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e))
+        };
+        let data: Value = serde_json::from_str(&data_str).map_err(to_sql_err)?;
+        let info = MatchDto::from_value(&data).map_err(to_sql_err)?.info;
         Ok(Self {
             id: row.get("id")?,
-            team_1: row.get("team_1")?,
-            team_2: row.get("team_2")?,
-            // manual: row.get("manual")?,
-            data: serde_json::from_str(&data_str).map_err(|e| {
+            // Imported games have no teams until someone assigns them
+            team_1: row.get::<_, Option<String>>("team_1")?.unwrap_or_default(),
+            team_2: row.get::<_, Option<String>>("team_2")?.unwrap_or_default(),
+            manual: row.get("manual")?,
+            series_id: row.get("series_id")?,
+            game_number: row.get("game_number")?,
+            patch: row.get("patch")?,
+            notes: row.get("notes")?,
+            data,
+            info,
+        })
+    }
+}
+
+/// A stored game whose payload no longer reads as a match, left out of the
+/// game list rather than failing it.
+#[derive(Debug, Clone)]
+pub struct UnreadableGame {
+    pub id: u64,
+    pub error: String,
+}
+
+/// What's already stored for a game, to compare a re-import against.
+#[derive(Debug, Clone)]
+pub struct StoredGame {
+    pub manual: bool,
+    pub data: Value,
+    pub team_1: Option<String>,
+    pub team_2: Option<String>,
+    pub series_id: Option<i64>,
+}
+
+impl TryFrom<&Row<'_>> for StoredGame {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let data: String = row.get("data")?;
+        let non_empty = |name: Option<String>| name.filter(|n| !n.is_empty());
+        Ok(Self {
+            manual: row.get("manual")?,
+            data: serde_json::from_str(&data).map_err(|e| {
                 rusqlite::Error::FromSqlConversionFailure(
-                    1,
+                    data.len(),
                     rusqlite::types::Type::Text,
                     Box::new(e),
                 )
             })?,
+            team_1: non_empty(row.get("team_1")?),
+            team_2: non_empty(row.get("team_2")?),
+            series_id: row.get("series_id")?,
+        })
+    }
+}
+
+/// One ban or pick in a game's champion select.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DraftEntry {
+    pub seq: u64,
+    pub team_id: u64,
+    pub action: DraftAction,
+    /// `None` for a skipped ban, or a ban whose champion hasn't been picked
+    /// in any stored game so has no known name.
+    pub champion: Option<String>,
+    /// Entered by hand, rather than read from the payload.
+    pub manual: bool,
+}
+
+impl TryFrom<&Row<'_>> for DraftEntry {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            seq: row.get("seq")?,
+            team_id: row.get("team_id")?,
+            action: match row.get::<_, String>("action")?.as_str() {
+                "pick" => DraftAction::Pick,
+                _ => DraftAction::Ban,
+            },
+            champion: row.get("champion")?,
+            manual: row.get("manual")?,
+        })
+    }
+}
+
+/// How a team drafts, over the games it has draft data for. Each list is
+/// `(champion, times)`, most frequent first.
+#[derive(Debug, Default, Clone)]
+pub struct TeamDraftTendency {
+    pub team: String,
+    pub games: u64,
+    pub bans: Vec<(String, u64)>,
+    pub banned_against: Vec<(String, u64)>,
+    pub first_picks: Vec<(String, u64)>,
+}
+
+/// One minute of a game's timeline, from blue side's point of view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TeamLead {
+    pub minute: u64,
+    pub gold: i64,
+    pub xp: i64,
+}
+
+impl TryFrom<&Row<'_>> for TeamLead {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            minute: row.get("minute")?,
+            gold: row.get("gold")?,
+            xp: row.get("xp")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectiveKind {
+    Dragon,
+    Baron,
+    Herald,
+    Tower,
+}
+
+/// An objective taken during a game, from its timeline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObjectiveEvent {
+    /// Fractional, e.g. `12.5` for 12:30.
+    pub minute: f64,
+    /// The side that took it.
+    pub team_id: u64,
+    pub kind: ObjectiveKind,
+}
+
+impl TryFrom<&Row<'_>> for ObjectiveEvent {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let kind: String = row.get("kind")?;
+        Ok(Self {
+            minute: row.get("minute")?,
+            team_id: row.get("team_id")?,
+            kind: match kind.as_str() {
+                "dragon" => ObjectiveKind::Dragon,
+                "baron" => ObjectiveKind::Baron,
+                "herald" => ObjectiveKind::Herald,
+                "tower" => ObjectiveKind::Tower,
+                _ => {
+                    return Err(rusqlite::Error::InvalidColumnType(
+                        0,
+                        kind,
+                        rusqlite::types::Type::Text,
+                    ));
+                }
+            },
+        })
+    }
+}
+
+/// What's stored from a game's timeline, for the gold lead chart.
+#[derive(Debug, Default, Clone)]
+pub struct GameTimeline {
+    pub leads: Vec<TeamLead>,
+    pub objectives: Vec<ObjectiveEvent>,
+}
+
+/// A player's time on a team. Dates are `YYYY-MM-DD` and inclusive.
+#[derive(Debug, Default, Clone)]
+pub struct RosterEntry {
+    pub id: i64,
+    pub team: String,
+    /// A puuid, or a Riot ID as `name` or `name#tag`.
+    pub player: String,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+impl TryFrom<&Row<'_>> for RosterEntry {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.get("id")?,
+            team: row.get("team")?,
+            player: row.get("player")?,
+            start_date: row.get("start_date")?,
+            end_date: row.get("end_date")?,
         })
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
+pub struct MatchSeries {
+    pub id: i64,
+    pub team_1: String,
+    pub team_2: String,
+    /// `None` when the format isn't known.
+    pub best_of: Option<u64>,
+    pub scheduled_date: Option<String>,
+    pub stage: Option<String>,
+}
+
+impl TryFrom<&Row<'_>> for MatchSeries {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.get("id")?,
+            team_1: row.get("team_1")?,
+            team_2: row.get("team_2")?,
+            best_of: row.get("best_of")?,
+            scheduled_date: row.get("scheduled_date")?,
+            stage: row.get("stage")?,
+        })
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
 pub struct OverallStats {
     pub games: u64,
     pub blue_wins: u64,
@@ -83,6 +356,47 @@ pub struct ChampionHistory {
     pub gold_share: f64,
 }
 
+/// A champion's or player's record against one lane opponent, the player
+/// on the other side in the same role.
+#[derive(Debug, Default, Clone)]
+pub struct Matchup {
+    /// The champion, or the player's puuid.
+    pub subject: String,
+    pub role: String,
+    /// The opposing champion, or the opposing player's name.
+    pub opponent: String,
+    pub games: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub kda: f64,
+    /// Average end of game differences to the opponent.
+    pub gold_diff: f64,
+    pub cs_diff: f64,
+    /// Average CS difference at 10, over the games that recorded it.
+    pub cd10: Option<f64>,
+}
+
+impl TryFrom<&Row<'_>> for Matchup {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            subject: row.get("subject")?,
+            role: row.get("role")?,
+            opponent: row
+                .get::<_, Option<String>>("opponent")?
+                .unwrap_or_default(),
+            games: row.get("games")?,
+            wins: row.get("wins")?,
+            losses: row.get("losses")?,
+            kda: row.get("kda")?,
+            gold_diff: row.get("gold_diff")?,
+            cs_diff: row.get("cs_diff")?,
+            cd10: row.get("cd10")?,
+        })
+    }
+}
+
 impl TryFrom<&Row<'_>> for ChampionHistory {
     type Error = rusqlite::Error;
 
@@ -114,11 +428,17 @@ impl TryFrom<&Row<'_>> for ChampionHistory {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct ChampionStats {
     pub champion: String,
     pub games: u64,
     pub pick_percentage: f64,
+    pub ban_percentage: f64,
+    /// Picked or banned.
+    pub presence_percentage: f64,
+    /// How often the champion was its team's first pick, out of the picks
+    /// with a known draft order.
+    pub first_pick_percentage: Option<f64>,
     pub unique_players: u64,
     pub wins: u64,
     pub losses: u64,
@@ -149,6 +469,9 @@ impl TryFrom<&Row<'_>> for ChampionStats {
             champion: row.get("champion")?,
             games: row.get("games")?,
             pick_percentage: row.get("pick_percentage")?,
+            ban_percentage: row.get("ban_percentage")?,
+            presence_percentage: row.get("presence_percentage")?,
+            first_pick_percentage: row.get("first_pick_percentage")?,
             unique_players: row.get("unique_players")?,
             wins: row.get("wins")?,
             losses: row.get("losses")?,
@@ -173,7 +496,367 @@ impl TryFrom<&Row<'_>> for ChampionStats {
     }
 }
 #[derive(Debug, Default, Clone)]
+pub struct TeamStats {
+    pub team_name: String,
+    pub games: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub win_percentage: f64,
+    pub blue_games: u64,
+    pub blue_wins: u64,
+    pub red_games: u64,
+    pub red_wins: u64,
+    pub game_length_avg: u64,
+    pub kills: f64,
+    pub deaths: f64,
+    pub kd: f64,
+    pub gpm: Option<f64>,
+    // First objective rates
+    pub fb_percentage: Option<f64>,
+    pub ft_percentage: Option<f64>,
+    pub fd_percentage: Option<f64>,
+    pub grubs: Option<f64>,
+}
+
+impl TryFrom<&Row<'_>> for TeamStats {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            team_name: row.get("team_name")?,
+            games: row.get("games")?,
+            wins: row.get("wins")?,
+            losses: row.get("losses")?,
+            win_percentage: row.get("win_percentage")?,
+            blue_games: row.get("blue_games")?,
+            blue_wins: row.get("blue_wins")?,
+            red_games: row.get("red_games")?,
+            red_wins: row.get("red_wins")?,
+            game_length_avg: row.get("game_length_avg")?,
+            kills: row.get("kills")?,
+            deaths: row.get("deaths")?,
+            kd: row.get("kd")?,
+            gpm: row.get("gpm")?,
+            fb_percentage: row.get("fb_percentage")?,
+            ft_percentage: row.get("ft_percentage")?,
+            fd_percentage: row.get("fd_percentage")?,
+            grubs: row.get("grubs")?,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SeriesGame {
+    pub game_id: u64,
+    pub team_1: String,
+    pub team_2: String,
+    pub winner: String,
+    pub date: Option<String>,
+    pub end_timestamp: Option<i64>,
+    pub series_id: Option<i64>,
+    pub game_number: Option<u64>,
+    pub best_of: Option<u64>,
+    pub series_date: Option<String>,
+    pub stage: Option<String>,
+}
+
+impl TryFrom<&Row<'_>> for SeriesGame {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            game_id: row.get("game_id")?,
+            team_1: row.get("team_1")?,
+            team_2: row.get("team_2")?,
+            winner: row.get("winner")?,
+            date: row.get("game_date")?,
+            end_timestamp: row.get("end_timestamp")?,
+            series_id: row.get("series_id")?,
+            game_number: row.get("game_number")?,
+            best_of: row.get("best_of")?,
+            series_date: row.get("series_date")?,
+            stage: row.get("stage")?,
+        })
+    }
+}
+
+/// A set of games between the same two teams. `team_a` is always the
+/// alphabetically smaller name so a pairing has a single orientation.
+#[derive(Debug, Default, Clone)]
+pub struct Series {
+    pub team_a: String,
+    pub team_b: String,
+    pub date: Option<String>,
+    pub stage: Option<String>,
+    pub best_of: u64,
+    pub team_a_wins: u64,
+    pub team_b_wins: u64,
+    pub game_ids: Vec<u64>,
+}
+
+impl Series {
+    /// The series winner, or `None` for a drawn Bo2.
+    pub fn winner(&self) -> Option<&str> {
+        match self.team_a_wins.cmp(&self.team_b_wins) {
+            std::cmp::Ordering::Greater => Some(&self.team_a),
+            std::cmp::Ordering::Less => Some(&self.team_b),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct StandingRow {
+    pub rank: u64,
+    pub team: String,
+    pub match_wins: u64,
+    pub match_losses: u64,
+    pub match_draws: u64,
+    pub game_wins: u64,
+    pub game_losses: u64,
+}
+
+impl StandingRow {
+    pub fn match_win_percent(&self) -> f64 {
+        percent(self.match_wins, self.match_wins + self.match_losses)
+    }
+
+    pub fn game_win_percent(&self) -> f64 {
+        percent(self.game_wins, self.game_wins + self.game_losses)
+    }
+}
+
+/// Two teams side by side, see `repo::head_to_head`.
+#[derive(Debug, Default, Clone)]
+pub struct HeadToHead {
+    pub teams: [TeamProfile; 2],
+    /// Series between the two teams, most recent first.
+    pub series: Vec<Series>,
+    /// Games between the two teams, in the order they were played.
+    pub games: Vec<SeriesGame>,
+}
+
+impl HeadToHead {
+    /// Series won and games won against the other team, for each team.
+    pub fn record(&self) -> [(u64, u64); 2] {
+        let [a, b] = [0, 1].map(|i| {
+            let team = &self.teams[i].stats.team_name;
+            let series = self
+                .series
+                .iter()
+                .filter(|s| s.winner() == Some(team))
+                .count() as u64;
+            let games = self.games.iter().filter(|g| &g.winner == team).count() as u64;
+            (series, games)
+        });
+        [a, b]
+    }
+}
+
+/// One team's half of a `HeadToHead`, its stats against everyone.
+#[derive(Debug, Default, Clone)]
+pub struct TeamProfile {
+    pub stats: TeamStats,
+    /// One line per role, in role order.
+    pub roles: Vec<TeamRoleLine>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TeamRoleLine {
+    pub role: String,
+    /// The team's most played player in the role.
+    pub player: Option<String>,
+    /// That player's stats in the role, `None` under the four game minimum.
+    pub stats: Option<PlayerRoleStats>,
+    /// The team's most picked champion in the role and how often.
+    pub champion: Option<(String, u64)>,
+}
+
+/// Two players side by side, see `repo::shared_games`. Their overall and
+/// role stats come from `PlayerDeepStats`.
+#[derive(Debug, Default, Clone)]
+pub struct PlayerVersus {
+    /// The rows picked from the player stats table, so each has a role.
+    pub players: [PlayerStats; 2],
+    /// Games they were both in, most recent first.
+    pub games: Vec<SharedGame>,
+}
+
+impl PlayerVersus {
+    /// Games they played against each other in the same role.
+    pub fn lane_games(&self) -> impl Iterator<Item = &SharedGame> {
+        self.games.iter().filter(|g| g.is_lane_matchup())
+    }
+}
+
+/// A game two players were both in, from the first player's side.
+#[derive(Debug, Default, Clone)]
+pub struct SharedGame {
+    pub date: Option<String>,
+    pub same_team: bool,
+    /// Whether the first player won.
+    pub win: bool,
+    pub champions: [String; 2],
+    pub roles: [String; 2],
+    /// Kills, deaths and assists for each player.
+    pub kda: [(u64, u64, u64); 2],
+    /// First player minus second, when the game recorded it.
+    pub cd10: Option<i64>,
+    /// First player minus second, only on games with a timeline.
+    pub gd10: Option<i64>,
+}
+
+impl SharedGame {
+    pub fn is_lane_matchup(&self) -> bool {
+        !self.same_team && !self.roles[0].is_empty() && self.roles[0] == self.roles[1]
+    }
+}
+
+impl TryFrom<&Row<'_>> for SharedGame {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            date: row.get("game_date")?,
+            same_team: row.get("same_team")?,
+            win: row.get("win")?,
+            champions: [row.get("champion_a")?, row.get("champion_b")?],
+            roles: [row.get("role_a")?, row.get("role_b")?],
+            kda: [
+                (
+                    row.get("kills_a")?,
+                    row.get("deaths_a")?,
+                    row.get("assists_a")?,
+                ),
+                (
+                    row.get("kills_b")?,
+                    row.get("deaths_b")?,
+                    row.get("assists_b")?,
+                ),
+            ],
+            cd10: row.get("cd10")?,
+            gd10: row.get("gd10")?,
+        })
+    }
+}
+
+/// Two players on the same side of a game, with their combined line.
+#[derive(Debug, Default, Clone)]
+pub struct PairGame {
+    pub win: bool,
+    /// Game length in seconds.
+    pub duration: u64,
+    /// Kills by the pair's whole side.
+    pub team_kills: u64,
+    pub puuids: [Option<String>; 2],
+    pub names: [String; 2],
+    pub champions: [String; 2],
+    pub roles: [String; 2],
+    pub kills: u64,
+    pub deaths: u64,
+    pub assists: u64,
+    pub gold: u64,
+}
+
+impl TryFrom<&Row<'_>> for PairGame {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            win: row.get("win")?,
+            duration: row.get::<_, Option<u64>>("duration")?.unwrap_or(0),
+            team_kills: row.get("team_kills")?,
+            puuids: [row.get("puuid_a")?, row.get("puuid_b")?],
+            names: [
+                row.get::<_, Option<String>>("name_a")?.unwrap_or_default(),
+                row.get::<_, Option<String>>("name_b")?.unwrap_or_default(),
+            ],
+            champions: [
+                row.get::<_, Option<String>>("champion_a")?
+                    .unwrap_or_default(),
+                row.get::<_, Option<String>>("champion_b")?
+                    .unwrap_or_default(),
+            ],
+            roles: [row.get("role_a")?, row.get("role_b")?],
+            kills: row.get("kills")?,
+            deaths: row.get("deaths")?,
+            assists: row.get("assists")?,
+            gold: row.get("gold")?,
+        })
+    }
+}
+
+/// A player in a game, as `rating::rate` reads them.
+#[derive(Debug, Default, Clone)]
+pub struct RatingParticipant {
+    pub game_id: u64,
+    pub team_id: u64,
+    /// The team on this player's side, when the game has one.
+    pub team: Option<String>,
+    pub puuid: Option<String>,
+    pub role: String,
+    pub win: bool,
+}
+
+impl TryFrom<&Row<'_>> for RatingParticipant {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            game_id: row.get("game_id")?,
+            team_id: row.get("team_id")?,
+            team: row.get("team")?,
+            puuid: row.get("puuid")?,
+            role: row.get("role")?,
+            win: row.get("win")?,
+        })
+    }
+}
+
+/// A team's or player's Glicko-2 rating after a game.
+#[derive(Debug, Clone)]
+pub struct Rating {
+    pub kind: RatingKind,
+    /// Team name or player puuid.
+    pub subject: String,
+    pub game_id: u64,
+    /// The game's place in rating order.
+    pub seq: u64,
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+}
+
+impl TryFrom<&Row<'_>> for Rating {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            kind: match row.get::<_, String>("kind")?.as_str() {
+                "team" => RatingKind::Team,
+                _ => RatingKind::Player,
+            },
+            subject: row.get("subject")?,
+            game_id: row.get("game_id")?,
+            seq: row.get("seq")?,
+            rating: row.get("rating")?,
+            deviation: row.get("deviation")?,
+            volatility: row.get("volatility")?,
+        })
+    }
+}
+
+fn percent(n: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        n as f64 * 100.0 / total as f64
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct PlayerStats {
+    pub puuid: String,
     pub riot_id: String,
     pub tag_line: String,
     pub team_name: String,
@@ -186,6 +869,14 @@ pub struct PlayerStats {
     pub gpm: u64,
     pub cspm: f64,
     pub cd10: Option<f64>,
+    /// Gold, XP and CS differences against the lane opponent, only from games
+    /// with a timeline.
+    pub gd10: Option<f64>,
+    pub xpd10: Option<f64>,
+    pub timeline_cd10: Option<f64>,
+    pub gd15: Option<f64>,
+    pub xpd15: Option<f64>,
+    pub cd15: Option<f64>,
     pub kill_participation: u64,
     pub death_participation: u64,
     pub dpm: Option<u64>,
@@ -203,9 +894,12 @@ impl TryFrom<&Row<'_>> for PlayerStats {
 
     fn try_from(row: &Row) -> Result<Self, Self::Error> {
         Ok(Self {
+            puuid: row.get("puuid")?,
             riot_id: row.get("riot_id")?,
             tag_line: row.get("tag_line")?,
-            team_name: row.get("team_name")?,
+            team_name: row
+                .get::<_, Option<String>>("team_name")?
+                .unwrap_or_default(),
             role: row.get("role")?,
             games: row.get("games")?,
             kills: row.get("kills")?,
@@ -215,6 +909,12 @@ impl TryFrom<&Row<'_>> for PlayerStats {
             gpm: row.get("gpm")?,
             cspm: row.get("cspm")?,
             cd10: row.get("cd10")?,
+            gd10: row.get("gd10")?,
+            xpd10: row.get("xpd10")?,
+            timeline_cd10: row.get("timeline_cd10")?,
+            gd15: row.get("gd15")?,
+            xpd15: row.get("xpd15")?,
+            cd15: row.get("cd15")?,
             vpm: row.get("vpm")?,
             kill_participation: row.get("kill_participation")?,
             death_participation: row.get("death_participation")?,
@@ -227,9 +927,9 @@ impl TryFrom<&Row<'_>> for PlayerStats {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct PlayerChampionStats {
-    pub player_name: String,
+    pub puuid: String,
     pub champion_name: String,
     pub role: String,
     pub games: u64,
@@ -245,9 +945,9 @@ pub struct PlayerChampionStats {
     pub cd10: Option<f64>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct PlayerOverallStats {
-    pub player_name: String,
+    pub puuid: String,
     pub games: u64,
     pub wins: u64,
     pub losses: u64,
@@ -269,9 +969,9 @@ pub struct PlayerOverallStats {
     pub cd10n: Option<u64>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct PlayerRoleStats {
-    pub player_name: String,
+    pub puuid: String,
     pub role: String,
     pub games: u64,
     pub wins: u64,
@@ -306,7 +1006,7 @@ impl TryFrom<&Row<'_>> for PlayerOverallStats {
 
     fn try_from(row: &Row) -> Result<Self, Self::Error> {
         Ok(Self {
-            player_name: row.get("player_name")?,
+            puuid: row.get("puuid")?,
             games: row.get("games")?,
             wins: row.get("wins")?,
             losses: row.get("losses")?,
@@ -335,7 +1035,7 @@ impl TryFrom<&Row<'_>> for PlayerChampionStats {
 
     fn try_from(row: &Row) -> Result<Self, Self::Error> {
         Ok(Self {
-            player_name: row.get("player_name")?,
+            puuid: row.get("puuid")?,
             role: row.get("role")?,
             champion_name: row.get("champion")?,
             games: row.get("games")?,
@@ -358,7 +1058,7 @@ impl TryFrom<&Row<'_>> for PlayerRoleStats {
 
     fn try_from(row: &Row) -> Result<Self, Self::Error> {
         Ok(Self {
-            player_name: row.get("player_name")?,
+            puuid: row.get("puuid")?,
             role: row.get("role")?,
             games: row.get("games")?,
             wins: row.get("wins")?,
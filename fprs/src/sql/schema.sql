@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS game (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    data TEXT CHECK (json_valid(data)),
+    manual INTEGER NOT NULL DEFAULT 0,
+    team_1 TEXT,
+    team_2 TEXT
+);
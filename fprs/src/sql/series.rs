@@ -0,0 +1,296 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+};
+
+use crate::sql::schema::{Series, SeriesGame, StandingRow};
+
+/// The longest gap between the end of one game and the end of the next for
+/// them to be in the same series: a long game, a pause and the next draft.
+const SERIES_GAP_MS: i64 = 3 * 60 * 60 * 1000;
+
+/// Group games into series.
+///
+/// Games attached to a stored series are grouped by it. Any other game
+/// carries on the last series between the same pair of teams when it ended
+/// within `SERIES_GAP_MS` of that series' last game, so a series played over
+/// midnight stays together. Manual games only have a date, and carry on a
+/// series from the same date. Games with neither are a series of their own.
+///
+/// Series without a stored format get the smallest one their score fits in.
+pub fn group_series(games: &[SeriesGame]) -> Vec<Series> {
+    let mut series: Vec<Series> = Vec::new();
+    let mut stored: HashMap<i64, usize> = HashMap::new();
+    // The last series between each pair of teams and its latest game
+    let mut open: HashMap<(String, String), (usize, &SeriesGame)> = HashMap::new();
+
+    let mut games: Vec<&SeriesGame> = games.iter().collect();
+    games.sort_by_key(|g| (g.date.clone(), g.end_timestamp, g.game_number, g.game_id));
+
+    for game in games {
+        let (team_a, team_b) = match game.team_1.cmp(&game.team_2) {
+            Ordering::Greater => (game.team_2.clone(), game.team_1.clone()),
+            _ => (game.team_1.clone(), game.team_2.clone()),
+        };
+
+        let existing = match game.series_id {
+            Some(id) => stored.get(&id).copied(),
+            None => open
+                .get(&(team_a.clone(), team_b.clone()))
+                .filter(|(_, last)| same_series(last, game))
+                .map(|(i, _)| *i),
+        };
+        let i = existing.unwrap_or_else(|| {
+            series.push(Series {
+                team_a: team_a.clone(),
+                team_b: team_b.clone(),
+                date: game.series_date.clone().or(game.date.clone()),
+                stage: game.stage.clone(),
+                best_of: game.best_of.unwrap_or(0),
+                ..Default::default()
+            });
+            series.len() - 1
+        });
+        match game.series_id {
+            Some(id) => {
+                stored.insert(id, i);
+            }
+            None => {
+                open.insert((team_a, team_b), (i, game));
+            }
+        }
+
+        let series = &mut series[i];
+        if game.winner == series.team_a {
+            series.team_a_wins += 1;
+        } else {
+            series.team_b_wins += 1;
+        }
+        series.game_ids.push(game.game_id);
+    }
+
+    for s in series.iter_mut().filter(|s| s.best_of == 0) {
+        s.best_of = infer_best_of(s.team_a_wins, s.team_b_wins);
+    }
+
+    // Most recent first, same as the match browser
+    series.reverse();
+    series.sort_by(|a, b| b.date.cmp(&a.date));
+    series
+}
+
+/// Whether `game` carries on from `last`, the game before it between the
+/// same teams.
+fn same_series(last: &SeriesGame, game: &SeriesGame) -> bool {
+    match (last.end_timestamp, game.end_timestamp) {
+        (Some(last), Some(end)) => end - last <= SERIES_GAP_MS,
+        _ => last.date.is_some() && last.date == game.date,
+    }
+}
+
+/// Smallest format the score fits in: a 1-1 is a Bo2, a 2-0 or 2-1 a Bo3.
+fn infer_best_of(a_wins: u64, b_wins: u64) -> u64 {
+    let games = a_wins + b_wins;
+    games.max(2 * a_wins.max(b_wins) - 1)
+}
+
+/// Rank teams by match win rate, then match wins, then game win rate and game wins.
+pub fn standings(series: &[Series]) -> Vec<StandingRow> {
+    let mut rows: BTreeMap<String, StandingRow> = BTreeMap::new();
+
+    for s in series {
+        for (team, won, lost) in [
+            (&s.team_a, s.team_a_wins, s.team_b_wins),
+            (&s.team_b, s.team_b_wins, s.team_a_wins),
+        ] {
+            let row = rows.entry(team.clone()).or_insert_with(|| StandingRow {
+                team: team.clone(),
+                ..Default::default()
+            });
+            row.game_wins += won;
+            row.game_losses += lost;
+            match won.cmp(&lost) {
+                Ordering::Greater => row.match_wins += 1,
+                Ordering::Less => row.match_losses += 1,
+                Ordering::Equal => row.match_draws += 1,
+            }
+        }
+    }
+
+    let mut rows: Vec<StandingRow> = rows.into_values().collect();
+    rows.sort_by(|a, b| {
+        b.match_win_percent()
+            .partial_cmp(&a.match_win_percent())
+            .unwrap_or(Ordering::Equal)
+            .then(b.match_wins.cmp(&a.match_wins))
+            .then(
+                b.game_win_percent()
+                    .partial_cmp(&a.game_win_percent())
+                    .unwrap_or(Ordering::Equal),
+            )
+            .then(b.game_wins.cmp(&a.game_wins))
+    });
+
+    for (i, row) in rows.iter_mut().enumerate() {
+        row.rank = i as u64 + 1;
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR_MS: i64 = 60 * 60 * 1000;
+
+    fn game(id: u64, teams: [&str; 2], winner: &str, end: Option<i64>) -> SeriesGame {
+        SeriesGame {
+            game_id: id,
+            team_1: teams[0].to_string(),
+            team_2: teams[1].to_string(),
+            winner: winner.to_string(),
+            // Dates only need to order the games here
+            date: end.map(|end| format!("day {}", end / (24 * HOUR_MS))),
+            end_timestamp: end,
+            ..Default::default()
+        }
+    }
+
+    fn manual(id: u64, teams: [&str; 2], winner: &str, date: &str) -> SeriesGame {
+        SeriesGame {
+            date: Some(date.to_string()),
+            ..game(id, teams, winner, None)
+        }
+    }
+
+    #[test]
+    fn games_close_together_are_one_series() {
+        let series = group_series(&[
+            game(1, ["Tigers", "Eagles"], "Tigers", Some(HOUR_MS)),
+            game(2, ["Eagles", "Tigers"], "Eagles", Some(2 * HOUR_MS)),
+            game(3, ["Tigers", "Eagles"], "Tigers", Some(3 * HOUR_MS)),
+        ]);
+        assert_eq!(series.len(), 1);
+        let s = &series[0];
+        assert_eq!((s.team_a.as_str(), s.team_b.as_str()), ("Eagles", "Tigers"));
+        assert_eq!((s.team_a_wins, s.team_b_wins), (1, 2));
+        assert_eq!(s.best_of, 3);
+        assert_eq!(s.game_ids, [1, 2, 3]);
+    }
+
+    #[test]
+    fn a_series_over_midnight_stays_together() {
+        let midnight = 24 * HOUR_MS;
+        let series = group_series(&[
+            game(
+                1,
+                ["Tigers", "Eagles"],
+                "Tigers",
+                Some(midnight - HOUR_MS / 2),
+            ),
+            game(
+                2,
+                ["Tigers", "Eagles"],
+                "Tigers",
+                Some(midnight + HOUR_MS / 2),
+            ),
+        ]);
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].game_ids, [1, 2]);
+    }
+
+    #[test]
+    fn a_long_gap_starts_a_new_series() {
+        let series = group_series(&[
+            game(1, ["Tigers", "Eagles"], "Tigers", Some(HOUR_MS)),
+            game(
+                2,
+                ["Tigers", "Eagles"],
+                "Eagles",
+                Some(HOUR_MS + SERIES_GAP_MS + 1),
+            ),
+        ]);
+        assert_eq!(series.len(), 2);
+        // Most recent first
+        assert_eq!(series[0].game_ids, [2]);
+        assert_eq!(series[1].game_ids, [1]);
+    }
+
+    #[test]
+    fn other_pairings_dont_break_a_series_up() {
+        let series = group_series(&[
+            game(1, ["Tigers", "Eagles"], "Tigers", Some(HOUR_MS)),
+            game(2, ["Wolves", "Bears"], "Bears", Some(2 * HOUR_MS)),
+            game(3, ["Tigers", "Eagles"], "Tigers", Some(3 * HOUR_MS)),
+        ]);
+        assert_eq!(series.len(), 2);
+        let tigers = series.iter().find(|s| s.team_b == "Tigers").unwrap();
+        assert_eq!(tigers.game_ids, [1, 3]);
+    }
+
+    #[test]
+    fn manual_games_group_by_date_and_undated_games_stand_alone() {
+        let series = group_series(&[
+            manual(1, ["Tigers", "Eagles"], "Tigers", "2025-03-01"),
+            manual(2, ["Tigers", "Eagles"], "Eagles", "2025-03-01"),
+            manual(3, ["Tigers", "Eagles"], "Eagles", "2025-03-08"),
+            game(4, ["Tigers", "Eagles"], "Tigers", None),
+            game(5, ["Tigers", "Eagles"], "Tigers", None),
+        ]);
+        let mut ids: Vec<Vec<u64>> = series.iter().map(|s| s.game_ids.clone()).collect();
+        ids.sort();
+        assert_eq!(ids, [vec![1, 2], vec![3], vec![4], vec![5]]);
+    }
+
+    #[test]
+    fn stored_series_keep_their_games_and_format() {
+        let stored = |id, end| SeriesGame {
+            series_id: Some(7),
+            best_of: Some(5),
+            ..game(id, ["Tigers", "Eagles"], "Tigers", Some(end))
+        };
+        let series = group_series(&[
+            stored(1, HOUR_MS),
+            // A day later, but stored in the same series
+            stored(2, 30 * HOUR_MS),
+            game(3, ["Tigers", "Eagles"], "Eagles", Some(31 * HOUR_MS)),
+        ]);
+        assert_eq!(series.len(), 2);
+        let stored = series.iter().find(|s| s.game_ids.contains(&1)).unwrap();
+        assert_eq!(stored.game_ids, [1, 2]);
+        assert_eq!(stored.best_of, 5);
+    }
+
+    #[test]
+    fn infer_best_of_fits_the_score() {
+        assert_eq!(infer_best_of(1, 0), 1);
+        assert_eq!(infer_best_of(1, 1), 2);
+        assert_eq!(infer_best_of(2, 0), 3);
+        assert_eq!(infer_best_of(2, 1), 3);
+        assert_eq!(infer_best_of(3, 0), 5);
+    }
+
+    #[test]
+    fn standings_rank_by_match_then_game_record() {
+        let series = |a: &str, b: &str, a_wins, b_wins| Series {
+            team_a: a.to_string(),
+            team_b: b.to_string(),
+            team_a_wins: a_wins,
+            team_b_wins: b_wins,
+            ..Default::default()
+        };
+        let rows = standings(&[
+            series("Eagles", "Tigers", 0, 2),
+            series("Bears", "Eagles", 2, 1),
+            series("Bears", "Tigers", 1, 1),
+        ]);
+        let order: Vec<(&str, u64, u64, u64)> = rows
+            .iter()
+            .map(|r| (r.team.as_str(), r.rank, r.match_wins, r.match_draws))
+            .collect();
+        assert_eq!(
+            order,
+            [("Tigers", 1, 1, 1), ("Bears", 2, 1, 1), ("Eagles", 3, 0, 0)]
+        );
+    }
+}
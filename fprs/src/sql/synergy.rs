@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use crate::sql::schema::PairGame;
+
+/// How many games a pair of players has to play together before it's listed.
+pub const MIN_GAMES: u64 = 3;
+/// Champion pairs come up less often than the same two players, so they need
+/// fewer games.
+pub const MIN_CHAMPION_GAMES: u64 = 2;
+
+/// Roles that play off each other, listed apart from the other pairs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duo {
+    BotLane,
+    MidJungle,
+}
+
+impl Duo {
+    pub const ALL: [Duo; 2] = [Duo::BotLane, Duo::MidJungle];
+
+    /// The roles in the order the pair is listed.
+    pub fn roles(self) -> [&'static str; 2] {
+        match self {
+            Self::BotLane => ["BOTTOM", "UTILITY"],
+            Self::MidJungle => ["MIDDLE", "JUNGLE"],
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::BotLane => "Bot lane",
+            Self::MidJungle => "Mid & jungle",
+        }
+    }
+}
+
+/// Two players or champions and how they did on the same side.
+#[derive(Debug, Default, Clone)]
+pub struct Pair {
+    /// Puuids or champions, in the order the pair is listed.
+    pub keys: [String; 2],
+    pub names: [String; 2],
+    pub games: u64,
+    pub wins: u64,
+    /// Combined over both of them.
+    pub kills: u64,
+    pub deaths: u64,
+    pub assists: u64,
+    pub gold: u64,
+    pub team_kills: u64,
+    /// Seconds played together.
+    pub duration: u64,
+}
+
+impl Pair {
+    pub fn losses(&self) -> u64 {
+        self.games - self.wins
+    }
+
+    pub fn win_percent(&self) -> f64 {
+        self.wins as f64 * 100.0 / self.games.max(1) as f64
+    }
+
+    pub fn kda(&self) -> f64 {
+        (self.kills + self.assists) as f64 / self.deaths.max(1) as f64
+    }
+
+    /// The pair's average kill participation.
+    pub fn kill_participation(&self) -> f64 {
+        (self.kills + self.assists) as f64 * 100.0 / (2 * self.team_kills).max(1) as f64
+    }
+
+    /// Gold per minute between the two of them.
+    pub fn gpm(&self) -> u64 {
+        self.gold * 60 / self.duration.max(60)
+    }
+
+    fn add(&mut self, game: &PairGame) {
+        self.games += 1;
+        self.wins += game.win as u64;
+        self.kills += game.kills;
+        self.deaths += game.deaths;
+        self.assists += game.assists;
+        self.gold += game.gold;
+        self.team_kills += game.team_kills;
+        self.duration += game.duration;
+    }
+}
+
+/// A player's teammates with the league's duos and champion pairs, for the
+/// synergy screen.
+#[derive(Debug, Default, Clone)]
+pub struct Synergy {
+    pub puuid: String,
+    pub name: String,
+    /// Each pair has the player first.
+    pub teammates: Vec<Pair>,
+    pub duos: Vec<(Duo, Vec<Pair>)>,
+    pub champions: Vec<Pair>,
+}
+
+impl Synergy {
+    pub fn new(games: &[PairGame], puuid: &str, name: &str) -> Self {
+        let teammates = player_pairs(games, MIN_GAMES)
+            .into_iter()
+            .filter(|p| p.keys.iter().any(|k| k == puuid))
+            .map(|mut p| {
+                if p.keys[1] == puuid {
+                    p.keys.swap(0, 1);
+                    p.names.swap(0, 1);
+                }
+                p
+            })
+            .collect();
+
+        Self {
+            puuid: puuid.to_string(),
+            name: name.to_string(),
+            teammates,
+            duos: Duo::ALL
+                .into_iter()
+                .map(|duo| (duo, duos(games, duo, MIN_GAMES)))
+                .collect(),
+            champions: champion_pairs(games, MIN_CHAMPION_GAMES),
+        }
+    }
+}
+
+/// Every pair of players with at least `min_games` together, whatever roles
+/// they played.
+pub fn player_pairs(games: &[PairGame], min_games: u64) -> Vec<Pair> {
+    tally(games, min_games, |g| {
+        let [Some(a), Some(b)] = g.puuids.clone() else {
+            return None;
+        };
+        // Either way round is the same pair
+        match a <= b {
+            true => Some(([a, b], g.names.clone())),
+            false => Some(([b, a], [g.names[1].clone(), g.names[0].clone()])),
+        }
+    })
+}
+
+/// Pairs of players who played `duo`'s roles together.
+pub fn duos(games: &[PairGame], duo: Duo, min_games: u64) -> Vec<Pair> {
+    let [first, second] = duo.roles();
+    tally(games, min_games, |g| {
+        let [Some(a), Some(b)] = g.puuids.clone() else {
+            return None;
+        };
+        match [g.roles[0].as_str(), g.roles[1].as_str()] {
+            [x, y] if x == first && y == second => Some(([a, b], g.names.clone())),
+            [x, y] if x == second && y == first => {
+                Some(([b, a], [g.names[1].clone(), g.names[0].clone()]))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Every pair of champions with at least `min_games` on the same side.
+pub fn champion_pairs(games: &[PairGame], min_games: u64) -> Vec<Pair> {
+    tally(games, min_games, |g| {
+        let [a, b] = g.champions.clone();
+        if a.is_empty() || b.is_empty() {
+            return None;
+        }
+        let pair = match a <= b {
+            true => [a, b],
+            false => [b, a],
+        };
+        Some((pair.clone(), pair))
+    })
+}
+
+/// Add up the games by the keys and names `pair` gives them, most played
+/// first. Names come from the latest game, as players rename.
+fn tally<F>(games: &[PairGame], min_games: u64, pair: F) -> Vec<Pair>
+where
+    F: Fn(&PairGame) -> Option<([String; 2], [String; 2])>,
+{
+    let mut pairs: HashMap<[String; 2], Pair> = HashMap::new();
+    for game in games {
+        let Some((keys, names)) = pair(game) else {
+            continue;
+        };
+        let entry = pairs.entry(keys.clone()).or_insert_with(|| Pair {
+            keys,
+            ..Default::default()
+        });
+        entry.names = names;
+        entry.add(game);
+    }
+
+    let mut pairs: Vec<Pair> = pairs
+        .into_values()
+        .filter(|p| p.games >= min_games)
+        .collect();
+    pairs.sort_by(|a, b| {
+        b.games
+            .cmp(&a.games)
+            .then(b.wins.cmp(&a.wins))
+            .then(a.names.cmp(&b.names))
+    });
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A game `a` and `b` played together, `a` in `roles[0]`.
+    fn game(a: (&str, &str), b: (&str, &str), roles: [&str; 2], win: bool) -> PairGame {
+        PairGame {
+            win,
+            duration: 1800,
+            team_kills: 20,
+            puuids: [Some(a.0.to_string()), Some(b.0.to_string())],
+            names: [a.1.to_string(), b.1.to_string()],
+            champions: ["Ahri".to_string(), "Lee Sin".to_string()],
+            roles: roles.map(str::to_string),
+            kills: 6,
+            deaths: 4,
+            assists: 10,
+            gold: 20000,
+        }
+    }
+
+    #[test]
+    fn player_pairs_are_the_same_either_way_round() {
+        let games = [
+            game(("p1", "One"), ("p2", "Two"), ["MIDDLE", "JUNGLE"], true),
+            game(("p2", "Two"), ("p1", "One"), ["TOP", "JUNGLE"], false),
+            game(("p1", "One"), ("p3", "Three"), ["MIDDLE", "UTILITY"], true),
+        ];
+        let pairs = player_pairs(&games, 2);
+        assert_eq!(pairs.len(), 1);
+        let pair = &pairs[0];
+        assert_eq!(pair.keys, ["p1", "p2"]);
+        assert_eq!(pair.names, ["One", "Two"]);
+        assert_eq!((pair.games, pair.wins, pair.losses()), (2, 1, 1));
+        assert_eq!((pair.kills, pair.deaths, pair.assists), (12, 8, 20));
+        assert_eq!(pair.kda(), 4.0);
+        assert_eq!(pair.kill_participation(), 40.0);
+        assert_eq!(pair.gpm(), 666);
+    }
+
+    #[test]
+    fn tally_names_pairs_from_the_latest_game() {
+        let games = [
+            game(("p1", "Old"), ("p2", "Two"), ["MIDDLE", "JUNGLE"], true),
+            game(("p1", "New"), ("p2", "Two"), ["MIDDLE", "JUNGLE"], true),
+        ];
+        assert_eq!(player_pairs(&games, 1)[0].names, ["New", "Two"]);
+    }
+
+    #[test]
+    fn tally_skips_players_without_a_puuid_and_sorts_by_games() {
+        let mut unknown = game(("p1", "One"), ("p2", "Two"), ["MIDDLE", "JUNGLE"], true);
+        unknown.puuids[1] = None;
+        let games = [
+            unknown,
+            game(("p3", "Three"), ("p4", "Four"), ["TOP", "JUNGLE"], false),
+            game(("p1", "One"), ("p2", "Two"), ["MIDDLE", "JUNGLE"], false),
+            game(("p3", "Three"), ("p4", "Four"), ["TOP", "JUNGLE"], false),
+        ];
+        let keys: Vec<[String; 2]> = player_pairs(&games, 1)
+            .into_iter()
+            .map(|p| p.keys)
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                ["p3".to_string(), "p4".to_string()],
+                ["p1".to_string(), "p2".to_string()]
+            ]
+        );
+    }
+
+    #[test]
+    fn duos_list_the_pair_in_role_order() {
+        let games = [
+            game(("sup", "Sup"), ("adc", "Adc"), ["UTILITY", "BOTTOM"], true),
+            game(("adc", "Adc"), ("sup", "Sup"), ["BOTTOM", "UTILITY"], true),
+            game(("adc", "Adc"), ("mid", "Mid"), ["BOTTOM", "MIDDLE"], true),
+        ];
+        let bot = duos(&games, Duo::BotLane, 1);
+        assert_eq!(bot.len(), 1);
+        assert_eq!(bot[0].keys, ["adc", "sup"]);
+        assert_eq!(bot[0].names, ["Adc", "Sup"]);
+        assert_eq!(bot[0].games, 2);
+        assert!(duos(&games, Duo::MidJungle, 1).is_empty());
+    }
+
+    #[test]
+    fn champion_pairs_ignore_order() {
+        let mut swapped = game(("p1", "One"), ("p2", "Two"), ["MIDDLE", "JUNGLE"], true);
+        swapped.champions.swap(0, 1);
+        let games = [
+            game(("p1", "One"), ("p2", "Two"), ["MIDDLE", "JUNGLE"], false),
+            swapped,
+        ];
+        let pairs = champion_pairs(&games, 2);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].names, ["Ahri", "Lee Sin"]);
+        assert_eq!(pairs[0].wins, 1);
+    }
+}
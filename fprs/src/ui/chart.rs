@@ -0,0 +1,169 @@
+//! The gold lead chart, shown beside the scoreboard and in the game detail.
+//!
+//! Games with a stored timeline get a point per minute and markers for the
+//! objectives taken. Anything else only has end of game totals, so the chart
+//! draws a straight line from an even start to the final gold difference.
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Color, Style, palette::tailwind},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Widget},
+};
+
+use crate::sql::schema::{Game, GameTimeline, ObjectiveKind, TeamLead};
+
+const BLUE: Color = tailwind::BLUE.c400;
+const RED: Color = tailwind::RED.c400;
+
+const OBJECTIVES: [(ObjectiveKind, &str, Color); 4] = [
+    (ObjectiveKind::Dragon, "Dragon", tailwind::ORANGE.c400),
+    (ObjectiveKind::Baron, "Baron", tailwind::PURPLE.c400),
+    (ObjectiveKind::Herald, "Herald", tailwind::PINK.c400),
+    (ObjectiveKind::Tower, "Tower", tailwind::SLATE.c300),
+];
+
+/// Blue side's gold lead over the game, with its XP lead as well when
+/// `with_xp` is set and the game has a timeline.
+pub fn draw_lead_chart(
+    buf: &mut Buffer,
+    area: Rect,
+    game: &Game,
+    timeline: Option<&GameTimeline>,
+    with_xp: bool,
+) {
+    let (leads, objectives, title) = match timeline {
+        Some(t) if !t.leads.is_empty() => (t.leads.clone(), t.objectives.as_slice(), " Gold lead "),
+        _ => (
+            estimated_leads(game),
+            [].as_slice(),
+            " Gold lead, from end of game totals ",
+        ),
+    };
+    let with_xp = with_xp && timeline.is_some_and(|t| !t.leads.is_empty());
+    let title = match with_xp {
+        true => " Gold & XP leads ",
+        false => title,
+    };
+
+    let gold: Vec<(f64, f64)> = leads
+        .iter()
+        .map(|l| (l.minute as f64, l.gold as f64))
+        .collect();
+    let xp: Vec<(f64, f64)> = leads
+        .iter()
+        .map(|l| (l.minute as f64, l.xp as f64))
+        .collect();
+    let last_minute = leads.last().map_or(1, |l| l.minute.max(1)) as f64;
+    // Keep zero in the middle so it's clear who is ahead
+    let bound = leads
+        .iter()
+        .flat_map(|l| [l.gold.abs(), if with_xp { l.xp.abs() } else { 0 }])
+        .max()
+        .unwrap_or(0)
+        .max(1000) as f64;
+
+    // Objectives sit on a rail along the top for blue and the bottom for red
+    let markers: Vec<_> = OBJECTIVES
+        .iter()
+        .map(|&(kind, name, colour)| {
+            let points: Vec<(f64, f64)> = objectives
+                .iter()
+                .filter(|o| o.kind == kind)
+                .map(|o| match o.team_id {
+                    100 => (o.minute, bound * 0.9),
+                    _ => (o.minute, -bound * 0.9),
+                })
+                .collect();
+            (name, colour, points)
+        })
+        .filter(|(_, _, points)| !points.is_empty())
+        .collect();
+
+    let mut datasets = vec![
+        Dataset::default()
+            .name("Gold")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(tailwind::YELLOW.c400))
+            .data(&gold),
+    ];
+    if with_xp {
+        datasets.push(
+            Dataset::default()
+                .name("XP")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(tailwind::CYAN.c400))
+                .data(&xp),
+        );
+    }
+    for (name, colour, points) in &markers {
+        datasets.push(
+            Dataset::default()
+                .name(*name)
+                .marker(Marker::Dot)
+                .graph_type(GraphType::Scatter)
+                .style(Style::default().fg(*colour))
+                .data(points),
+        );
+    }
+
+    let name = |team: &str, fallback: &'static str| match team {
+        "" => fallback.to_string(),
+        team => team.to_string(),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_bottom(Line::from(vec![
+            Span::raw(" Above zero "),
+            Span::styled(name(&game.team_1, "blue side"), Style::default().fg(BLUE)),
+            Span::raw(" is ahead, below "),
+            Span::styled(name(&game.team_2, "red side"), Style::default().fg(RED)),
+            Span::raw(" "),
+        ]));
+    let thousands = |v: f64| format!("{:+.1}k", v / 1000.0);
+    Chart::new(datasets)
+        .block(block)
+        // The objectives make for a tall legend
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
+        .x_axis(
+            Axis::default()
+                .title("Minute")
+                .bounds([0.0, last_minute])
+                .labels(["0".to_string(), format!("{last_minute:.0}")]),
+        )
+        .y_axis(Axis::default().bounds([-bound, bound]).labels([
+            thousands(-bound),
+            "0".to_string(),
+            thousands(bound),
+        ]))
+        .render(area, buf);
+}
+
+/// An even start and the final gold difference, for games without a timeline.
+fn estimated_leads(game: &Game) -> Vec<TeamLead> {
+    let gold = game
+        .info
+        .participants
+        .iter()
+        .map(|p| match p.team_id {
+            100 => p.gold_earned as i64,
+            _ => -(p.gold_earned as i64),
+        })
+        .sum();
+    vec![
+        TeamLead {
+            minute: 0,
+            gold: 0,
+            xp: 0,
+        },
+        TeamLead {
+            minute: game.info.game_duration / 60,
+            gold,
+            xp: 0,
+        },
+    ]
+}
@@ -0,0 +1,389 @@
+//! The game detail screen, opened from the match browser.
+//!
+//! Manual games only record a handful of fields, so everything here shows
+//! `-` or a note rather than a zero when a value wasn't recorded.
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Style, Stylize, palette::tailwind},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, Tabs, Widget, Wrap},
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    app::DetailTab,
+    riot::model::{ObjectiveDto, ParticipantDto},
+    sql::{
+        draft::DraftAction,
+        schema::{DraftEntry, Game, GameTimeline},
+    },
+};
+
+use super::chart::draw_lead_chart;
+use super::lolui::{TableColors, role_order, team_order, truncate_with_ellipsis_width};
+
+const BLUE: Color = tailwind::BLUE.c400;
+const RED: Color = tailwind::RED.c400;
+
+pub fn draw_game_detail(
+    buf: &mut Buffer,
+    area: Rect,
+    game: &Game,
+    draft: &[DraftEntry],
+    timeline: Option<&GameTimeline>,
+    tab: DetailTab,
+) {
+    let [tabs_area, content_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).areas(area);
+
+    let titles =
+        DetailTab::iter().map(|t| t.title().fg(tailwind::SLATE.c200).bg(tailwind::SLATE.c900));
+    Tabs::new(titles)
+        .select(tab as usize)
+        .padding("", "")
+        .divider(" ")
+        .render(tabs_area, buf);
+
+    match tab {
+        DetailTab::Scoreboard => draw_full_scoreboard(buf, content_area, game),
+        DetailTab::Objectives => draw_objectives(buf, content_area, game),
+        DetailTab::Shares => draw_shares(buf, content_area, game),
+        DetailTab::Leads => draw_lead_chart(buf, content_area, game, timeline, true),
+        DetailTab::Draft => draw_draft(buf, content_area, game, draft),
+    }
+}
+
+/// The blue and red side's players, each in role order.
+fn sides(game: &Game) -> [(u64, String, Vec<&ParticipantDto>); 2] {
+    let mut participants: Vec<&ParticipantDto> = game.info.participants.iter().collect();
+    participants.sort_by_key(|p| (team_order(&p.team_id), role_order(&p.team_position)));
+    let team = |id: u64, name: &str, fallback: &str| {
+        let players = participants
+            .iter()
+            .filter(|p| p.team_id == id)
+            .copied()
+            .collect();
+        let name = match name {
+            "" => fallback.to_string(),
+            name => name.to_string(),
+        };
+        (id, name, players)
+    };
+    [
+        team(100, &game.team_1, "Blue side"),
+        team(200, &game.team_2, "Red side"),
+    ]
+}
+
+fn opt<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+fn right<'a>(text: String) -> Cell<'a> {
+    Cell::from(Text::from(text).alignment(Alignment::Right))
+}
+
+fn spell_name(id: u64) -> String {
+    match id {
+        1 => "Cleanse",
+        3 => "Exhaust",
+        4 => "Flash",
+        6 => "Ghost",
+        7 => "Heal",
+        11 => "Smite",
+        12 => "Teleport",
+        13 => "Clarity",
+        14 => "Ignite",
+        21 => "Barrier",
+        32 => "Mark",
+        _ => return id.to_string(),
+    }
+    .to_string()
+}
+
+fn draw_full_scoreboard(buf: &mut Buffer, area: Rect, game: &Game) {
+    let colours = TableColors::new(&tailwind::SLATE);
+    let minutes = game.info.game_duration as f64 / 60.0;
+    let winner = game.info.winner();
+
+    let chunks = Layout::vertical([Constraint::Length(8), Constraint::Length(8)]).split(area);
+    for ((side, name, players), chunk) in sides(game).into_iter().zip(chunks.iter()) {
+        let header = [
+            "Player", "Champion", "Spells", "K/D/A", "CS", "CS/m", "CS@10", "Gold", "Damage",
+            "Vision", "Wards", "Items",
+        ]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(Style::default().fg(colours.header_fg).bg(colours.header_bg));
+
+        let rows = players.iter().enumerate().map(|(i, p)| {
+            let spells = match (p.summoner1_id, p.summoner2_id) {
+                (None, None) => "-".to_string(),
+                (a, b) => format!(
+                    "{}/{}",
+                    a.map_or("-".to_string(), spell_name),
+                    b.map_or("-".to_string(), spell_name)
+                ),
+            };
+            let wards = match (p.wards_placed, p.wards_killed, p.detector_wards_placed) {
+                (None, None, None) => "-".to_string(),
+                (placed, killed, control) => {
+                    format!("{}/{}/{}", opt(placed), opt(killed), opt(control))
+                }
+            };
+            let items = p.items();
+            let items = match items.is_empty() {
+                true => "-".to_string(),
+                false => items
+                    .iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            };
+            Row::new(vec![
+                Cell::from(truncate_with_ellipsis_width(p.name(), 18)),
+                Cell::from(p.champion_name.clone()),
+                Cell::from(spells),
+                right(format!("{}/{}/{}", p.kills, p.deaths, p.assists)),
+                right(p.cs().to_string()),
+                right(match minutes > 0.0 {
+                    true => format!("{:.1}", p.cs() as f64 / minutes),
+                    false => "-".to_string(),
+                }),
+                right(opt(p.cs_at_10())),
+                right(format!("{:.1}k", p.gold_earned as f64 / 1000.0)),
+                right(opt(p.total_damage_dealt_to_champions)),
+                right(opt(p.vision_score)),
+                right(wards),
+                Cell::from(items),
+            ])
+            .bg(match i % 2 {
+                0 => colours.normal_row_color,
+                _ => colours.alt_row_color,
+            })
+        });
+
+        let result = match winner {
+            Some(w) if w == side => " | Victory",
+            Some(_) => " | Loss",
+            None => "",
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(18),
+                Constraint::Length(12),
+                Constraint::Length(16),
+                Constraint::Length(8),
+                Constraint::Length(4),
+                Constraint::Length(5),
+                Constraint::Length(5),
+                Constraint::Length(6),
+                Constraint::Length(7),
+                Constraint::Length(6),
+                Constraint::Length(8),
+                Constraint::Min(10),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {name}{result} "))
+                .border_style(Style::default().fg(if side == 100 { BLUE } else { RED })),
+        );
+        Widget::render(table, *chunk, buf);
+    }
+}
+
+fn draw_objectives(buf: &mut Buffer, area: Rect, game: &Game) {
+    let block = Block::default().borders(Borders::ALL).title(" Objectives ");
+    let [blue, red] = sides(game);
+    let team = |id: u64| game.info.teams.iter().find(|t| t.team_id == id);
+
+    let (Some(blue_team), Some(red_team)) = (team(100), team(200)) else {
+        Paragraph::new("No objectives were recorded for this game.")
+            .block(block)
+            .render(area, buf);
+        return;
+    };
+
+    // A first is marked with a star, and only API games record them
+    let cell = |objective: &Option<ObjectiveDto>| match objective {
+        Some(o) if o.first == Some(true) => right(format!("★ {}", o.kills)),
+        Some(o) => right(o.kills.to_string()),
+        None => right("-".to_string()),
+    };
+    let (b, r) = (&blue_team.objectives, &red_team.objectives);
+    let rows = [
+        ("Kills", &b.champion, &r.champion),
+        ("Towers", &b.tower, &r.tower),
+        ("Inhibitors", &b.inhibitor, &r.inhibitor),
+        ("Dragons", &b.dragon, &r.dragon),
+        ("Grubs", &b.horde, &r.horde),
+        ("Heralds", &b.rift_herald, &r.rift_herald),
+        ("Barons", &b.baron, &r.baron),
+        ("Atakhan", &b.atakhan, &r.atakhan),
+    ]
+    .into_iter()
+    .map(|(label, blue, red)| Row::new(vec![Cell::from(label), cell(blue), cell(red)]));
+
+    let header = Row::new(vec![
+        Cell::from(""),
+        right(blue.1).fg(BLUE),
+        right(red.1).fg(RED),
+    ])
+    .bold();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(12),
+            Constraint::Length(20),
+            Constraint::Length(20),
+        ],
+    )
+    .header(header)
+    .block(block);
+    Widget::render(table, area, buf);
+}
+
+fn draw_shares(buf: &mut Buffer, area: Rect, game: &Game) {
+    let [damage_area, gold_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+    let sides = sides(game);
+
+    let recorded = game
+        .info
+        .participants
+        .iter()
+        .any(|p| p.total_damage_dealt_to_champions.is_some());
+    let damage_block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Damage share ");
+    match recorded {
+        true => draw_share_bars(buf, damage_area, damage_block, &sides, |p| {
+            p.total_damage_dealt_to_champions.unwrap_or(0)
+        }),
+        false => Paragraph::new("No damage was recorded for this game.")
+            .wrap(Wrap { trim: true })
+            .block(damage_block)
+            .render(damage_area, buf),
+    }
+
+    let gold_block = Block::default().borders(Borders::ALL).title(" Gold share ");
+    draw_share_bars(buf, gold_area, gold_block, &sides, |p| p.gold_earned);
+}
+
+/// One bar per player showing their share of their team's total, scaled so
+/// the largest share in the game fills the width.
+fn draw_share_bars(
+    buf: &mut Buffer,
+    area: Rect,
+    block: Block,
+    sides: &[(u64, String, Vec<&ParticipantDto>)],
+    value: impl Fn(&ParticipantDto) -> u64,
+) {
+    const LABEL: usize = 16;
+    const PERCENT: usize = 7;
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let shares: Vec<Vec<f64>> = sides
+        .iter()
+        .map(|(_, _, players)| {
+            let total: u64 = players.iter().map(|p| value(p)).sum();
+            players
+                .iter()
+                .map(|p| match total {
+                    0 => 0.0,
+                    total => value(p) as f64 / total as f64,
+                })
+                .collect()
+        })
+        .collect();
+    let max = shares.iter().flatten().copied().fold(0.0, f64::max);
+    let bar_width = (inner.width as usize).saturating_sub(LABEL + PERCENT + 2);
+
+    let mut lines = Vec::new();
+    for ((side, name, players), shares) in sides.iter().zip(&shares) {
+        let colour = if *side == 100 { BLUE } else { RED };
+        lines.push(Line::styled(
+            name.clone(),
+            Style::default().fg(colour).bold(),
+        ));
+        for (p, share) in players.iter().zip(shares) {
+            let width = match max > 0.0 {
+                true => (share / max * bar_width as f64).round() as usize,
+                false => 0,
+            };
+            lines.push(Line::from(vec![
+                Span::raw(format!(
+                    "{:<LABEL$} ",
+                    truncate_with_ellipsis_width(p.name(), LABEL)
+                )),
+                Span::styled("█".repeat(width), Style::default().fg(colour)),
+                Span::raw(" ".repeat(bar_width - width)),
+                Span::raw(format!(" {:>5.1}%", share * 100.0)),
+            ]));
+        }
+        lines.push(Line::default());
+    }
+    Paragraph::new(lines).render(inner, buf);
+}
+
+fn draw_draft(buf: &mut Buffer, area: Rect, game: &Game, draft: &[DraftEntry]) {
+    let block = Block::default().borders(Borders::ALL);
+    if draft.is_empty() {
+        Paragraph::new("No draft was recorded for this game. Press (d) to enter one.")
+            .block(block.title(" Draft "))
+            .render(area, buf);
+        return;
+    }
+
+    let [blue, red] = sides(game);
+    let header = Row::new(vec![
+        Cell::from("#"),
+        Cell::from(""),
+        Cell::from(blue.1).fg(BLUE),
+        Cell::from(red.1).fg(RED),
+    ])
+    .bold();
+    let rows = draft.iter().map(|entry| {
+        let champion = entry.champion.clone().unwrap_or_else(|| "-".to_string());
+        let (blue, red) = match entry.team_id {
+            100 => (champion, String::new()),
+            _ => (String::new(), champion),
+        };
+        let style = match entry.action {
+            DraftAction::Ban => Style::default().fg(tailwind::SLATE.c400),
+            DraftAction::Pick => Style::default(),
+        };
+        Row::new(vec![
+            entry.seq.to_string(),
+            entry.action.to_string(),
+            blue,
+            red,
+        ])
+        .style(style)
+    });
+
+    // Payload bans only say which turn they were, not where in the draft
+    let title = match draft.iter().any(|e| e.manual) {
+        true => " Draft ",
+        false => " Bans, by pick turn ",
+    };
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(3),
+            Constraint::Length(5),
+            Constraint::Length(20),
+            Constraint::Length(20),
+        ],
+    )
+    .header(header)
+    .block(block.title(title));
+    Widget::render(table, area, buf);
+}
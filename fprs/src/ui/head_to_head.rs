@@ -0,0 +1,301 @@
+//! The head-to-head screen, comparing two teams picked from the stats screen.
+//!
+//! Everything but the meetings is each team's record against every opponent,
+//! so two teams that have never played can still be compared.
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, palette::tailwind},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Cell, Row, Table, Widget},
+};
+
+use crate::sql::schema::{HeadToHead, Series, SeriesGame, TeamProfile, TeamStats};
+
+use super::lolui::{TableColors, draw_series_results};
+
+/// The first team picked, then the second.
+const TEAM_COLOURS: [Color; 2] = [tailwind::SKY.c400, tailwind::AMBER.c400];
+
+pub fn draw_head_to_head(buf: &mut Buffer, area: Rect, h2h: &HeadToHead) {
+    let [top, bottom] = Layout::vertical([Constraint::Length(13), Constraint::Min(8)]).areas(area);
+    let [compare_area, meetings_area] =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(top);
+    let [series_area, games_area] =
+        Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .areas(meetings_area);
+    let role_areas: [Rect; 2] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(bottom);
+
+    draw_comparison(buf, compare_area, h2h);
+    let series: Vec<&Series> = h2h.series.iter().collect();
+    draw_series_results(buf, series_area, &series);
+    draw_games(buf, games_area, &h2h.games);
+    for (i, team) in h2h.teams.iter().enumerate() {
+        draw_role_lines(buf, role_areas[i], team, TEAM_COLOURS[i]);
+    }
+}
+
+/// A value for each team, with the better one in bold when there is one.
+fn comparison_row<'a>(
+    label: &'a str,
+    values: [String; 2],
+    scores: Option<[Option<f64>; 2]>,
+) -> Row<'a> {
+    let better = match scores {
+        Some([Some(a), Some(b)]) if a > b => Some(0),
+        Some([Some(a), Some(b)]) if b > a => Some(1),
+        _ => None,
+    };
+    let [a, b] = values;
+    let cell = |i: usize, value: String| {
+        let style = match better == Some(i) {
+            true => Style::default().add_modifier(Modifier::BOLD),
+            false => Style::default().fg(tailwind::SLATE.c400),
+        };
+        Cell::from(Text::from(value).style(style).alignment(Alignment::Right))
+    };
+    Row::new([Cell::from(label), cell(0, a), cell(1, b)])
+}
+
+fn draw_comparison(buf: &mut Buffer, area: Rect, h2h: &HeadToHead) {
+    let colours = TableColors::new(&tailwind::EMERALD);
+    let [a, b] = [&h2h.teams[0].stats, &h2h.teams[1].stats];
+    let [(a_series, a_games), (b_series, b_games)] = h2h.record();
+
+    let side = |wins: u64, games: u64| match games {
+        0 => "-".to_string(),
+        n => format!(
+            "{}-{} ({:.0}%)",
+            wins,
+            n - wins,
+            wins as f64 * 100.0 / n as f64
+        ),
+    };
+    let side_rate = |wins: u64, games: u64| match games {
+        0 => None,
+        n => Some(wins as f64 / n as f64),
+    };
+    let length = |t: &TeamStats| match t.games {
+        0 => "-".to_string(),
+        _ => format!(
+            "{:02}:{:02}",
+            t.game_length_avg / 60,
+            t.game_length_avg % 60
+        ),
+    };
+    let rate = |r: Option<f64>| match r {
+        Some(r) => format!("{r:.0}%"),
+        None => "-".to_string(),
+    };
+    let first = |label, r: fn(&TeamStats) -> Option<f64>| {
+        comparison_row(label, [rate(r(a)), rate(r(b))], Some([r(a), r(b)]))
+    };
+
+    let rows = vec![
+        comparison_row(
+            "Series won",
+            [a_series.to_string(), b_series.to_string()],
+            Some([Some(a_series as f64), Some(b_series as f64)]),
+        ),
+        comparison_row(
+            "Games won",
+            [a_games.to_string(), b_games.to_string()],
+            Some([Some(a_games as f64), Some(b_games as f64)]),
+        ),
+        comparison_row(
+            "Win rate",
+            [side(a.wins, a.games), side(b.wins, b.games)],
+            Some([side_rate(a.wins, a.games), side_rate(b.wins, b.games)]),
+        ),
+        comparison_row(
+            "Blue side",
+            [
+                side(a.blue_wins, a.blue_games),
+                side(b.blue_wins, b.blue_games),
+            ],
+            Some([
+                side_rate(a.blue_wins, a.blue_games),
+                side_rate(b.blue_wins, b.blue_games),
+            ]),
+        ),
+        comparison_row(
+            "Red side",
+            [side(a.red_wins, a.red_games), side(b.red_wins, b.red_games)],
+            Some([
+                side_rate(a.red_wins, a.red_games),
+                side_rate(b.red_wins, b.red_games),
+            ]),
+        ),
+        // Shorter isn't better, so neither side is picked out
+        comparison_row("Game length", [length(a), length(b)], None),
+        first("First blood", |t| t.fb_percentage),
+        first("First tower", |t| t.ft_percentage),
+        first("First dragon", |t| t.fd_percentage),
+    ];
+    let rows = rows.into_iter().enumerate().map(|(i, row)| {
+        row.style(Style::new().fg(colours.row_fg).bg(match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        }))
+    });
+
+    let header = Row::new([
+        Cell::from(""),
+        Cell::from(Text::from(a.team_name.clone()).alignment(Alignment::Right))
+            .style(Style::default().fg(TEAM_COLOURS[0])),
+        Cell::from(Text::from(b.team_name.clone()).alignment(Alignment::Right))
+            .style(Style::default().fg(TEAM_COLOURS[1])),
+    ])
+    .style(
+        Style::default()
+            .bg(colours.header_bg)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    Table::new(
+        rows,
+        [
+            Constraint::Length(13),
+            Constraint::Min(10),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Head to head ")
+            .title_bottom(" Meetings first, then against everyone "),
+    )
+    .render(area, buf);
+}
+
+fn draw_games(buf: &mut Buffer, area: Rect, games: &[SeriesGame]) {
+    let colours = TableColors::new(&tailwind::EMERALD);
+    let header = ["Date", "#", "Blue", "Red"]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(Style::default().fg(colours.header_fg).bg(colours.header_bg));
+
+    // Most recent first, like the series
+    let rows = games.iter().rev().enumerate().map(|(i, g)| {
+        let team = |team: &str| match team == g.winner {
+            true => {
+                Cell::from(team.to_string()).style(Style::default().add_modifier(Modifier::BOLD))
+            }
+            false => Cell::from(team.to_string()),
+        };
+        Row::new([
+            Cell::from(g.date.clone().unwrap_or_else(|| "-".to_string())),
+            Cell::from(g.game_number.map_or("-".to_string(), |n| n.to_string())),
+            team(&g.team_1),
+            team(&g.team_2),
+        ])
+        .style(Style::new().fg(colours.row_fg).bg(match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        }))
+    });
+
+    let block = Block::default().borders(Borders::ALL).title("Games");
+    match games.is_empty() {
+        true => Text::from("These teams haven't played each other.")
+            .style(Style::default().fg(tailwind::SLATE.c400))
+            .render(block.inner(area), buf),
+        false => Table::new(
+            rows,
+            [
+                Constraint::Length(10),
+                Constraint::Length(2),
+                Constraint::Min(5),
+                Constraint::Min(5),
+            ],
+        )
+        .header(header)
+        .render(block.inner(area), buf),
+    }
+    block.render(area, buf);
+}
+
+/// The team's most played player in each role with their line for the role,
+/// and the team's most picked champion there.
+fn draw_role_lines(buf: &mut Buffer, area: Rect, team: &TeamProfile, colour: Color) {
+    let colours = TableColors::new(&tailwind::EMERALD);
+    let header = [
+        "Role",
+        "Player",
+        "Games",
+        "Win %",
+        "KDA",
+        "GPM",
+        "DPM",
+        "CSD@10",
+        "Most picked",
+    ]
+    .into_iter()
+    .map(Cell::from)
+    .collect::<Row>()
+    .style(Style::default().fg(colours.header_fg).bg(colours.header_bg));
+
+    let dash = || "-".to_string();
+    let rows = team.roles.iter().enumerate().map(|(i, line)| {
+        let stats = line.stats.as_ref();
+        let right = |s: String| Cell::from(Text::from(s).alignment(Alignment::Right));
+        Row::new([
+            Cell::from(line.role.clone()),
+            Cell::from(line.player.clone().unwrap_or_else(dash)),
+            right(stats.map_or_else(dash, |s| s.games.to_string())),
+            right(stats.map_or_else(dash, |s| format!("{}%", s.win_percent))),
+            right(stats.map_or_else(dash, |s| format!("{:.2}", s.kda))),
+            right(stats.map_or_else(dash, |s| s.gpm.to_string())),
+            right(
+                stats
+                    .and_then(|s| s.dpm)
+                    .map_or_else(dash, |d| d.to_string()),
+            ),
+            right(
+                stats
+                    .and_then(|s| s.cd10)
+                    .map_or_else(dash, |c| format!("{c:+.1}")),
+            ),
+            Cell::from(
+                line.champion
+                    .as_ref()
+                    .map_or_else(dash, |(champion, n)| format!("{champion} ({n})")),
+            ),
+        ])
+        .style(Style::new().fg(colours.row_fg).bg(match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        }))
+    });
+
+    Table::new(
+        rows,
+        [
+            Constraint::Length(7),
+            Constraint::Min(10),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(6),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Line::from(vec![
+                Span::raw(" "),
+                Span::styled(team.stats.team_name.clone(), Style::default().fg(colour)),
+                Span::raw(" by role "),
+            ]))
+            .title_bottom(" Player lines need four games in the role "),
+    )
+    .render(area, buf);
+}
@@ -1,10 +1,9 @@
 use color_eyre::owo_colors::OwoColorize;
 use pad::PadStr;
-use reqwest::header;
-use std::{cmp::Ordering, collections::HashMap, fmt::format};
+use std::cmp::Ordering;
 use unicode_width::UnicodeWidthChar;
 
-use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
@@ -19,53 +18,46 @@ use ratatui::{
     text::{Line, Span, Text},
     widgets::{
         Block, BorderType, Borders, Cell, HighlightSpacing, Paragraph, Row, StatefulWidget, Table,
-        Widget,
+        Widget, Wrap,
     },
 };
-use serde_json::Value;
 use unicode_width::UnicodeWidthStr;
 
 use crate::{
     app::App,
+    riot::model::ParticipantDto,
     sql::schema::{
-        ChampionHistory, Game, PlayerChampionStats, PlayerOverallStats, PlayerRoleStats,
+        ChampionHistory, Game, Matchup, PlayerChampionStats, PlayerOverallStats, PlayerRoleStats,
+        PlayerStats, Series,
     },
 };
 
+use super::ratings::draw_team_ratings;
+
 const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
 const ROLES: [&'static str; 5] = ["TOP", "JUNGLE", "MIDDLE", "BOTTOM", "UTILITY"];
 
-struct TableColors {
-    buffer_bg: Color,
-    header_bg: Color,
-    header_fg: Color,
-    row_fg: Color,
-    selected_row_style_fg: Color,
-    selected_column_style_fg: Color,
-    selected_cell_style_fg: Color,
-    normal_row_color: Color,
-    alt_row_color: Color,
-    footer_border_color: Color,
+pub(super) struct TableColors {
+    pub(super) header_bg: Color,
+    pub(super) header_fg: Color,
+    pub(super) row_fg: Color,
+    pub(super) normal_row_color: Color,
+    pub(super) alt_row_color: Color,
 }
 
 impl TableColors {
-    const fn new(color: &tailwind::Palette) -> Self {
+    pub(super) const fn new(color: &tailwind::Palette) -> Self {
         Self {
-            buffer_bg: tailwind::SLATE.c950,
             header_bg: color.c900,
             header_fg: tailwind::SLATE.c200,
             row_fg: tailwind::SLATE.c200,
-            selected_row_style_fg: color.c400,
-            selected_column_style_fg: color.c400,
-            selected_cell_style_fg: color.c600,
             normal_row_color: tailwind::SLATE.c950,
             alt_row_color: tailwind::SLATE.c900,
-            footer_border_color: color.c400,
         }
     }
 }
 
-fn role_order(role: &str) -> usize {
+pub(super) fn role_order(role: &str) -> usize {
     match role {
         "TOP" => 0,
         "JUNGLE" => 1,
@@ -87,7 +79,7 @@ pub const DASHED: Set = Set {
     horizontal_bottom: "╌",
 };
 
-fn team_order(team: &u64) -> usize {
+pub(super) fn team_order(team: &u64) -> usize {
     match team {
         100 => 0,
         200 => 1,
@@ -96,38 +88,30 @@ fn team_order(team: &u64) -> usize {
 }
 
 pub fn draw_scoreboard(buf: &mut Buffer, area: Rect, game: &Game) {
-    let game_data = game.data.clone();
+    let info = &game.info;
     // Parent block
-    let game_duration = game_data
-        .get("info")
-        .and_then(|i| i.get("gameDuration"))
-        .and_then(|d| d.as_u64())
-        .unwrap_or_else(|| 0);
-
-    let game_end_timestamp = Local
-        .timestamp_opt(
-            game_data
-                .get("info")
-                .and_then(|i| i.get("gameEndTimestamp"))
-                .and_then(|d| d.as_i64())
-                .unwrap_or_else(|| 0)
-                / 1_000,
-            0,
-        )
-        .unwrap();
+    let game_duration = info.game_duration;
+
+    let game_date = match (info.game_end_timestamp, &info.game_end_date) {
+        (Some(ts), _) => Local
+            .timestamp_opt(ts / 1_000, 0)
+            .single()
+            .map(|d| d.format("%d/%m/%Y").to_string()),
+        (None, Some(date)) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .ok()
+            .map(|d| d.format("%d/%m/%Y").to_string()),
+        (None, None) => None,
+    }
+    .unwrap_or_else(|| "Unknown".to_string());
 
     let block = Block::default()
         .title(format!(
-            " {} | {:02}:{:02} | {} ",
-            game_data
-                .get("info")
-                .and_then(|info| info.get("gameId"))
-                .and_then(|gid| gid.as_u64())
-                .map(|gid| gid.to_string())
-                .unwrap_or_else(|| "Unknown".to_string()),
+            " {} | {:02}:{:02} | {}{} ",
+            info.game_id,
             game_duration / 60,
             game_duration % 60,
-            game_end_timestamp.format("%d/%m/%Y").to_string()
+            game_date,
+            game.patch().map(|p| format!(" | {p}")).unwrap_or_default()
         ))
         .borders(Borders::ALL)
         .style(Style::default());
@@ -153,134 +137,56 @@ pub fn draw_scoreboard(buf: &mut Buffer, area: Rect, game: &Game) {
 
     let col_widths = [18, 12, 10, 7, 7];
 
-    let mut lines: Vec<Line> = Vec::new();
-
-    let mut participants = game_data
-        .get("info")
-        .and_then(|info| info.get("participants"))
-        .and_then(|p| p.as_array())
-        .cloned()
-        .unwrap_or(Vec::new());
-
-    participants.sort_by_key(|p| {
-        let role = p.get("teamPosition").and_then(|v| v.as_str()).unwrap_or("");
-        let team = p
-            .get("teamId")
-            .and_then(|v| v.as_u64())
-            .unwrap_or_else(|| 0);
-
-        (team_order(&team), role_order(role))
-    });
-
-    let mut stats: HashMap<u64, (u64, u64, u64, u64, u64)> = HashMap::new();
-    let mut blue_win: bool = false;
-
-    for i in 0..participants.len() / 2 {
-        let p = participants.get(i).unwrap_or_else(|| &Value::Null);
-        let team_id = p.get("teamId").and_then(|t| t.as_u64()).unwrap_or(0);
-        let kills = p.get("kills").and_then(|n| n.as_u64()).unwrap_or(0);
-        let deaths = p.get("deaths").and_then(|n| n.as_u64()).unwrap_or(0);
-        let assists = p.get("assists").and_then(|n| n.as_u64()).unwrap_or(0);
-        let damage = p
-            .get("totalDamageDealtToChampions")
-            .and_then(|n| n.as_u64())
-            .unwrap_or(0);
-        let gold = p.get("goldEarned").and_then(|n| n.as_u64()).unwrap_or(0);
-
-        blue_win = p.get("win").and_then(|w| w.as_bool()).unwrap_or(false);
-
-        stats
-            .entry(team_id)
-            .and_modify(|e| {
-                e.0 += kills;
-                e.1 += deaths;
-                e.2 += assists;
-                e.3 += damage;
-                e.4 += gold;
-            })
-            .or_insert((kills, deaths, assists, damage, gold));
-        lines.push(map_participant_line(p, &col_widths));
-    }
-    let (k, d, a, _, g) = stats.get(&100).cloned().unwrap_or((0, 0, 0, 0, 0));
-    Paragraph::new(Text::from(lines.clone()))
-        .block(team_block.clone().title(format!(
-            " {} | 󰞇 {:>2}/{:>2}/{:>2} |  {:.1}k | {} ",
-            game.team_1.to_string(),
-            k,
-            d,
-            a,
-            g.to_owned() as f64 / 1000f64,
-            if blue_win { "Victory" } else { "Loss" },
-        )))
-        .render(inner_chunks[0], buf);
-    lines.clear();
-
-    for i in participants.len() / 2..participants.len() {
-        let p = participants.get(i).unwrap_or_else(|| &Value::Null);
-        let team_id = p.get("teamId").and_then(|t| t.as_u64()).unwrap_or(0);
-        let kills = p.get("kills").and_then(|n| n.as_u64()).unwrap_or(0);
-        let deaths = p.get("deaths").and_then(|n| n.as_u64()).unwrap_or(0);
-        let assists = p.get("assists").and_then(|n| n.as_u64()).unwrap_or(0);
-        let damage = p
-            .get("totalDamageDealtToChampions")
-            .and_then(|n| n.as_u64())
-            .unwrap_or(0);
-        let gold = p.get("goldEarned").and_then(|n| n.as_u64()).unwrap_or(0);
-        stats
-            .entry(team_id)
-            .and_modify(|e| {
-                e.0 += kills;
-                e.1 += deaths;
-                e.2 += assists;
-                e.3 += damage;
-                e.4 += gold;
-            })
-            .or_insert((kills, deaths, assists, damage, gold));
-        lines.push(map_participant_line(p, &col_widths));
+    let mut participants: Vec<&ParticipantDto> = info.participants.iter().collect();
+    participants.sort_by_key(|p| (team_order(&p.team_id), role_order(&p.team_position)));
+
+    let winner = info.winner();
+    let (blue, red) = participants.split_at(participants.len() / 2);
+
+    for (side, players, team_name, chunk) in [
+        (100, blue, &game.team_1, inner_chunks[0]),
+        (200, red, &game.team_2, inner_chunks[2]),
+    ] {
+        let (k, d, a, g) = players.iter().fold((0, 0, 0, 0), |acc, p| {
+            (
+                acc.0 + p.kills,
+                acc.1 + p.deaths,
+                acc.2 + p.assists,
+                acc.3 + p.gold_earned,
+            )
+        });
+        let lines: Vec<Line> = players
+            .iter()
+            .map(|p| map_participant_line(p, &col_widths))
+            .collect();
+        Paragraph::new(Text::from(lines))
+            .block(team_block.clone().title(format!(
+                " {} | 󰞇 {:>2}/{:>2}/{:>2} |  {:.1}k | {} ",
+                team_name,
+                k,
+                d,
+                a,
+                g as f64 / 1000f64,
+                if winner == Some(side) {
+                    "Victory"
+                } else {
+                    "Loss"
+                },
+            )))
+            .render(chunk, buf);
     }
 
-    let (k, d, a, _, g) = stats.get(&200).cloned().unwrap_or((0, 0, 0, 0, 0));
-    Paragraph::new(Text::from(lines.clone()))
-        .block(team_block.clone().title(format!(
-            " {} | 󰞇 {:>2}/{:>2}/{:>2} |  {:.1}k | {} ",
-            game.team_2.to_string(),
-            k,
-            d,
-            a,
-            g.to_owned() as f64 / 1000f64,
-            if !blue_win { "Victory" } else { "Loss" },
-        )))
-        .render(inner_chunks[2], buf);
-    lines.clear();
+    if let Some(notes) = &game.notes {
+        Paragraph::new(notes.as_str())
+            .wrap(Wrap { trim: true })
+            .block(team_block.title(" Notes "))
+            .render(inner_chunks[3], buf);
+    }
 }
 
-fn map_participant_line<'a>(p: &Value, col_widths: &[usize; 5]) -> Line<'a> {
-    let player_name = p
-        .get("riotIdGameName")
-        .and_then(|n| n.as_str())
-        .unwrap_or_else(|| "Unknown");
-    let trunc_name = truncate_with_ellipsis_width(player_name, col_widths[0]);
-    let champion_name = p
-        .get("championName")
-        .and_then(|n| n.as_str())
-        .unwrap_or_else(|| "Unknown");
-    let kills = p.get("kills").and_then(|n| n.as_u64()).unwrap_or_else(|| 0);
-    let deaths = p
-        .get("deaths")
-        .and_then(|n| n.as_u64())
-        .unwrap_or_else(|| 0);
-    let assists = p
-        .get("assists")
-        .and_then(|n| n.as_u64())
-        .unwrap_or_else(|| 0);
-    let damage = p
-        .get("totalDamageDealtToChampions")
-        .and_then(|n| n.as_u64())
-        .unwrap_or_else(|| 0);
-    let gold = p
-        .get("goldEarned")
-        .and_then(|n| n.as_u64())
-        .unwrap_or_else(|| 0);
+fn map_participant_line<'a>(p: &ParticipantDto, col_widths: &[usize; 5]) -> Line<'a> {
+    let trunc_name = truncate_with_ellipsis_width(p.name(), col_widths[0]);
+    let damage = p.total_damage_dealt_to_champions.unwrap_or(0);
     Line::from(vec![
         Span::styled(
             format!(
@@ -290,13 +196,13 @@ fn map_participant_line<'a>(p: &Value, col_widths: &[usize; 5]) -> Line<'a> {
             Style::default(),
         ),
         Span::styled(
-            format!(" {:>width$} ", champion_name, width = col_widths[1]),
+            format!(" {:>width$} ", p.champion_name, width = col_widths[1]),
             Style::default(),
         ),
         Span::styled(
             format!(
                 " {:<width$} ",
-                format!("󰞇 {:>2}/{:>2}/{:>2}", kills, deaths, assists),
+                format!("󰞇 {:>2}/{:>2}/{:>2}", p.kills, p.deaths, p.assists),
                 width = col_widths[2]
             ),
             Style::default(),
@@ -312,7 +218,7 @@ fn map_participant_line<'a>(p: &Value, col_widths: &[usize; 5]) -> Line<'a> {
         Span::styled(
             format!(
                 " {:>width$} ",
-                format!(" {:2.1}k", gold as f64 / 1000f64),
+                format!(" {:2.1}k", p.gold_earned as f64 / 1000f64),
                 width = col_widths[4]
             ),
             Style::default(),
@@ -320,7 +226,7 @@ fn map_participant_line<'a>(p: &Value, col_widths: &[usize; 5]) -> Line<'a> {
     ])
 }
 
-fn truncate_with_ellipsis_width(s: &str, max_width: usize) -> String {
+pub(super) fn truncate_with_ellipsis_width(s: &str, max_width: usize) -> String {
     let mut w = 0;
     let mut truncated = String::new();
 
@@ -394,33 +300,8 @@ pub fn draw_overall(buf: &mut Buffer, area: Rect, app: &App) {
     Widget::render(t, area, buf);
 }
 
-pub fn draw_players(buf: &mut Buffer, area: Rect, app: &mut App) {
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Max(23), Constraint::Min(1)])
-        .split(area);
-    let sublay = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(1), Constraint::Min(1)])
-        .split(layout[1]);
-    let colours = TableColors::new(&tailwind::BLUE);
-
-    let block = Block::default()
-        .title("Players stats")
-        .borders(Borders::ALL)
-        .style(Style::default());
-
-    let header = [
-        "#", "Riot ID", // "Tagline",
-        "Team", "Role", "Games", "Kills", "Deaths", "Assists", "KDA", "GPM", "CSPM", "CSD@10",
-        "Kill %", "Death %", "DPM", "VSPM",
-    ]
-    .into_iter()
-    .map(Cell::from)
-    .collect::<Row>()
-    .height(3)
-    .style(Style::default().fg(colours.header_fg).bg(colours.header_bg))
-    .height(1);
+/// The player stats table's rows, filtered and sorted as they are shown.
+pub fn sorted_players(app: &App) -> Vec<PlayerStats> {
     let mut stats = app.players_stats.clone();
     let sel_col = app
         .players_table_state
@@ -457,10 +338,16 @@ pub fn draw_players(buf: &mut Buffer, area: Rect, app: &mut App) {
             Some(Ordering::Greater) => Ordering::Greater,
             _ => Ordering::Equal,
         },
-        12 => a.kill_participation.cmp(&b.kill_participation),
-        13 => a.death_participation.cmp(&b.death_participation),
-        14 => a.dpm.cmp(&b.dpm),
-        15 => match a.vpm.partial_cmp(&b.vpm) {
+        12 => cmp_optional(a.gd10, b.gd10),
+        13 => cmp_optional(a.xpd10, b.xpd10),
+        14 => cmp_optional(a.timeline_cd10, b.timeline_cd10),
+        15 => cmp_optional(a.gd15, b.gd15),
+        16 => cmp_optional(a.xpd15, b.xpd15),
+        17 => cmp_optional(a.cd15, b.cd15),
+        18 => a.kill_participation.cmp(&b.kill_participation),
+        19 => a.death_participation.cmp(&b.death_participation),
+        20 => a.dpm.cmp(&b.dpm),
+        21 => match a.vpm.partial_cmp(&b.vpm) {
             Some(Ordering::Less) => Ordering::Less,
             Some(Ordering::Greater) => Ordering::Greater,
             _ => Ordering::Equal,
@@ -474,6 +361,56 @@ pub fn draw_players(buf: &mut Buffer, area: Rect, app: &mut App) {
         }
         _ => {}
     }
+    stats
+}
+
+pub fn draw_players(buf: &mut Buffer, area: Rect, app: &mut App) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Max(23), Constraint::Min(1)])
+        .split(area);
+    let sublay = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(1), Constraint::Min(1)])
+        .split(layout[1]);
+    let colours = TableColors::new(&tailwind::BLUE);
+
+    let block = Block::default()
+        .title("Players stats")
+        .borders(Borders::ALL)
+        .style(Style::default());
+
+    let header = [
+        "#",
+        "Riot ID", // "Tagline",
+        "Team",
+        "Role",
+        "Games",
+        "Kills",
+        "Deaths",
+        "Assists",
+        "KDA",
+        "GPM",
+        "CSPM",
+        "CSD@10",
+        "GD@10",
+        "XPD@10",
+        "TL CSD@10",
+        "GD@15",
+        "XPD@15",
+        "CSD@15",
+        "Kill %",
+        "Death %",
+        "DPM",
+        "VSPM",
+    ]
+    .into_iter()
+    .map(Cell::from)
+    .collect::<Row>()
+    .height(3)
+    .style(Style::default().fg(colours.header_fg).bg(colours.header_bg))
+    .height(1);
+    let stats = sorted_players(app);
     let rows = stats.iter().enumerate().map(|(i, stat)| {
         let colour = match i % 2 {
             0 => colours.normal_row_color,
@@ -496,6 +433,30 @@ pub fn draw_players(buf: &mut Buffer, area: Rect, app: &mut App) {
                 Some(d) => d.to_string(),
                 None => "-".to_string(),
             })),
+            Cell::from(Text::from(match stat.gd10 {
+                Some(d) => d.to_string(),
+                None => "-".to_string(),
+            })),
+            Cell::from(Text::from(match stat.xpd10 {
+                Some(d) => d.to_string(),
+                None => "-".to_string(),
+            })),
+            Cell::from(Text::from(match stat.timeline_cd10 {
+                Some(d) => d.to_string(),
+                None => "-".to_string(),
+            })),
+            Cell::from(Text::from(match stat.gd15 {
+                Some(d) => d.to_string(),
+                None => "-".to_string(),
+            })),
+            Cell::from(Text::from(match stat.xpd15 {
+                Some(d) => d.to_string(),
+                None => "-".to_string(),
+            })),
+            Cell::from(Text::from(match stat.cd15 {
+                Some(d) => d.to_string(),
+                None => "-".to_string(),
+            })),
             Cell::from(Text::from(stat.kill_participation.to_string())),
             Cell::from(Text::from(stat.death_participation.to_string())),
             Cell::from(Text::from(match stat.dpm {
@@ -526,6 +487,12 @@ pub fn draw_players(buf: &mut Buffer, area: Rect, app: &mut App) {
             Constraint::Max(4),
             Constraint::Max(4),
             Constraint::Max(7),
+            Constraint::Max(6),
+            Constraint::Max(6),
+            Constraint::Max(9),
+            Constraint::Max(6),
+            Constraint::Max(6),
+            Constraint::Max(6),
             Constraint::Max(7),
             Constraint::Max(7),
             Constraint::Max(5),
@@ -546,6 +513,7 @@ pub fn draw_players(buf: &mut Buffer, area: Rect, app: &mut App) {
                 buf,
                 sublay[0],
                 app,
+                stats.get(i).unwrap().puuid.clone(),
                 stats.get(i).unwrap().riot_id.clone(),
                 stats.get(i).unwrap().tag_line.clone(),
             );
@@ -555,14 +523,45 @@ pub fn draw_players(buf: &mut Buffer, area: Rect, app: &mut App) {
             .style(Style::default())
             .render(layout[1], buf),
     }
+    // The first player picked to compare stays beside the selected one,
+    // otherwise the selected player's lane opponents in the selected role
+    match (app.versus_pick.clone(), app.players_table_state.selected()) {
+        (Some(pick), _) => {
+            draw_players_deep(buf, sublay[1], app, pick.puuid, pick.riot_id, pick.tag_line)
+        }
+        (None, Some(i)) => {
+            let player = stats.get(i).unwrap();
+            let matchups: Vec<&Matchup> = app
+                .player_matchups
+                .get(&player.puuid)
+                .map(|m| m.iter().filter(|m| m.role == player.role).collect())
+                .unwrap_or_default();
+            draw_matchups(buf, sublay[1], "Lane opponents", "Vs Player", &matchups);
+        }
+        (None, None) => {}
+    }
 }
 
-pub fn draw_players_deep(buf: &mut Buffer, area: Rect, app: &mut App, plr: String, tag: String) {
+pub fn draw_players_deep(
+    buf: &mut Buffer,
+    area: Rect,
+    app: &mut App,
+    puuid: String,
+    plr: String,
+    tag: String,
+) {
     let colours = TableColors::new(&tailwind::BLUE);
-    let block = Block::default()
+    let mut block = Block::default()
         .title(format!("{}#{}", plr, tag))
         .borders(Borders::ALL)
         .style(Style::default());
+    if let Some(r) = app.player_ratings.get(&puuid).and_then(|h| h.last()) {
+        block = block.title_bottom(format!(
+            " Rating {:.0} ± {:.0}, against lane opponents ",
+            r.rating,
+            2.0 * r.deviation
+        ));
+    }
 
     let header = [
         "", "Games", "Wins", "Losses", "Win %", "KPG", "DPG", "APG", "KDA", "GPM", "DPM", "CSD@10",
@@ -572,9 +571,9 @@ pub fn draw_players_deep(buf: &mut Buffer, area: Rect, app: &mut App, plr: Strin
     .collect::<Row>()
     .style(Style::default().fg(colours.header_fg).bg(colours.header_bg))
     .height(1);
-    let overall_stats = &app.player_deep_stats.overall_stats.get(&plr).unwrap();
-    let role_stats = &app.player_deep_stats.role_stats.get(&plr).unwrap();
-    let champ_stats = &app.player_deep_stats.champion_stats.get(&plr).unwrap();
+    let overall_stats = &app.player_deep_stats.overall_stats.get(&puuid).unwrap();
+    let role_stats = &app.player_deep_stats.role_stats.get(&puuid).unwrap();
+    let champ_stats = &app.player_deep_stats.champion_stats.get(&puuid).unwrap();
     let mut rows: Vec<Row> = Vec::new();
     rows.extend(get_overall_rows(&colours, overall_stats));
     rows.extend(get_role_rows(&colours, role_stats));
@@ -769,6 +768,335 @@ fn get_champs_rows(
         .collect()
 }
 
+pub fn draw_teams(buf: &mut Buffer, area: Rect, app: &mut App) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(8), Constraint::Length(14)])
+        .split(area);
+    let colours = TableColors::new(&tailwind::EMERALD);
+
+    let block = Block::default()
+        .title("Team stats")
+        .borders(Borders::ALL)
+        .style(Style::default());
+
+    let header = [
+        "#", "Team", "Games", "Wins", "Losses", "Win %", "Blue", "Red", "Length", "Kills",
+        "Deaths", "K/D", "GPM", "FB %", "FT %", "FD %", "Grubs",
+    ]
+    .into_iter()
+    .map(Cell::from)
+    .collect::<Row>()
+    .style(Style::default().fg(colours.header_fg).bg(colours.header_bg))
+    .height(1);
+
+    let mut stats = app.teams_stats.clone();
+    let sel_col = app.teams_table_state.selected_column().unwrap_or(1);
+
+    match sel_col {
+        1 => stats.sort_by_key(|s| s.team_name.clone()),
+        2 => stats.sort_by_key(|s| s.games),
+        3 => stats.sort_by_key(|s| s.wins),
+        4 => stats.sort_by_key(|s| s.losses),
+        5 => stats.sort_by(|a, b| {
+            a.win_percentage
+                .partial_cmp(&b.win_percentage)
+                .unwrap_or(Ordering::Equal)
+        }),
+        6 => stats.sort_by_key(|s| (s.blue_wins, s.blue_games)),
+        7 => stats.sort_by_key(|s| (s.red_wins, s.red_games)),
+        8 => stats.sort_by_key(|s| s.game_length_avg),
+        9 => stats.sort_by(|a, b| a.kills.partial_cmp(&b.kills).unwrap_or(Ordering::Equal)),
+        10 => stats.sort_by(|a, b| a.deaths.partial_cmp(&b.deaths).unwrap_or(Ordering::Equal)),
+        11 => stats.sort_by(|a, b| a.kd.partial_cmp(&b.kd).unwrap_or(Ordering::Equal)),
+        12 => stats.sort_by(|a, b| cmp_optional(a.gpm, b.gpm)),
+        13 => stats.sort_by(|a, b| cmp_optional(a.fb_percentage, b.fb_percentage)),
+        14 => stats.sort_by(|a, b| cmp_optional(a.ft_percentage, b.ft_percentage)),
+        15 => stats.sort_by(|a, b| cmp_optional(a.fd_percentage, b.fd_percentage)),
+        16 => stats.sort_by(|a, b| cmp_optional(a.grubs, b.grubs)),
+        _ => {}
+    }
+
+    if app.teams_sort_dir == -1 {
+        stats.reverse();
+    }
+
+    let rows = stats.iter().enumerate().map(|(i, stat)| {
+        let colour = match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        };
+        Row::from_iter(vec![
+            Cell::from(Text::from((i + 1).to_string())),
+            Cell::from(Text::from(stat.team_name.clone())),
+            Cell::from(
+                Text::from(stat.games.to_string()).alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(stat.wins.to_string()).alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(stat.losses.to_string()).alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(format!("{:.1}%", stat.win_percentage))
+                    .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(format!("{}-{}", stat.blue_wins, stat.blue_games - stat.blue_wins))
+                    .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(format!("{}-{}", stat.red_wins, stat.red_games - stat.red_wins))
+                    .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(format!(
+                    "{:02}:{:02}",
+                    stat.game_length_avg / 60,
+                    stat.game_length_avg % 60
+                ))
+                .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(format!("{:.1}", stat.kills))
+                    .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(format!("{:.1}", stat.deaths))
+                    .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(format!("{:.2}", stat.kd)).alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(match stat.gpm {
+                    Some(d) => format!("{:.0}", d),
+                    None => "-".to_string(),
+                })
+                .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(match stat.fb_percentage {
+                    Some(d) => format!("{:.0}%", d),
+                    None => "-".to_string(),
+                })
+                .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(match stat.ft_percentage {
+                    Some(d) => format!("{:.0}%", d),
+                    None => "-".to_string(),
+                })
+                .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(match stat.fd_percentage {
+                    Some(d) => format!("{:.0}%", d),
+                    None => "-".to_string(),
+                })
+                .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(match stat.grubs {
+                    Some(d) => format!("{:.1}", d),
+                    None => "-".to_string(),
+                })
+                .alignment(ratatui::layout::Alignment::Right),
+            ),
+        ])
+        .style(Style::new().fg(colours.row_fg).bg(colour))
+    });
+
+    let t = Table::new(
+        rows,
+        [
+            Constraint::Max(4),
+            Constraint::Max(20),
+            Constraint::Max(6),
+            Constraint::Max(5),
+            Constraint::Max(7),
+            Constraint::Max(7),
+            Constraint::Max(6),
+            Constraint::Max(6),
+            Constraint::Max(7),
+            Constraint::Max(6),
+            Constraint::Max(7),
+            Constraint::Max(5),
+            Constraint::Max(5),
+            Constraint::Max(5),
+            Constraint::Max(5),
+            Constraint::Max(5),
+            Constraint::Min(1),
+        ],
+    )
+    .header(header)
+    .row_highlight_style(SELECTED_STYLE)
+    .column_highlight_style(SELECTED_STYLE)
+    .highlight_spacing(HighlightSpacing::Always)
+    .block(block);
+
+    let selected = app
+        .teams_table_state
+        .selected()
+        .and_then(|i| stats.get(i))
+        .map(|s| s.team_name.clone());
+    StatefulWidget::render(t, layout[0], buf, &mut app.teams_table_state);
+    draw_team_ratings(buf, layout[1], &app.team_ratings, selected.as_deref());
+}
+
+pub fn draw_standings(buf: &mut Buffer, area: Rect, app: &mut App) {
+    let layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(1), Constraint::Min(1)])
+        .split(area);
+    let colours = TableColors::new(&tailwind::EMERALD);
+
+    let block = Block::default()
+        .title("Standings")
+        .borders(Borders::ALL)
+        .style(Style::default());
+
+    let header = ["#", "Team", "Matches", "Match %", "Games", "Game %"]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(Style::default().fg(colours.header_fg).bg(colours.header_bg))
+        .height(1);
+
+    let rows = app.standings.iter().enumerate().map(|(i, row)| {
+        let colour = match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        };
+        let match_record = match row.match_draws {
+            0 => format!("{}-{}", row.match_wins, row.match_losses),
+            d => format!("{}-{}-{}", row.match_wins, d, row.match_losses),
+        };
+        Row::from_iter(vec![
+            Cell::from(Text::from(row.rank.to_string())),
+            Cell::from(Text::from(row.team.clone())),
+            Cell::from(Text::from(match_record).alignment(ratatui::layout::Alignment::Right)),
+            Cell::from(
+                Text::from(format!("{:.0}%", row.match_win_percent()))
+                    .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(format!("{}-{}", row.game_wins, row.game_losses))
+                    .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(format!("{:.0}%", row.game_win_percent()))
+                    .alignment(ratatui::layout::Alignment::Right),
+            ),
+        ])
+        .style(Style::new().fg(colours.row_fg).bg(colour))
+    });
+
+    let t = Table::new(
+        rows,
+        [
+            Constraint::Max(4),
+            Constraint::Max(20),
+            Constraint::Max(8),
+            Constraint::Max(8),
+            Constraint::Max(6),
+            Constraint::Min(1),
+        ],
+    )
+    .header(header)
+    .row_highlight_style(SELECTED_STYLE)
+    .highlight_spacing(HighlightSpacing::Always)
+    .block(block);
+
+    StatefulWidget::render(t, layout[0], buf, &mut app.standings_table_state);
+
+    // Only show the selected team's series once a row is picked
+    let selected_team = app
+        .standings_table_state
+        .selected()
+        .and_then(|i| app.standings.get(i))
+        .map(|row| row.team.clone());
+    let series: Vec<&Series> = app
+        .series
+        .iter()
+        .filter(|s| match &selected_team {
+            Some(team) => &s.team_a == team || &s.team_b == team,
+            None => true,
+        })
+        .collect();
+    draw_series_results(buf, layout[1], &series);
+}
+
+pub(super) fn draw_series_results(buf: &mut Buffer, area: Rect, series: &[&Series]) {
+    let colours = TableColors::new(&tailwind::EMERALD);
+
+    let block = Block::default()
+        .title("Series")
+        .borders(Borders::ALL)
+        .style(Style::default());
+
+    let header = ["Date", "Stage", "Team", "", "Team", "Format"]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(Style::default().fg(colours.header_fg).bg(colours.header_bg))
+        .height(1);
+
+    let rows = series.iter().enumerate().map(|(i, s)| {
+        let colour = match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        };
+        let team_style = |team: &str| match s.winner() {
+            Some(w) if w == team => Style::default().add_modifier(Modifier::BOLD),
+            _ => Style::default(),
+        };
+        Row::from_iter(vec![
+            Cell::from(Text::from(s.date.clone().unwrap_or_else(|| "-".to_string()))),
+            Cell::from(Text::from(s.stage.clone().unwrap_or_else(|| "-".to_string()))),
+            Cell::from(
+                Text::from(s.team_a.clone())
+                    .style(team_style(&s.team_a))
+                    .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(format!("{} - {}", s.team_a_wins, s.team_b_wins))
+                    .alignment(ratatui::layout::Alignment::Center),
+            ),
+            Cell::from(Text::from(s.team_b.clone()).style(team_style(&s.team_b))),
+            Cell::from(Text::from(format!("Bo{}", s.best_of))),
+        ])
+        .style(Style::new().fg(colours.row_fg).bg(colour))
+    });
+
+    let t = Table::new(
+        rows,
+        [
+            Constraint::Max(10),
+            Constraint::Max(12),
+            Constraint::Max(20),
+            Constraint::Max(5),
+            Constraint::Max(20),
+            Constraint::Min(1),
+        ],
+    )
+    .header(header)
+    .block(block);
+
+    Widget::render(t, area, buf);
+}
+
+/// Orders optional stats with missing values last, matching the champion table.
+fn cmp_optional(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (None, None) => Ordering::Equal,
+    }
+}
+
 pub fn draw_champions(buf: &mut Buffer, area: Rect, app: &mut App) {
     let layout = Layout::default()
         .direction(Direction::Vertical)
@@ -782,9 +1110,9 @@ pub fn draw_champions(buf: &mut Buffer, area: Rect, app: &mut App) {
         .style(Style::default());
 
     let header = [
-        "#", "Champion", "Games", "Pick %", "Players", "Wins", "Losses", "Wins %", "Kills",
-        "Deaths", "Assists", "KDA", "CS", "CS/M", "VS", "VS/M", "Gold", "G/M", "Damage", "DMG/M",
-        "KPAR", "KS", "GS", "Roles",
+        "#", "Champion", "Games", "Pick %", "Ban %", "Pres %", "FP %", "Players", "Wins", "Losses",
+        "Wins %", "Kills", "Deaths", "Assists", "KDA", "CS", "CS/M", "VS", "VS/M", "Gold", "G/M",
+        "Damage", "DMG/M", "KPAR", "KS", "GS", "Roles",
     ]
     .into_iter()
     .map(Cell::from)
@@ -798,67 +1126,78 @@ pub fn draw_champions(buf: &mut Buffer, area: Rect, app: &mut App) {
     match sel_col {
         1 => stats.sort_by_key(|s| s.champion.clone()),
         2 => stats.sort_by_key(|s| s.games),
-        4 => stats.sort_by_key(|s| s.unique_players),
-        5 => stats.sort_by_key(|s| s.wins),
-        6 => stats.sort_by_key(|s| s.losses),
-        23 => stats.sort_by_key(|s| s.roles.clone()),
+        7 => stats.sort_by_key(|s| s.unique_players),
+        8 => stats.sort_by_key(|s| s.wins),
+        9 => stats.sort_by_key(|s| s.losses),
+        26 => stats.sort_by_key(|s| s.roles.clone()),
         3 => stats.sort_by(|a, b| {
             a.pick_percentage
                 .partial_cmp(&b.pick_percentage)
                 .unwrap_or(Ordering::Equal)
         }),
-        7 => stats.sort_by(|a, b| {
+        10 => stats.sort_by(|a, b| {
             a.win_percentage
                 .partial_cmp(&b.win_percentage)
                 .unwrap_or(Ordering::Equal)
         }),
-        8 => stats.sort_by(|a, b| a.kills.partial_cmp(&b.kills).unwrap_or(Ordering::Equal)),
-        9 => stats.sort_by(|a, b| a.deaths.partial_cmp(&b.deaths).unwrap_or(Ordering::Equal)),
-        10 => stats.sort_by(|a, b| a.assists.partial_cmp(&b.assists).unwrap_or(Ordering::Equal)),
-        11 => stats.sort_by(|a, b| a.kda.partial_cmp(&b.kda).unwrap_or(Ordering::Equal)),
-        12 => stats.sort_by(|a, b| a.cs.partial_cmp(&b.cs).unwrap_or(Ordering::Equal)),
-        13 => stats.sort_by(|a, b| a.csm.partial_cmp(&b.csm).unwrap_or(Ordering::Equal)),
-        16 => stats.sort_by(|a, b| a.gold.partial_cmp(&b.gold).unwrap_or(Ordering::Equal)),
-        17 => stats.sort_by(|a, b| a.goldm.partial_cmp(&b.goldm).unwrap_or(Ordering::Equal)),
-        20 => stats.sort_by(|a, b| {
+        11 => stats.sort_by(|a, b| a.kills.partial_cmp(&b.kills).unwrap_or(Ordering::Equal)),
+        12 => stats.sort_by(|a, b| a.deaths.partial_cmp(&b.deaths).unwrap_or(Ordering::Equal)),
+        13 => stats.sort_by(|a, b| a.assists.partial_cmp(&b.assists).unwrap_or(Ordering::Equal)),
+        14 => stats.sort_by(|a, b| a.kda.partial_cmp(&b.kda).unwrap_or(Ordering::Equal)),
+        15 => stats.sort_by(|a, b| a.cs.partial_cmp(&b.cs).unwrap_or(Ordering::Equal)),
+        16 => stats.sort_by(|a, b| a.csm.partial_cmp(&b.csm).unwrap_or(Ordering::Equal)),
+        19 => stats.sort_by(|a, b| a.gold.partial_cmp(&b.gold).unwrap_or(Ordering::Equal)),
+        20 => stats.sort_by(|a, b| a.goldm.partial_cmp(&b.goldm).unwrap_or(Ordering::Equal)),
+        23 => stats.sort_by(|a, b| {
             a.kill_percentage
                 .partial_cmp(&b.kill_percentage)
                 .unwrap_or(Ordering::Equal)
         }),
-        21 => stats.sort_by(|a, b| {
+        24 => stats.sort_by(|a, b| {
             a.kill_share
                 .partial_cmp(&b.kill_share)
                 .unwrap_or(Ordering::Equal)
         }),
-        22 => stats.sort_by(|a, b| {
+        25 => stats.sort_by(|a, b| {
             a.gold_share
                 .partial_cmp(&b.gold_share)
                 .unwrap_or(Ordering::Equal)
         }),
-        14 => stats.sort_by(|a, b| match (a.vs, b.vs) {
+        17 => stats.sort_by(|a, b| match (a.vs, b.vs) {
             (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
             (None, Some(_)) => Ordering::Greater,
             (Some(_), None) => Ordering::Less,
             (None, None) => Ordering::Equal,
         }),
-        15 => stats.sort_by(|a, b| match (a.vsm, b.vsm) {
+        18 => stats.sort_by(|a, b| match (a.vsm, b.vsm) {
             (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
             (None, Some(_)) => Ordering::Greater,
             (Some(_), None) => Ordering::Less,
             (None, None) => Ordering::Equal,
         }),
-        18 => stats.sort_by(|a, b| match (a.damage, b.damage) {
+        21 => stats.sort_by(|a, b| match (a.damage, b.damage) {
             (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
             (None, Some(_)) => Ordering::Greater,
             (Some(_), None) => Ordering::Less,
             (None, None) => Ordering::Equal,
         }),
-        19 => stats.sort_by(|a, b| match (a.damagem, b.damagem) {
+        22 => stats.sort_by(|a, b| match (a.damagem, b.damagem) {
             (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
             (None, Some(_)) => Ordering::Greater,
             (Some(_), None) => Ordering::Less,
             (None, None) => Ordering::Equal,
         }),
+        4 => stats.sort_by(|a, b| {
+            a.ban_percentage
+                .partial_cmp(&b.ban_percentage)
+                .unwrap_or(Ordering::Equal)
+        }),
+        5 => stats.sort_by(|a, b| {
+            a.presence_percentage
+                .partial_cmp(&b.presence_percentage)
+                .unwrap_or(Ordering::Equal)
+        }),
+        6 => stats.sort_by(|a, b| cmp_optional(a.first_pick_percentage, b.first_pick_percentage)),
         _ => {}
     }
 
@@ -894,6 +1233,21 @@ pub fn draw_champions(buf: &mut Buffer, area: Rect, app: &mut App) {
                 Text::from(format!("{:.1}%", stat.pick_percentage))
                     .alignment(ratatui::layout::Alignment::Right),
             ),
+            Cell::from(
+                Text::from(format!("{:.1}%", stat.ban_percentage))
+                    .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(format!("{:.1}%", stat.presence_percentage))
+                    .alignment(ratatui::layout::Alignment::Right),
+            ),
+            Cell::from(
+                Text::from(match stat.first_pick_percentage {
+                    Some(p) => format!("{:.1}%", p),
+                    None => "-".to_string(),
+                })
+                .alignment(ratatui::layout::Alignment::Right),
+            ),
             Cell::from(
                 Text::from(stat.unique_players.to_string())
                     .alignment(ratatui::layout::Alignment::Right),
@@ -987,6 +1341,9 @@ pub fn draw_champions(buf: &mut Buffer, area: Rect, app: &mut App) {
             Constraint::Max(5),
             Constraint::Max(5),
             Constraint::Max(5),
+            Constraint::Max(6),
+            Constraint::Max(6),
+            Constraint::Max(5),
             Constraint::Max(7),
             Constraint::Max(7),
             Constraint::Max(7),
@@ -1023,6 +1380,182 @@ pub fn draw_champions(buf: &mut Buffer, area: Rect, app: &mut App) {
     if histories.len() > 0 {
         draw_champion_history(buf, sublay[0], histories);
     }
+    if let Some(matchups) = app
+        .all_champs_state
+        .selected()
+        .and_then(|i| stats.get(i))
+        .and_then(|stat| app.champion_matchups.get(&stat.champion))
+    {
+        let matchups: Vec<&Matchup> = matchups.iter().collect();
+        draw_matchups(buf, sublay[1], "Champion matchups", "Vs Champ", &matchups);
+    }
+}
+
+pub fn draw_draft_tendencies(buf: &mut Buffer, area: Rect, app: &mut App) {
+    let layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(32),
+            Constraint::Min(1),
+            Constraint::Min(1),
+            Constraint::Min(1),
+        ])
+        .split(area);
+    let colours = TableColors::new(&tailwind::VIOLET);
+
+    let header = ["Team", "Games"]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(Style::default().fg(colours.header_fg).bg(colours.header_bg))
+        .height(1);
+
+    let rows = app.draft_tendencies.iter().enumerate().map(|(i, row)| {
+        let colour = match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        };
+        Row::from_iter(vec![
+            Cell::from(Text::from(row.team.clone())),
+            Cell::from(
+                Text::from(row.games.to_string()).alignment(ratatui::layout::Alignment::Right),
+            ),
+        ])
+        .style(Style::new().fg(colours.row_fg).bg(colour))
+    });
+
+    let t = Table::new(rows, [Constraint::Max(20), Constraint::Min(1)])
+        .header(header)
+        .row_highlight_style(SELECTED_STYLE)
+        .highlight_spacing(HighlightSpacing::Always)
+        .block(Block::default().title("Teams").borders(Borders::ALL));
+
+    StatefulWidget::render(t, layout[0], buf, &mut app.draft_table_state);
+
+    let selected = app
+        .draft_table_state
+        .selected()
+        .and_then(|i| app.draft_tendencies.get(i));
+    let Some(team) = selected else {
+        return;
+    };
+    draw_champion_counts(buf, layout[1], "Bans", &team.bans, &colours);
+    draw_champion_counts(
+        buf,
+        layout[2],
+        "Banned against",
+        &team.banned_against,
+        &colours,
+    );
+    draw_champion_counts(buf, layout[3], "First picks", &team.first_picks, &colours);
+}
+
+fn draw_champion_counts(
+    buf: &mut Buffer,
+    area: Rect,
+    title: &str,
+    counts: &[(String, u64)],
+    colours: &TableColors,
+) {
+    let rows = counts.iter().enumerate().map(|(i, (champion, times))| {
+        let colour = match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        };
+        Row::from_iter(vec![
+            Cell::from(Text::from(champion.clone())),
+            Cell::from(Text::from(times.to_string()).alignment(ratatui::layout::Alignment::Right)),
+        ])
+        .style(Style::new().fg(colours.row_fg).bg(colour))
+    });
+
+    let t = Table::new(rows, [Constraint::Max(14), Constraint::Max(5)])
+        .block(Block::default().title(title).borders(Borders::ALL));
+
+    Widget::render(t, area, buf);
+}
+
+/// Records against each lane opponent, with the average differences to them.
+pub fn draw_matchups(
+    buf: &mut Buffer,
+    area: Rect,
+    title: &str,
+    opponent_header: &str,
+    matchups: &[&Matchup],
+) {
+    let colours = TableColors::new(&tailwind::ORANGE);
+
+    let block = Block::default()
+        .title(title.to_string())
+        .title_bottom(" GD and CSD at the end of the game ")
+        .borders(Borders::ALL)
+        .style(Style::default());
+
+    let header = [
+        "Role",
+        opponent_header,
+        "Games",
+        "W-L",
+        "Win %",
+        "KDA",
+        "GD",
+        "CSD",
+        "CSD@10",
+    ]
+    .into_iter()
+    .map(Cell::from)
+    .collect::<Row>()
+    .style(Style::default().fg(colours.header_fg).bg(colours.header_bg))
+    .height(1);
+
+    let right = |s: String| Cell::from(Text::from(s).alignment(ratatui::layout::Alignment::Right));
+    let rows = matchups.iter().enumerate().map(|(i, m)| {
+        let colour = match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        };
+        Row::from_iter(vec![
+            Cell::from(Text::from(m.role.clone())),
+            Cell::from(Text::from(m.opponent.clone())),
+            right(m.games.to_string()),
+            right(format!("{}-{}", m.wins, m.losses)),
+            right(format!("{:.0}%", m.wins as f64 * 100.0 / m.games as f64)),
+            right(format!("{:.2}", m.kda)),
+            right(format!("{:+.0}", m.gold_diff)),
+            right(format!("{:+.1}", m.cs_diff)),
+            right(match m.cd10 {
+                Some(c) => format!("{:+.1}", c),
+                None => "-".to_string(),
+            }),
+        ])
+        .style(Style::new().fg(colours.row_fg).bg(colour))
+    });
+
+    if matchups.is_empty() {
+        Paragraph::new("No lane opponents recorded.")
+            .style(Style::default().fg(tailwind::SLATE.c400))
+            .block(block)
+            .render(area, buf);
+        return;
+    }
+    let t = Table::new(
+        rows,
+        [
+            Constraint::Length(7),
+            Constraint::Min(10),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(6),
+        ],
+    )
+    .header(header)
+    .block(block);
+
+    Widget::render(t, area, buf);
 }
 
 pub fn draw_champion_history(buf: &mut Buffer, area: Rect, histories: &Vec<ChampionHistory>) {
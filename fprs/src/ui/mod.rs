@@ -1,4 +1,10 @@
+pub mod chart;
+pub mod detail;
+pub mod head_to_head;
 pub mod lolui;
+pub mod ratings;
+pub mod synergy;
+pub mod versus;
 pub mod view;
 
 pub use lolui::draw_scoreboard;
@@ -0,0 +1,131 @@
+//! Glicko-2 team ratings under the team stats, with the selected team's
+//! rating over time.
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Modifier, Style, palette::tailwind},
+    text::Text,
+    widgets::{Block, Borders, Cell, Row, Sparkline, Table, Widget},
+};
+use std::collections::HashMap;
+
+use crate::sql::schema::Rating;
+
+use super::lolui::TableColors;
+
+/// Ratings beside the history of `selected`, or of the top rated team when
+/// nothing is selected.
+pub fn draw_team_ratings(
+    buf: &mut Buffer,
+    area: Rect,
+    ratings: &HashMap<String, Vec<Rating>>,
+    selected: Option<&str>,
+) {
+    let [table_area, history_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+
+    // Highest first, each team's latest rating
+    let mut latest: Vec<(&String, &Vec<Rating>)> =
+        ratings.iter().filter(|(_, h)| !h.is_empty()).collect();
+    latest.sort_by(|a, b| {
+        let rating = |h: &Vec<Rating>| h.last().map_or(0.0, |r| r.rating);
+        rating(b.1)
+            .total_cmp(&rating(a.1))
+            .then_with(|| a.0.cmp(b.0))
+    });
+    let selected = selected
+        .filter(|team| ratings.contains_key(*team))
+        .or(latest.first().map(|(team, _)| team.as_str()));
+
+    let colours = TableColors::new(&tailwind::EMERALD);
+    let header = ["#", "Team", "Rating", "RD", "Games", "Last"]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(Style::default().fg(colours.header_fg).bg(colours.header_bg));
+
+    let right = |s: String| Cell::from(Text::from(s).alignment(Alignment::Right));
+    let rows = latest.iter().enumerate().map(|(i, (team, history))| {
+        let now = history.last().unwrap();
+        let before = match history.len() {
+            1 => 1500.0,
+            n => history[n - 2].rating,
+        };
+        let style = Style::new().fg(colours.row_fg).bg(match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        });
+        let style = match Some(team.as_str()) == selected {
+            true => style.add_modifier(Modifier::BOLD),
+            false => style,
+        };
+        Row::new([
+            Cell::from((i + 1).to_string()),
+            Cell::from(team.to_string()),
+            right(format!("{:.0}", now.rating)),
+            right(format!("{:.0}", now.deviation)),
+            right(history.len().to_string()),
+            right(format!("{:+.0}", now.rating - before)),
+        ])
+        .style(style)
+    });
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Ratings")
+        .title_bottom(" Glicko-2, only games with both teams set ");
+    match latest.is_empty() {
+        true => Text::from("No games between two named teams yet.")
+            .style(Style::default().fg(tailwind::SLATE.c400))
+            .render(block.inner(table_area), buf),
+        false => Widget::render(
+            Table::new(
+                rows,
+                [
+                    Constraint::Length(3),
+                    Constraint::Min(10),
+                    Constraint::Length(6),
+                    Constraint::Length(4),
+                    Constraint::Length(5),
+                    Constraint::Length(5),
+                ],
+            )
+            .header(header),
+            block.inner(table_area),
+            buf,
+        ),
+    }
+    block.render(table_area, buf);
+
+    match selected.and_then(|team| ratings.get(team).map(|h| (team, h))) {
+        Some((team, history)) => draw_rating_history(buf, history_area, team, history),
+        None => Block::default()
+            .borders(Borders::ALL)
+            .title("Rating history")
+            .render(history_area, buf),
+    }
+}
+
+/// A bar per game, the most recent games when there are more than fit.
+fn draw_rating_history(buf: &mut Buffer, area: Rect, team: &str, history: &[Rating]) {
+    let low = history.iter().map(|r| r.rating).fold(f64::MAX, f64::min);
+    let high = history.iter().map(|r| r.rating).fold(f64::MIN, f64::max);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {team} rating history "))
+        .title_bottom(format!(" Low {low:.0}, high {high:.0} "));
+
+    let inner = block.inner(area);
+    let shown = &history[history.len().saturating_sub(inner.width as usize)..];
+    // Sit the lowest rating a little off the floor so it still shows
+    let data: Vec<u64> = shown
+        .iter()
+        .map(|r| (r.rating - low + (high - low) / 10.0 + 1.0) as u64)
+        .collect();
+
+    block.render(area, buf);
+    Sparkline::default()
+        .data(&data)
+        .style(Style::default().fg(tailwind::EMERALD.c400))
+        .render(inner, buf);
+}
@@ -0,0 +1,132 @@
+//! The synergy screen, opened on a player from the player stats tab.
+//!
+//! The player's teammates sit above the league's bot lane and mid & jungle
+//! duos, with champion pairs down the side. Stats are for the two together:
+//! kills, deaths and assists are summed before the KDA, kill participation is
+//! their average and GPM is their combined gold.
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Modifier, Style, palette::tailwind},
+    text::Text,
+    widgets::{Block, Borders, Cell, Row, Table, Widget},
+};
+
+use crate::sql::synergy::{Duo, MIN_CHAMPION_GAMES, MIN_GAMES, Pair, Synergy};
+
+use super::lolui::TableColors;
+
+pub fn draw_synergy(buf: &mut Buffer, area: Rect, synergy: &Synergy) {
+    let [left, champions_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+    let [teammates_area, duos_area] =
+        Layout::vertical([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(left);
+    let duo_areas: [Rect; 2] =
+        Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(duos_area);
+
+    draw_pairs(
+        buf,
+        teammates_area,
+        &format!(" {}'s teammates ", synergy.name),
+        &["Teammate"],
+        &synergy.teammates,
+        MIN_GAMES,
+        None,
+    );
+    for (i, (duo, pairs)) in synergy.duos.iter().enumerate() {
+        let labels = match duo {
+            Duo::BotLane => ["Bottom", "Support"],
+            Duo::MidJungle => ["Mid", "Jungle"],
+        };
+        draw_pairs(
+            buf,
+            duo_areas[i],
+            &format!(" {} duos ", duo.name()),
+            &labels,
+            pairs,
+            MIN_GAMES,
+            Some(&synergy.puuid),
+        );
+    }
+    draw_pairs(
+        buf,
+        champions_area,
+        " Champion pairs ",
+        &["Champion", "Champion"],
+        &synergy.champions,
+        MIN_CHAMPION_GAMES,
+        None,
+    );
+}
+
+/// A table of pairs, naming the last `labels.len()` of each pair. Pairs with
+/// `highlight` in them are in bold.
+fn draw_pairs(
+    buf: &mut Buffer,
+    area: Rect,
+    title: &str,
+    labels: &[&str],
+    pairs: &[Pair],
+    min_games: u64,
+    highlight: Option<&str>,
+) {
+    let colours = TableColors::new(&tailwind::TEAL);
+    let skip = 2 - labels.len();
+    let header = labels
+        .iter()
+        .copied()
+        .chain(["Games", "W-L", "Win %", "KDA", "KP %", "GPM"])
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(Style::default().fg(colours.header_fg).bg(colours.header_bg));
+
+    let right = |s: String| Cell::from(Text::from(s).alignment(Alignment::Right));
+    let rows = pairs.iter().enumerate().map(|(i, pair)| {
+        let style = Style::new().fg(colours.row_fg).bg(match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        });
+        let style = match highlight.is_some_and(|h| pair.keys.iter().any(|k| k == h)) {
+            true => style.add_modifier(Modifier::BOLD),
+            false => style,
+        };
+        pair.names[skip..]
+            .iter()
+            .map(|name| Cell::from(name.clone()))
+            .chain([
+                right(pair.games.to_string()),
+                right(format!("{}-{}", pair.wins, pair.losses())),
+                right(format!("{:.0}%", pair.win_percent())),
+                right(format!("{:.2}", pair.kda())),
+                right(format!("{:.0}%", pair.kill_participation())),
+                right(pair.gpm().to_string()),
+            ])
+            .collect::<Row>()
+            .style(style)
+    });
+
+    let widths = labels.iter().map(|_| Constraint::Min(10)).chain([
+        Constraint::Length(5),
+        Constraint::Length(5),
+        Constraint::Length(5),
+        Constraint::Length(5),
+        Constraint::Length(5),
+        Constraint::Length(5),
+    ]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title.to_string())
+        .title_bottom(format!(" Pairs need {min_games} games together "));
+    match pairs.is_empty() {
+        true => Text::from("No pairs have played enough games together.")
+            .style(Style::default().fg(tailwind::SLATE.c400))
+            .render(block.inner(area), buf),
+        false => Widget::render(
+            Table::new(rows, widths).header(header),
+            block.inner(area),
+            buf,
+        ),
+    }
+    block.render(area, buf);
+}
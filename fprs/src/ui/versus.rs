@@ -0,0 +1,382 @@
+//! The player comparison screen, for two players picked from the player stats.
+//!
+//! Percentiles come from each player's rank in their role, so they only show
+//! for players with enough games in the role to be ranked.
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, palette::tailwind},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Cell, Row, Table, Widget},
+};
+
+use crate::sql::schema::{
+    PlayerDeepStats, PlayerOverallStats, PlayerRoleStats, PlayerStats, PlayerVersus, SharedGame,
+};
+
+use super::lolui::TableColors;
+
+/// The first player picked, then the second.
+const PLAYER_COLOURS: [Color; 2] = [tailwind::SKY.c400, tailwind::AMBER.c400];
+
+const BAR_WIDTH: usize = 12;
+
+pub fn draw_player_versus(
+    buf: &mut Buffer,
+    area: Rect,
+    versus: &PlayerVersus,
+    deep: &PlayerDeepStats,
+) {
+    let [top, games_area] =
+        Layout::vertical([Constraint::Length(14), Constraint::Min(6)]).areas(area);
+    let [stats_area, percentile_area] =
+        Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(top);
+
+    let overall = versus
+        .players
+        .each_ref()
+        .map(|p| deep.overall_stats.get(&p.puuid));
+    let role = versus.players.each_ref().map(|p| {
+        deep.role_stats
+            .get(&p.puuid)
+            .and_then(|lines| lines.iter().find(|l| l.role == p.role))
+    });
+
+    draw_stats(buf, stats_area, &versus.players, overall, role);
+    draw_percentiles(buf, percentile_area, &versus.players, role);
+    draw_shared_games(buf, games_area, versus);
+}
+
+fn player_name(p: &PlayerStats) -> String {
+    match p.tag_line.as_str() {
+        "" => p.riot_id.clone(),
+        tag => format!("{}#{tag}", p.riot_id),
+    }
+}
+
+/// Overall and in-role lines for both players. The better role line is in
+/// bold, as that is the like for like comparison.
+fn draw_stats(
+    buf: &mut Buffer,
+    area: Rect,
+    players: &[PlayerStats; 2],
+    overall: [Option<&PlayerOverallStats>; 2],
+    role: [Option<&PlayerRoleStats>; 2],
+) {
+    let colours = TableColors::new(&tailwind::BLUE);
+    let dash = || "-".to_string();
+
+    // Each stat as (label, overall, role, higher is better)
+    type Stat = (
+        &'static str,
+        fn(&PlayerOverallStats) -> Option<f64>,
+        fn(&PlayerRoleStats) -> Option<f64>,
+        bool,
+    );
+    let stats: [Stat; 9] = [
+        (
+            "Games",
+            |o| Some(o.games as f64),
+            |r| Some(r.games as f64),
+            true,
+        ),
+        (
+            "Win %",
+            |o| Some(o.win_percent as f64),
+            |r| Some(r.win_percent as f64),
+            true,
+        ),
+        (
+            "KPG",
+            |o| Some(o.kills_per_game),
+            |r| Some(r.kills_per_game),
+            true,
+        ),
+        (
+            "DPG",
+            |o| Some(o.deaths_per_game),
+            |r| Some(r.deaths_per_game),
+            false,
+        ),
+        (
+            "APG",
+            |o| Some(o.assists_per_game),
+            |r| Some(r.assists_per_game),
+            true,
+        ),
+        ("KDA", |o| Some(o.kda), |r| Some(r.kda), true),
+        ("GPM", |o| Some(o.gpm as f64), |r| Some(r.gpm as f64), true),
+        (
+            "DPM",
+            |o| o.dpm.map(|d| d as f64),
+            |r| r.dpm.map(|d| d as f64),
+            true,
+        ),
+        ("CSD@10", |o| o.cd10, |r| r.cd10, true),
+    ];
+
+    let rows = stats
+        .iter()
+        .enumerate()
+        .map(|(i, &(label, of, rf, higher))| {
+            let o = overall.map(|o| o.and_then(of));
+            let r = role.map(|r| r.and_then(rf));
+            let better = match r {
+                [Some(a), Some(b)] if (a > b) == higher && a != b => Some(0),
+                [Some(a), Some(b)] if a != b => Some(1),
+                _ => None,
+            };
+            let value = |v: Option<f64>| match (label, v) {
+                (_, None) => dash(),
+                ("KPG" | "DPG" | "APG" | "KDA" | "CSD@10", Some(v)) => format!("{v:.1}"),
+                (_, Some(v)) => format!("{v:.0}"),
+            };
+            let cell = |v: Option<f64>, bold: bool| {
+                let style = match bold {
+                    true => Style::default().add_modifier(Modifier::BOLD),
+                    false => Style::default(),
+                };
+                Cell::from(
+                    Text::from(value(v))
+                        .style(style)
+                        .alignment(Alignment::Right),
+                )
+            };
+            let dim = Style::default().fg(tailwind::SLATE.c400);
+            Row::new([
+                Cell::from(label),
+                cell(o[0], false).style(dim),
+                cell(r[0], better == Some(0)),
+                cell(r[1], better == Some(1)),
+                cell(o[1], false).style(dim),
+            ])
+            .style(Style::new().fg(colours.row_fg).bg(match i % 2 {
+                0 => colours.normal_row_color,
+                _ => colours.alt_row_color,
+            }))
+        });
+
+    let right = |s: String| Cell::from(Text::from(s).alignment(Alignment::Right));
+    let header = Row::new([
+        Cell::from(""),
+        right("All".to_string()),
+        right(players[0].role.clone()),
+        right(players[1].role.clone()),
+        right("All".to_string()),
+    ])
+    .style(Style::default().fg(colours.header_fg).bg(colours.header_bg));
+
+    let title = Line::from(vec![
+        Span::raw(" "),
+        Span::styled(
+            player_name(&players[0]),
+            Style::default().fg(PLAYER_COLOURS[0]),
+        ),
+        Span::raw(" vs "),
+        Span::styled(
+            player_name(&players[1]),
+            Style::default().fg(PLAYER_COLOURS[1]),
+        ),
+        Span::raw(" "),
+    ]);
+    Table::new(
+        rows,
+        [
+            Constraint::Length(7),
+            Constraint::Min(7),
+            Constraint::Min(7),
+            Constraint::Min(7),
+            Constraint::Min(7),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .title_bottom(" Each player's overall line on the outside, role line inside "),
+    )
+    .render(area, buf);
+}
+
+/// Where a rank sits among `total`, 100 being the best.
+fn percentile(rank: Option<u64>, total: u64) -> Option<u64> {
+    let rank = rank?;
+    match total {
+        0 => None,
+        1 => Some(100),
+        n => Some(100 * (n - rank.min(n)) / (n - 1)),
+    }
+}
+
+fn bar(percentile: Option<u64>, colour: Color) -> Line<'static> {
+    match percentile {
+        Some(p) => {
+            let filled = (p as usize * BAR_WIDTH).div_ceil(100);
+            Line::from(vec![
+                Span::styled("█".repeat(filled), Style::default().fg(colour)),
+                Span::styled(
+                    "░".repeat(BAR_WIDTH - filled),
+                    Style::default().fg(tailwind::SLATE.c700),
+                ),
+                Span::raw(format!(" {p:>3}")),
+            ])
+        }
+        None => Line::from(Span::styled(
+            "not ranked",
+            Style::default().fg(tailwind::SLATE.c500),
+        )),
+    }
+}
+
+fn draw_percentiles(
+    buf: &mut Buffer,
+    area: Rect,
+    players: &[PlayerStats; 2],
+    role: [Option<&PlayerRoleStats>; 2],
+) {
+    let colours = TableColors::new(&tailwind::BLUE);
+    type Rank = fn(&PlayerRoleStats) -> Option<u64>;
+    let ranks: [(&str, Rank); 5] = [
+        ("KPG", |r| Some(r.kpgn)),
+        ("KDA", |r| Some(r.kdan)),
+        ("GPM", |r| Some(r.gpmn)),
+        ("DPM", |r| r.dpmn),
+        ("CSD@10", |r| r.cd10n),
+    ];
+
+    let rows = ranks.iter().enumerate().map(|(i, &(label, rank))| {
+        let [a, b] = [0, 1].map(|p| {
+            let pct = role[p].and_then(|r| percentile(rank(r), r.role_total));
+            Cell::from(bar(pct, PLAYER_COLOURS[p]))
+        });
+        Row::new([Cell::from(label), a, b]).style(Style::new().fg(colours.row_fg).bg(match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        }))
+    });
+
+    let header = Row::new([
+        Cell::from(""),
+        Cell::from(players[0].riot_id.clone()).style(Style::default().fg(PLAYER_COLOURS[0])),
+        Cell::from(players[1].riot_id.clone()).style(Style::default().fg(PLAYER_COLOURS[1])),
+    ])
+    .style(Style::default().bg(colours.header_bg));
+
+    Table::new(
+        rows,
+        [
+            Constraint::Length(7),
+            Constraint::Length(BAR_WIDTH as u16 + 4),
+            Constraint::Length(BAR_WIDTH as u16 + 4),
+        ],
+    )
+    .header(header)
+    .column_spacing(2)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Percentile in role ")
+            .title_bottom(" Ranked against players with four or more games in the role "),
+    )
+    .render(area, buf);
+}
+
+/// Games both played, with their lane record when they faced each other in
+/// the same role.
+fn draw_shared_games(buf: &mut Buffer, area: Rect, versus: &PlayerVersus) {
+    let colours = TableColors::new(&tailwind::BLUE);
+    let [a, b] = versus.players.each_ref().map(|p| p.riot_id.clone());
+
+    let header = [
+        "Date", "", "Champion", "Champion", "Role", "Winner", "K/D/A", "K/D/A", "CSD@10", "GD@10",
+    ]
+    .into_iter()
+    .map(Cell::from)
+    .collect::<Row>()
+    .style(Style::default().fg(colours.header_fg).bg(colours.header_bg));
+
+    let kda = |(k, d, a): (u64, u64, u64)| format!("{k}/{d}/{a}");
+    let diff = |d: Option<i64>| d.map_or("-".to_string(), |d| format!("{d:+}"));
+    let rows = versus.games.iter().enumerate().map(|(i, g)| {
+        let (relation, winner) = match (g.same_team, g.win) {
+            (true, true) => ("together", "both"),
+            (true, false) => ("together", "neither"),
+            (false, true) => ("against", a.as_str()),
+            (false, false) => ("against", b.as_str()),
+        };
+        let role = match g.roles[0] == g.roles[1] {
+            true => g.roles[0].clone(),
+            false => format!("{}/{}", g.roles[0], g.roles[1]),
+        };
+        // Lane differences only mean something against each other in the same role
+        let (cd10, gd10) = match g.is_lane_matchup() {
+            true => (diff(g.cd10), diff(g.gd10)),
+            false => ("".to_string(), "".to_string()),
+        };
+        Row::new([
+            Cell::from(g.date.clone().unwrap_or_else(|| "-".to_string())),
+            Cell::from(relation),
+            Cell::from(g.champions[0].clone()).style(Style::default().fg(PLAYER_COLOURS[0])),
+            Cell::from(g.champions[1].clone()).style(Style::default().fg(PLAYER_COLOURS[1])),
+            Cell::from(role),
+            Cell::from(winner.to_string()),
+            Cell::from(kda(g.kda[0])),
+            Cell::from(kda(g.kda[1])),
+            Cell::from(Text::from(cd10).alignment(Alignment::Right)),
+            Cell::from(Text::from(gd10).alignment(Alignment::Right)),
+        ])
+        .style(Style::new().fg(colours.row_fg).bg(match i % 2 {
+            0 => colours.normal_row_color,
+            _ => colours.alt_row_color,
+        }))
+    });
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Shared games ")
+        .title_bottom(lane_summary(versus, &a, &b));
+    match versus.games.is_empty() {
+        true => Text::from("These players haven't been in a game together.")
+            .style(Style::default().fg(tailwind::SLATE.c400))
+            .render(block.inner(area), buf),
+        false => Table::new(
+            rows,
+            [
+                Constraint::Length(10),
+                Constraint::Length(8),
+                Constraint::Min(10),
+                Constraint::Min(10),
+                Constraint::Length(15),
+                Constraint::Min(8),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(6),
+                Constraint::Length(6),
+            ],
+        )
+        .header(header)
+        .render(block.inner(area), buf),
+    }
+    block.render(area, buf);
+}
+
+/// The head-to-head lane record and average differences, from the first
+/// player's side.
+fn lane_summary(versus: &PlayerVersus, a: &str, b: &str) -> String {
+    let lane: Vec<&SharedGame> = versus.lane_games().collect();
+    if lane.is_empty() {
+        return " They haven't faced each other in lane ".to_string();
+    }
+    let wins = lane.iter().filter(|g| g.win).count();
+    let average = |diffs: Vec<i64>| match diffs.len() {
+        0 => "-".to_string(),
+        n => format!("{:+.0}", diffs.iter().sum::<i64>() as f64 / n as f64),
+    };
+    format!(
+        " In lane: {a} {wins}-{} {b}, CSD@10 {}, GD@10 {} ",
+        lane.len() - wins,
+        average(lane.iter().filter_map(|g| g.cd10).collect()),
+        average(lane.iter().filter_map(|g| g.gd10).collect()),
+    )
+}
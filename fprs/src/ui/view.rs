@@ -8,18 +8,23 @@ use ratatui::{
     symbols::line::{self, Set},
     text::{Line, Span, Text},
     widgets::{
-        Block, Borders, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget,
-        Tabs, Widget, Wrap,
+        Block, Borders, Cell, HighlightSpacing, List, ListItem, ListState, Paragraph, Row,
+        StatefulWidget, Table, Tabs, Widget, Wrap,
     },
 };
 use reqwest::header::WARNING;
 use serde_json::Value;
 
 use crate::{
-    app::{AlertType, App, CurrentScreen, StatsTab},
-    sql::schema::Game,
-    ui::{draw_scoreboard, lolui},
+    app::{AlertType, App, CurrentScreen, GameField, StatsTab},
+    import::{FileStatus, ImportOutcome},
+    sql::schema::{Game, GameTimeline, MatchSeries},
+    ui::{
+        chart::draw_lead_chart, detail::draw_game_detail, draw_scoreboard,
+        head_to_head::draw_head_to_head, lolui, synergy::draw_synergy, versus::draw_player_versus,
+    },
 };
+use std::collections::HashMap;
 use strum::IntoEnumIterator;
 
 const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
@@ -49,7 +54,13 @@ pub fn view(frame: &mut Frame, app: &mut App) {
         .style(Style::default());
 
     let title = Paragraph::new(Text::styled(
-        format!("LoL Stat Tracker | {} games tracked.", app.game_count),
+        match app.game_filter.is_empty() {
+            true => format!("LoL Stat Tracker | {} games tracked.", app.game_count),
+            false => format!(
+                "LoL Stat Tracker | {} games match the filter.",
+                app.game_count
+            ),
+        },
         Style::default().fg(Color::Green),
     ))
     .block(title_block.clone());
@@ -62,6 +73,12 @@ pub fn view(frame: &mut Frame, app: &mut App) {
         CurrentScreen::Search => render_search(frame, chunks[1], app),
         CurrentScreen::Quit => render_quit(frame, chunks[1], app),
         CurrentScreen::Stats => render_stats(frame, chunks[1], app),
+        CurrentScreen::Roster => render_roster(frame, chunks[1], app),
+        CurrentScreen::EditGame => render_edit_game(frame, chunks[1], app),
+        CurrentScreen::GameDetail => render_game_detail(frame, chunks[1], app),
+        CurrentScreen::HeadToHead => render_head_to_head(frame, chunks[1], app),
+        CurrentScreen::PlayerVersus => render_player_versus(frame, chunks[1], app),
+        CurrentScreen::Synergy => render_synergy(frame, chunks[1], app),
     }
 
     let current_navigation_text = vec![
@@ -76,6 +93,20 @@ pub fn view(frame: &mut Frame, app: &mut App) {
             CurrentScreen::Search => Span::styled("Search", Style::default().fg(Color::LightRed)),
             CurrentScreen::Quit => Span::styled("Search", Style::default().fg(Color::Red)),
             CurrentScreen::Stats => Span::styled("Stats", Style::default().fg(Color::White)),
+            CurrentScreen::Roster => Span::styled("Rosters", Style::default().fg(Color::Yellow)),
+            CurrentScreen::EditGame => {
+                Span::styled("Edit game", Style::default().fg(Color::Magenta))
+            }
+            CurrentScreen::GameDetail => {
+                Span::styled("Game detail", Style::default().fg(Color::Cyan))
+            }
+            CurrentScreen::HeadToHead => {
+                Span::styled("Head to head", Style::default().fg(Color::White))
+            }
+            CurrentScreen::PlayerVersus => {
+                Span::styled("Player vs player", Style::default().fg(Color::White))
+            }
+            CurrentScreen::Synergy => Span::styled("Synergy", Style::default().fg(Color::White)),
         }
         .to_owned(),
         // A white divider bar to separate the two sections
@@ -87,18 +118,52 @@ pub fn view(frame: &mut Frame, app: &mut App) {
 
     let current_keys_hint = {
         match app.current_screen {
-            CurrentScreen::Main
-            | CurrentScreen::Quit
-            | CurrentScreen::Start
-            | CurrentScreen::Stats => Span::styled(
-                "(q) to quit, (i) to import manual data",
+            CurrentScreen::Main | CurrentScreen::Start if app.filtering => Span::styled(
+                "<Enter> to keep the filter, <Esc> to clear it",
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::Main | CurrentScreen::Quit | CurrentScreen::Start => Span::styled(
+                "(q) to quit, <Enter> for details, (e) to edit, (i) to import, (r) for rosters, (/) to filter",
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::GameDetail => Span::styled(
+                "<Esc> to return to home, ([/]) to switch tabs, (d) to enter the draft",
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::EditGame => Span::styled(
+                "<Enter> to edit field, (s) to swap sides, (w) to save, <Esc> to discard",
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::Roster => Span::styled(
+                "(n) to add, <Del> to remove, (a) to assign teams to games, <Esc> to return",
                 Style::default().fg(Color::Red),
             ),
+            CurrentScreen::Stats => Span::styled(
+                match (app.stats_tab, &app.versus_pick) {
+                    (StatsTab::Player, Some(_)) => {
+                        "(c) on another player to compare, or on the same one to drop it"
+                    }
+                    (StatsTab::Player, None) => {
+                        "<Esc> to return to home, ([/]) to switch tabs, (e) to export, (c) to compare players, (y) for synergy"
+                    }
+                    _ => {
+                        "<Esc> to return to home, ([/]) to switch tabs, (e) to export, (v) to compare teams"
+                    }
+                },
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::HeadToHead | CurrentScreen::PlayerVersus | CurrentScreen::Synergy => {
+                Span::styled("<Esc> to return to stats", Style::default().fg(Color::Red))
+            }
             CurrentScreen::ImportManual => match app.show_input {
                 true => Span::styled(
                     "Enter the path to the manual data",
                     Style::default().fg(Color::Red),
                 ),
+                false if app.import_plan.is_some() => Span::styled(
+                    "(Space) to toggle, (a) to toggle all, <Enter> to import, <Esc> to cancel",
+                    Style::default().fg(Color::Red),
+                ),
                 false => Span::styled("<Esc> to return to home", Style::default().fg(Color::Red)),
             },
             CurrentScreen::Search => Span::styled(
@@ -137,7 +202,10 @@ fn render_input(app: &App, frame: &mut Frame, area: Rect) {
     let scroll = app.input.visual_scroll(width as usize);
     let input = Paragraph::new(app.input.value())
         .scroll((0, scroll as u16))
-        .block(Block::bordered().title("Input"));
+        .block(Block::bordered().title(match app.input_title.as_str() {
+            "" => "Input",
+            title => title,
+        }));
     frame.render_widget(input, area);
 
     // Ratatui hides the cursor unless it's explicitly set. Position the  cursor past the
@@ -195,6 +263,7 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 #[derive(Default)]
 pub struct GameList {
     items: Vec<Game>,
+    series: HashMap<i64, MatchSeries>,
     pub state: ListState,
 }
 
@@ -202,11 +271,38 @@ impl FromIterator<Game> for GameList {
     fn from_iter<I: IntoIterator<Item = Game>>(iter: I) -> Self {
         let items = iter.into_iter().collect();
         let state = ListState::default();
-        Self { items, state }
+        Self {
+            items,
+            series: HashMap::new(),
+            state,
+        }
     }
 }
 
 impl GameList {
+    /// Keeps games of the same series together, in game order, at the
+    /// position of the series' first game in `games`.
+    pub fn with_series(games: Vec<Game>, series: Vec<MatchSeries>) -> Self {
+        let mut first_seen: HashMap<i64, usize> = HashMap::new();
+        for (i, game) in games.iter().enumerate() {
+            if let Some(id) = game.series_id {
+                first_seen.entry(id).or_insert(i);
+            }
+        }
+
+        let mut indexed: Vec<(usize, Game)> = games.into_iter().enumerate().collect();
+        indexed.sort_by_key(|(i, game)| match game.series_id {
+            Some(id) => (first_seen[&id], game.game_number.unwrap_or(u64::MAX), *i),
+            None => (*i, 0, *i),
+        });
+
+        Self {
+            items: indexed.into_iter().map(|(_, game)| game).collect(),
+            series: series.into_iter().map(|s| (s.id, s)).collect(),
+            state: ListState::default(),
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.items.len()
     }
@@ -214,17 +310,98 @@ impl GameList {
     pub fn get_item(&self, idx: usize) -> Option<&Game> {
         self.items.get(idx)
     }
+
+    pub fn position_of(&self, game_id: u64) -> Option<usize> {
+        self.items.iter().position(|g| g.id == game_id)
+    }
 }
 
 fn game_str(game: &Game) -> String {
-    format!("{} | {} vs {}", game.id, game.team_1, game.team_2)
+    match (game.series_id, game.game_number) {
+        (Some(_), Some(n)) => format!(
+            "  G{} | {} | {} vs {}",
+            n, game.id, game.team_1, game.team_2
+        ),
+        (Some(_), None) => format!("  {} | {} vs {}", game.id, game.team_1, game.team_2),
+        _ => format!("{} | {} vs {}", game.id, game.team_1, game.team_2),
+    }
+}
+
+fn series_str(series: &MatchSeries) -> String {
+    format!(
+        "{} | {} vs {}{}{}",
+        series.stage.as_deref().unwrap_or("Series"),
+        series.team_1,
+        series.team_2,
+        series
+            .best_of
+            .map(|n| format!(" | Bo{n}"))
+            .unwrap_or_default(),
+        series
+            .scheduled_date
+            .as_ref()
+            .map(|d| format!(" | {d}"))
+            .unwrap_or_default()
+    )
+}
+
+/// A list entry for a game, headed by its series when it is the first game
+/// of that series in the list.
+fn game_item<'a>(matches: &GameList, i: usize, game: &Game) -> ListItem<'a> {
+    let series_header = game
+        .series_id
+        .filter(|id| i == 0 || matches.items[i - 1].series_id != Some(*id))
+        .and_then(|id| matches.series.get(&id));
+
+    match series_header {
+        Some(series) => ListItem::from(Text::from(vec![
+            Line::styled(series_str(series), Style::default().fg(Color::Cyan)),
+            Line::from(game_str(game)),
+        ])),
+        None => ListItem::from(game_str(game)),
+    }
 }
 
 fn render_main(frame: &mut Frame, area: Rect, app: &mut App) {
-    render_match_browser(frame, area, &mut app.db_games);
+    if !app.filtering && app.filter_input.value().is_empty() {
+        render_match_browser(frame, area, &mut app.db_games, &app.timelines);
+        return;
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(area);
+    render_filter(app, frame, chunks[0]);
+    render_match_browser(frame, chunks[1], &mut app.db_games, &app.timelines);
 }
 
-fn render_match_browser(frame: &mut Frame, area: Rect, matches: &mut GameList) {
+fn render_filter(app: &App, frame: &mut Frame, area: Rect) {
+    let width = area.width.max(3) - 3;
+    let scroll = app.filter_input.visual_scroll(width as usize);
+    let (title, style) = match &app.filter_error {
+        Some(e) => (
+            format!("Filter | {e}"),
+            Style::default().fg(Color::LightRed),
+        ),
+        None => ("Filter".to_string(), Style::default()),
+    };
+    let filter = Paragraph::new(app.filter_input.value())
+        .scroll((0, scroll as u16))
+        .block(Block::bordered().title(title).border_style(style));
+    frame.render_widget(filter, area);
+
+    if app.filtering {
+        let x = app.filter_input.visual_cursor().max(scroll) - scroll + 1;
+        frame.set_cursor_position((area.x + x as u16, area.y + 1))
+    }
+}
+
+fn render_match_browser(
+    frame: &mut Frame,
+    area: Rect,
+    matches: &mut GameList,
+    timelines: &HashMap<u64, GameTimeline>,
+) {
     let title_block = Block::default()
         .borders(Borders::ALL)
         .style(Style::default());
@@ -238,7 +415,7 @@ fn render_match_browser(frame: &mut Frame, area: Rect, matches: &mut GameList) {
         .items
         .iter()
         .enumerate()
-        .map(|(i, g)| ListItem::from(game_str(g)).bg(alternate_colors(i)))
+        .map(|(i, g)| game_item(matches, i, g).bg(alternate_colors(i)))
         .collect();
 
     let game_list = List::new(items)
@@ -254,12 +431,20 @@ fn render_match_browser(frame: &mut Frame, area: Rect, matches: &mut GameList) {
         &mut matches.state,
     );
 
-    match matches.state.selected() {
-        Some(m) => draw_scoreboard(
-            frame.buffer_mut(),
-            inner_chunks[1],
-            matches.get_item(m).unwrap(),
-        ),
+    match matches.state.selected().and_then(|m| matches.get_item(m)) {
+        Some(game) => {
+            let [scoreboard_area, chart_area] =
+                Layout::horizontal([Constraint::Min(60), Constraint::Percentage(40)])
+                    .areas(inner_chunks[1]);
+            draw_scoreboard(frame.buffer_mut(), scoreboard_area, game);
+            draw_lead_chart(
+                frame.buffer_mut(),
+                chart_area,
+                game,
+                timelines.get(&game.id),
+                false,
+            );
+        }
         None => {
             Block::default()
                 .borders(Borders::ALL)
@@ -269,18 +454,243 @@ fn render_match_browser(frame: &mut Frame, area: Rect, matches: &mut GameList) {
 }
 
 fn render_import(frame: &mut Frame, area: Rect, app: &mut App) {
-    let central_block = Block::default()
-        .borders(Borders::ALL)
-        .style(Style::default());
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(area);
 
-    let content = Paragraph::new(Text::styled(app.import_message.clone(), Style::default()))
-        .block(central_block);
+    let summary = Paragraph::new(Text::styled(app.import_message.clone(), Style::default()))
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(summary, chunks[0]);
 
-    frame.render_widget(content, area);
+    if app.import_plan.is_some() {
+        render_import_preview(frame, chunks[1], app);
+        return;
+    }
+
+    let header = Row::new(["File", "Game", "Result"]).style(Style::new().bold());
+    let rows = app
+        .import_report
+        .entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let colour = match entry.outcome {
+                ImportOutcome::Imported => Color::Green,
+                ImportOutcome::Updated => Color::Cyan,
+                ImportOutcome::Skipped(_) => Color::Yellow,
+                ImportOutcome::Failed(_) => Color::Red,
+            };
+            Row::new([
+                Cell::from(entry.file.clone()),
+                Cell::from(entry.game_id.map(|id| id.to_string()).unwrap_or_default()),
+                Cell::from(entry.outcome.to_string()).fg(colour),
+            ])
+            .bg(alternate_colors(i))
+        });
+    let table = Table::new(
+        rows,
+        [Constraint::Max(30), Constraint::Max(12), Constraint::Min(1)],
+    )
+    .header(header)
+    .row_highlight_style(SELECTED_STYLE)
+    .highlight_spacing(HighlightSpacing::Always)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Import report"),
+    );
+
+    frame.render_stateful_widget(table, chunks[1], &mut app.import_table_state);
+}
+
+fn render_import_preview(frame: &mut Frame, area: Rect, app: &mut App) {
+    let Some(plan) = &app.import_plan else {
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(area);
+
+    let header = Row::new(["", "File", "Game", "Status"]).style(Style::new().bold());
+    let rows = plan.files.iter().enumerate().map(|(i, file)| {
+        let colour = match file.status {
+            FileStatus::New => Color::Green,
+            FileStatus::Changed(_) => Color::Cyan,
+            FileStatus::Unchanged => Color::Gray,
+            FileStatus::Skipped(_) => Color::Yellow,
+            FileStatus::Failed(_) => Color::Red,
+        };
+        let check = match (file.selectable(), file.selected) {
+            (false, _) => "",
+            (true, true) => "[x]",
+            (true, false) => "[ ]",
+        };
+        Row::new([
+            Cell::from(check),
+            Cell::from(file.file.clone()),
+            Cell::from(file.game_id.map(|id| id.to_string()).unwrap_or_default()),
+            Cell::from(file.status.to_string()).fg(colour),
+        ])
+        .bg(alternate_colors(i))
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(3),
+            Constraint::Max(30),
+            Constraint::Max(12),
+            Constraint::Min(1),
+        ],
+    )
+    .header(header)
+    .row_highlight_style(SELECTED_STYLE)
+    .highlight_spacing(HighlightSpacing::Always)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Import preview"),
+    );
+
+    let changes: Vec<Line> = match app
+        .import_table_state
+        .selected()
+        .and_then(|i| plan.files.get(i))
+        .map(|f| &f.status)
+    {
+        Some(FileStatus::Changed(changes)) if changes.is_empty() => {
+            vec![Line::from("Only lists such as bans differ")]
+        }
+        Some(FileStatus::Changed(changes)) => changes
+            .iter()
+            .map(|c| {
+                Line::from(vec![
+                    Span::styled(format!("{} ", c.location), Style::new().bold()),
+                    Span::raw(format!("{}: ", c.field)),
+                    Span::styled(c.old.clone(), Style::new().fg(Color::Red)),
+                    Span::raw(" -> "),
+                    Span::styled(c.new.clone(), Style::new().fg(Color::Green)),
+                ])
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let diff = Paragraph::new(changes)
+        .block(Block::default().borders(Borders::ALL).title("Changes"))
+        .wrap(Wrap { trim: false });
+
+    frame.render_stateful_widget(table, chunks[0], &mut app.import_table_state);
+    frame.render_widget(diff, chunks[1]);
+}
+
+fn render_game_detail(frame: &mut Frame, area: Rect, app: &mut App) {
+    let selected = app.db_games.state.selected();
+    match selected.and_then(|i| app.db_games.get_item(i)) {
+        Some(game) => draw_game_detail(
+            frame.buffer_mut(),
+            area,
+            game,
+            &app.detail_draft,
+            app.timelines.get(&game.id),
+            app.detail_tab,
+        ),
+        None => Block::default()
+            .borders(Borders::ALL)
+            .render(area, frame.buffer_mut()),
+    }
+}
+
+fn render_head_to_head(frame: &mut Frame, area: Rect, app: &mut App) {
+    match &app.head_to_head {
+        Some(h2h) => draw_head_to_head(frame.buffer_mut(), area, h2h),
+        None => Block::default()
+            .borders(Borders::ALL)
+            .render(area, frame.buffer_mut()),
+    }
+}
+
+fn render_player_versus(frame: &mut Frame, area: Rect, app: &mut App) {
+    match &app.player_versus {
+        Some(versus) => {
+            draw_player_versus(frame.buffer_mut(), area, versus, &app.player_deep_stats)
+        }
+        None => Block::default()
+            .borders(Borders::ALL)
+            .render(area, frame.buffer_mut()),
+    }
+}
+
+fn render_synergy(frame: &mut Frame, area: Rect, app: &mut App) {
+    match &app.synergy {
+        Some(synergy) => draw_synergy(frame.buffer_mut(), area, synergy),
+        None => Block::default()
+            .borders(Borders::ALL)
+            .render(area, frame.buffer_mut()),
+    }
+}
+
+fn render_edit_game(frame: &mut Frame, area: Rect, app: &mut App) {
+    let Some(form) = app.edit_game.as_mut() else {
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(50), Constraint::Min(1)])
+        .split(area);
+
+    let rows = GameField::iter()
+        .enumerate()
+        .map(|(i, field)| Row::new([field.to_string(), form.value(field)]).bg(alternate_colors(i)));
+    let table = Table::new(rows, [Constraint::Length(15), Constraint::Min(10)])
+        .row_highlight_style(SELECTED_STYLE)
+        .highlight_spacing(HighlightSpacing::Always)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Edit game {}", form.game_id)),
+        );
+    frame.render_stateful_widget(table, chunks[0], &mut form.state);
+
+    // Show the game as it was stored for reference
+    match app.db_games.items.iter().find(|g| g.id == form.game_id) {
+        Some(game) => draw_scoreboard(frame.buffer_mut(), chunks[1], game),
+        None => Block::default()
+            .borders(Borders::ALL)
+            .render(chunks[1], frame.buffer_mut()),
+    }
+}
+
+fn render_roster(frame: &mut Frame, area: Rect, app: &mut App) {
+    let header = Row::new(["Team", "Player", "From", "To"]).style(Style::new().bold());
+    let rows = app.roster.iter().enumerate().map(|(i, entry)| {
+        Row::new([
+            entry.team.clone(),
+            entry.player.clone(),
+            entry.start_date.clone().unwrap_or_else(|| "-".into()),
+            entry.end_date.clone().unwrap_or_else(|| "-".into()),
+        ])
+        .bg(alternate_colors(i))
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Max(25),
+            Constraint::Min(20),
+            Constraint::Max(12),
+            Constraint::Max(12),
+        ],
+    )
+    .header(header)
+    .row_highlight_style(SELECTED_STYLE)
+    .highlight_spacing(HighlightSpacing::Always)
+    .block(Block::default().borders(Borders::ALL).title("Rosters"));
+
+    frame.render_stateful_widget(table, area, &mut app.roster_table_state);
 }
 
 fn render_search(frame: &mut Frame, area: Rect, app: &mut App) {
-    render_match_browser(frame, area, &mut app.search_games);
+    render_match_browser(frame, area, &mut app.search_games, &app.timelines);
 }
 
 fn render_quit(frame: &mut Frame, area: Rect, app: &mut App) {
@@ -313,7 +723,9 @@ fn render_stats(frame: &mut Frame, area: Rect, app: &mut App) {
     match app.stats_tab {
         StatsTab::Game => lolui::draw_overall(frame.buffer_mut(), content_area, app),
         StatsTab::Player => lolui::draw_players(frame.buffer_mut(), content_area, app),
+        StatsTab::Team => lolui::draw_teams(frame.buffer_mut(), content_area, app),
+        StatsTab::Standings => lolui::draw_standings(frame.buffer_mut(), content_area, app),
         StatsTab::Champion => lolui::draw_champions(frame.buffer_mut(), content_area, app),
-        _ => {}
+        StatsTab::Draft => lolui::draw_draft_tendencies(frame.buffer_mut(), content_area, app),
     };
 }
@@ -1,55 +1,64 @@
 use std::{fmt::format, path::PathBuf, result};
 
+use chrono::NaiveDate;
 use color_eyre::eyre::{Result, eyre};
 use ratatui::crossterm::event::{Event, KeyCode, KeyModifiers};
 use rusqlite::Connection;
 use serde_json::Value;
-use tui_input::backend::crossterm::EventHandler;
+use tui_input::{Input, backend::crossterm::EventHandler};
 
 use crate::{
-    app::{AlertType, App, CurrentScreen, Message, StatsTab},
-    command::{APP_NAME, import_manual_matches},
-    riot,
+    app::{
+        AlertType, Aliases, App, CurrentScreen, DetailTab, GameField, GameForm, Message, StatsTab,
+        aliases_path,
+    },
+    command::{self, APP_NAME},
+    export::ExportFormat,
+    import::{self, ImportPlan, ImportReport},
     sql::{
+        draft,
+        filter::GameFilter,
+        rating::RatingKind,
         repo,
-        schema::{Game, PlayerChampionStats, PlayerDeepStats, PlayerRoleStats},
+        schema::{Game, PlayerDeepStats, PlayerVersus, TeamStats, UnreadableGame},
+        series,
+        synergy::Synergy,
     },
-    ui::GameList,
+    ui::{GameList, lolui::sorted_players},
 };
 
 const ROLES: [&'static str; 5] = ["TOP", "JUNGLE", "MIDDLE", "BOTTOM", "UTILITY"];
 
+/// Prompts for a new roster entry, in order.
+const ROSTER_FIELDS: [&str; 4] = [
+    "Team",
+    "Player (puuid, name or name#tag)",
+    "On the team from (YYYY-MM-DD, blank for always)",
+    "On the team until (YYYY-MM-DD, blank for still on it)",
+];
+
 pub fn update(app: &mut App, msg: Message) -> Option<Message> {
     match msg {
         Message::NextTab => match app.current_screen {
             CurrentScreen::Stats => {
                 app.next_stats_tab();
                 match app.stats_tab {
-                    StatsTab::Champion => {
-                        app.all_champs_stats =
-                            repo::stats_all_champions(&app.db_connection.as_ref().unwrap())
-                                .unwrap();
-                        app.champs_history =
-                            repo::stats_champion_history(&app.db_connection.as_ref().unwrap())
-                                .unwrap();
-                    }
+                    StatsTab::Champion => load_champion_stats(app),
                     _ => {}
                 }
             }
+            CurrentScreen::GameDetail => app.next_detail_tab(),
             _ => {}
         },
         Message::PrevTab => match app.current_screen {
             CurrentScreen::Stats => {
                 app.previous_stats_tab();
                 match app.stats_tab {
-                    StatsTab::Champion => {
-                        app.all_champs_stats =
-                            repo::stats_all_champions(&app.db_connection.as_ref().unwrap())
-                                .unwrap();
-                    }
+                    StatsTab::Champion => load_champion_stats(app),
                     _ => {}
                 }
             }
+            CurrentScreen::GameDetail => app.previous_detail_tab(),
             _ => {}
         },
         Message::OpenAlert => {
@@ -61,17 +70,50 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
             app.show_alert = false;
         }
         Message::ReloadDatabaseGames => {
-            app.db_games = GameList::from_iter(
-                repo::all_games(app.db_connection.as_ref().unwrap()).unwrap_or(Vec::new()),
+            let selected = app
+                .db_games
+                .state
+                .selected()
+                .and_then(|i| app.db_games.get_item(i))
+                .map(|g| g.id);
+            let (games, unreadable) =
+                repo::all_games(app.db_connection.as_ref().unwrap(), &app.game_filter)
+                    .unwrap_or_default();
+            app.db_games = GameList::with_series(
+                games,
+                repo::all_series(app.db_connection.as_ref().unwrap()).unwrap_or_default(),
             );
+            alert_unreadable(app, &unreadable);
             app.game_count = app.db_games.len() as i64;
+            // Stay on the same game, e.g. after editing it
+            app.db_games
+                .state
+                .select(selected.and_then(|id| app.db_games.position_of(id)));
+            // Reloads run on every filter keystroke, and timelines only change
+            // when a game is added
+            if !app.timelines_loaded {
+                match repo::all_timelines(app.db_connection.as_ref().unwrap()) {
+                    Ok(timelines) => {
+                        app.timelines = timelines;
+                        app.timelines_loaded = true;
+                    }
+                    Err(e) => {
+                        app.alert_message = format!("Failed to load timelines: {e}");
+                        app.alert_type = AlertType::Error;
+                        return Some(Message::OpenAlert);
+                    }
+                }
+            }
         }
         Message::ListEnd => match app.current_screen {
             CurrentScreen::Start | CurrentScreen::Main => app.db_games.state.select_last(),
             CurrentScreen::Search => app.search_games.state.select_last(),
             CurrentScreen::Stats => match app.stats_tab {
                 StatsTab::Player => app.players_table_state.select_last(),
+                StatsTab::Team => app.teams_table_state.select_last(),
+                StatsTab::Standings => app.standings_table_state.select_last(),
                 StatsTab::Champion => app.all_champs_state.select_last(),
+                StatsTab::Draft => app.draft_table_state.select_last(),
                 _ => {}
             },
             _ => {}
@@ -81,7 +123,10 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
             CurrentScreen::Search => app.search_games.state.select_first(),
             CurrentScreen::Stats => match app.stats_tab {
                 StatsTab::Player => app.players_table_state.select_first(),
+                StatsTab::Team => app.teams_table_state.select_first(),
+                StatsTab::Standings => app.standings_table_state.select_first(),
                 StatsTab::Champion => app.all_champs_state.select_first(),
+                StatsTab::Draft => app.draft_table_state.select_first(),
                 _ => {}
             },
             _ => {}
@@ -89,9 +134,19 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
         Message::ListDown => match app.current_screen {
             CurrentScreen::Start | CurrentScreen::Main => app.db_games.state.select_next(),
             CurrentScreen::Search => app.search_games.state.select_next(),
+            CurrentScreen::ImportManual => app.import_table_state.select_next(),
+            CurrentScreen::Roster => app.roster_table_state.select_next(),
+            CurrentScreen::EditGame => {
+                if let Some(form) = app.edit_game.as_mut() {
+                    form.state.select_next();
+                }
+            }
             CurrentScreen::Stats => match app.stats_tab {
                 StatsTab::Player => app.players_table_state.select_next(),
+                StatsTab::Team => app.teams_table_state.select_next(),
+                StatsTab::Standings => app.standings_table_state.select_next(),
                 StatsTab::Champion => app.all_champs_state.select_next(),
+                StatsTab::Draft => app.draft_table_state.select_next(),
                 _ => {}
             },
             _ => {}
@@ -99,9 +154,19 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
         Message::ListUp => match app.current_screen {
             CurrentScreen::Start | CurrentScreen::Main => app.db_games.state.select_previous(),
             CurrentScreen::Search => app.search_games.state.select_previous(),
+            CurrentScreen::ImportManual => app.import_table_state.select_previous(),
+            CurrentScreen::Roster => app.roster_table_state.select_previous(),
+            CurrentScreen::EditGame => {
+                if let Some(form) = app.edit_game.as_mut() {
+                    form.state.select_previous();
+                }
+            }
             CurrentScreen::Stats => match app.stats_tab {
                 StatsTab::Player => app.players_table_state.select_previous(),
+                StatsTab::Team => app.teams_table_state.select_previous(),
+                StatsTab::Standings => app.standings_table_state.select_previous(),
                 StatsTab::Champion => app.all_champs_state.select_previous(),
+                StatsTab::Draft => app.draft_table_state.select_previous(),
                 _ => {}
             },
             _ => {}
@@ -109,6 +174,7 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
         Message::ListLeft => match app.current_screen {
             CurrentScreen::Stats => match app.stats_tab {
                 StatsTab::Player => app.players_table_state.select_previous_column(),
+                StatsTab::Team => app.teams_table_state.select_previous_column(),
                 StatsTab::Champion => app.all_champs_state.select_previous_column(),
                 _ => {}
             },
@@ -117,6 +183,7 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
         Message::ListRight => match app.current_screen {
             CurrentScreen::Stats => match app.stats_tab {
                 StatsTab::Player => app.players_table_state.select_next_column(),
+                StatsTab::Team => app.teams_table_state.select_next_column(),
                 StatsTab::Champion => app.all_champs_state.select_next_column(),
                 _ => {}
             },
@@ -128,6 +195,7 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
         Message::InputFinished => {
             app.messages.push(app.input.value_and_reset());
             app.show_input = false;
+            app.input_title.clear();
             app.current_screen = app.next_screen.clone();
             let msg = app.post_message.clone();
             app.post_message = None;
@@ -135,6 +203,7 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
         }
         Message::InputCancelled => {
             app.show_input = false;
+            app.input_title.clear();
         }
         Message::InputKey(key) => {
             if app.show_input {
@@ -149,11 +218,13 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
                         }
                         KeyCode::Char('<') => match app.stats_tab {
                             StatsTab::Player => app.players_sort_dir = -1,
+                            StatsTab::Team => app.teams_sort_dir = -1,
                             StatsTab::Champion => app.champs_sort_dir = -1,
                             _ => {}
                         },
                         KeyCode::Char('>') => match app.stats_tab {
                             StatsTab::Player => app.players_sort_dir = 1,
+                            StatsTab::Team => app.teams_sort_dir = 1,
                             StatsTab::Champion => app.champs_sort_dir = 1,
                             _ => {}
                         },
@@ -176,25 +247,90 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
             app.previous_screen = app.current_screen.clone();
             app.next_screen = CurrentScreen::ImportManual;
             app.post_message = Some(Message::DoImportManual);
+            app.input_title = "Path to the manual data".to_string();
             return Some(Message::PromptInput);
         }
-        Message::DoImportManual => {
-            let import_result = do_import_manual(app);
-            match import_result {
-                Ok(n) => {
-                    app.import_message = format!("Imported {} games", n);
-                    app.alert_message = format!("Imported {} games", n);
-                    app.alert_type = AlertType::Success;
-                    return Some(Message::OpenAlert);
+        Message::DoImportManual => match find_unresolved_teams(app) {
+            Ok(()) => return Some(Message::PromptImportTeam),
+            Err(e) => {
+                app.import_message = format!("Import failed: {}", e);
+                app.alert_message = format!("Import failed: {}", e);
+                app.alert_type = AlertType::Error;
+                app.import_plan = None;
+                return Some(Message::OpenAlert);
+            }
+        },
+        Message::PromptImportTeam => match app.import_unresolved.first() {
+            Some(abbr) => {
+                app.input_title = format!("Team name for '{abbr}' (blank to leave unset)");
+                app.next_screen = CurrentScreen::ImportManual;
+                app.post_message = Some(Message::ResolveImportTeam);
+                return Some(Message::PromptInput);
+            }
+            None => return Some(Message::PlanImportManual),
+        },
+        Message::ResolveImportTeam => {
+            let abbr = app.import_unresolved.remove(0);
+            let name = app.messages.last().map(|n| n.trim()).unwrap_or_default();
+            if !name.is_empty() {
+                app.import_naming.set_team(&abbr, name);
+            }
+            return Some(Message::PromptImportTeam);
+        }
+        Message::PlanImportManual => {
+            let plan_result = do_import_manual(app);
+            match plan_result {
+                Ok(plan) => {
+                    app.import_message = plan.summary();
+                    app.import_plan = Some(plan);
+                    app.import_table_state.select_first();
                 }
                 Err(e) => {
                     app.import_message = format!("Import failed: {}", e);
                     app.alert_message = format!("Import failed: {}", e);
                     app.alert_type = AlertType::Error;
+                    app.import_plan = None;
+                    app.import_report = ImportReport::default();
                     return Some(Message::OpenAlert);
                 }
             }
         }
+        Message::ToggleImportFile => {
+            if let (Some(plan), Some(i)) = (&mut app.import_plan, app.import_table_state.selected())
+            {
+                plan.toggle(i);
+                app.import_message = plan.summary();
+            }
+        }
+        Message::ToggleAllImportFiles => {
+            if let Some(plan) = &mut app.import_plan {
+                plan.toggle_all();
+                app.import_message = plan.summary();
+            }
+        }
+        Message::ApplyImportManual => {
+            let plan = app.import_plan.take()?;
+            let aliases = match Aliases::load(&aliases_path(APP_NAME)) {
+                Ok(aliases) => aliases.by_name(),
+                Err(e) => {
+                    app.alert_message = format!("Import failed: {e}");
+                    app.alert_type = AlertType::Error;
+                    app.import_plan = Some(plan);
+                    return Some(Message::OpenAlert);
+                }
+            };
+            let report = import::apply(app.db_connection.as_ref().unwrap(), &plan, &aliases);
+            app.import_message = report.summary();
+            app.alert_message = report.summary();
+            app.alert_type = match report.failed() {
+                0 => AlertType::Success,
+                _ => AlertType::Error,
+            };
+            app.show_alert = true;
+            app.import_report = report;
+            app.import_table_state.select_first();
+            return Some(Message::ReloadDatabaseGames);
+        }
         Message::OpenSearch => {
             app.previous_screen = app.current_screen.clone();
             app.next_screen = CurrentScreen::Search;
@@ -207,19 +343,11 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
                 Ok(matches) => {
                     app.alert_message = format!("Search successful!");
                     app.alert_type = AlertType::Success;
-                    app.search_games = GameList::from_iter(matches.iter().map(|m| {
-                        Game {
-                            id: m
-                                .get("metadata")
-                                .and_then(|md| md.get("matchId"))
-                                .and_then(|mid| mid.as_u64())
-                                .unwrap_or(0),
-                            data: m.clone(),
-                            // manual: true,
-                            team_1: "Unknown 1".to_string(),
-                            team_2: "Unknown 2".to_string(),
-                        }
-                    }));
+                    app.search_games = GameList::from_iter(
+                        matches
+                            .into_iter()
+                            .filter_map(|m| Game::from_data(m, "Unknown 1", "Unknown 2").ok()),
+                    );
                     return Some(Message::OpenAlert);
                 }
                 Err(e) => {
@@ -261,60 +389,84 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
                 .search_games
                 .state
                 .selected()
-                .map(|i| {
-                    &app.search_games
-                        .get_item(i)
-                        .ok_or(color_eyre::eyre::eyre!("failed to get game obj"))
-                        .unwrap()
-                        .data
-                })
+                .and_then(|i| app.search_games.get_item(i))
                 .ok_or(eyre!("failed to read game"))
                 .unwrap();
-            let game_id = game_to_add
-                .get("info")
-                .and_then(|i| i.get("gameId"))
-                .and_then(|id| id.as_u64())
-                .ok_or(eyre!("Failed to get game ID"))
-                .unwrap();
-            let insert_result = repo::insert_game(
-                &app.db_connection.as_ref().unwrap(),
-                &game_id.to_string(),
-                game_to_add,
-            );
+            let game_id = game_to_add.info.game_id;
+            let conn = app.db_connection.as_ref().unwrap();
+            let aliases = match Aliases::load(&aliases_path(APP_NAME)) {
+                Ok(aliases) => aliases.by_name(),
+                Err(e) => {
+                    app.alert_message = format!("Failed to add game: {e}");
+                    app.alert_type = AlertType::Error;
+                    return Some(Message::OpenAlert);
+                }
+            };
+            let insert_result = repo::insert_game(conn, &game_id.to_string(), &game_to_add.data)
+                .and_then(|_| {
+                    repo::assign_teams_from_roster(conn, game_id, &game_to_add.info, &aliases)
+                });
             match insert_result {
-                Ok(_) => {
-                    app.post_message = Some(Message::AddSearchTeam1);
-                    return Some(Message::PromptInput);
+                Ok(sides) => {
+                    // Nice to have, `fprs games timelines` fetches any missed here
+                    let stored = match game_to_add.data["metadata"]["matchId"].as_str() {
+                        Some(match_id) => app
+                            .riot
+                            .fetch_timeline("sea", match_id)
+                            .map_err(|e| format!("couldn't fetch its timeline: {e}"))
+                            .and_then(|timeline| {
+                                repo::insert_timeline(conn, game_id, &timeline)
+                                    .map_err(|e| format!("couldn't store its timeline: {e}"))
+                            }),
+                        None => Err("it has no match ID to fetch a timeline with".to_string()),
+                    };
+                    match stored {
+                        Ok(()) => app.timelines_loaded = false,
+                        Err(e) => {
+                            app.alert_message = format!("Added the game, but {e}");
+                            app.alert_type = AlertType::Warning;
+                            app.show_alert = true;
+                        }
+                    }
+                    app.search_sides = sides;
+                    return Some(Message::PromptSearchTeam);
+                }
+                Err(e) => {
+                    app.alert_message = format!("Failed to add game: {e}");
+                    app.alert_type = AlertType::Error;
+                    return Some(Message::OpenAlert);
                 }
-                Err(_) => {}
             }
         }
+        // Only ask for the sides the rosters couldn't settle
+        Message::PromptSearchTeam => match (&app.search_sides.blue, &app.search_sides.red) {
+            (None, _) => {
+                app.input_title = "Blue side team".to_string();
+                app.post_message = Some(Message::AddSearchTeam1);
+                return Some(Message::PromptInput);
+            }
+            (_, None) => {
+                app.input_title = "Red side team".to_string();
+                app.post_message = Some(Message::AddSearchTeam2);
+                return Some(Message::PromptInput);
+            }
+            _ => return Some(Message::ReloadDatabaseGames),
+        },
         Message::AddSearchTeam1 => {
             let team = app.messages.last()?;
             let game_to_add = app
                 .search_games
                 .state
                 .selected()
-                .map(|i| {
-                    &app.search_games
-                        .get_item(i)
-                        .ok_or(color_eyre::eyre::eyre!("failed to get game obj"))
-                        .unwrap()
-                        .data
-                })
+                .and_then(|i| app.search_games.get_item(i))
                 .ok_or(eyre!("failed to read game"))
                 .unwrap();
-            let game_id = game_to_add
-                .get("info")
-                .and_then(|i| i.get("gameId"))
-                .and_then(|id| id.as_u64())
-                .ok_or(eyre!("Failed to get game ID"))
-                .unwrap();
+            let game_id = game_to_add.info.game_id;
 
             match repo::update_team(&app.db_connection.as_ref().unwrap(), game_id, team, 1) {
                 Ok(_) => {
-                    app.post_message = Some(Message::AddSearchTeam2);
-                    return Some(Message::PromptInput);
+                    app.search_sides.blue = Some(team.clone());
+                    return Some(Message::PromptSearchTeam);
                 }
                 Err(e) => {
                     app.alert_message = format!("Search failed: {e}");
@@ -330,25 +482,15 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
                 .search_games
                 .state
                 .selected()
-                .map(|i| {
-                    &app.search_games
-                        .get_item(i)
-                        .ok_or(color_eyre::eyre::eyre!("failed to get game obj"))
-                        .unwrap()
-                        .data
-                })
+                .and_then(|i| app.search_games.get_item(i))
                 .ok_or(eyre!("failed to read game"))
                 .unwrap();
-            let game_id = game_to_add
-                .get("info")
-                .and_then(|i| i.get("gameId"))
-                .and_then(|id| id.as_u64())
-                .ok_or(eyre!("Failed to get game ID"))
-                .unwrap();
+            let game_id = game_to_add.info.game_id;
 
             match repo::update_team(&app.db_connection.as_ref().unwrap(), game_id, team, 2) {
                 Ok(_) => {
-                    return Some(Message::ReloadDatabaseGames);
+                    app.search_sides.red = Some(team.clone());
+                    return Some(Message::PromptSearchTeam);
                 }
                 Err(e) => {
                     app.alert_message = format!("Search failed: {e}");
@@ -358,7 +500,219 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
                 }
             }
         }
+        Message::OpenGameDetail => {
+            let game = app
+                .db_games
+                .state
+                .selected()
+                .and_then(|i| app.db_games.get_item(i))?;
+            app.detail_draft =
+                repo::game_draft(app.db_connection.as_ref().unwrap(), game.id).unwrap_or_default();
+            app.previous_screen = app.current_screen;
+            app.current_screen = CurrentScreen::GameDetail;
+        }
+        Message::PromptDraft => {
+            app.db_games.state.selected()?;
+            app.input_title = "Draft: 20 champions in order, - for no ban".to_string();
+            app.next_screen = CurrentScreen::GameDetail;
+            app.post_message = Some(Message::SetDraft);
+            return Some(Message::PromptInput);
+        }
+        Message::SetDraft => {
+            let game = app
+                .db_games
+                .state
+                .selected()
+                .and_then(|i| app.db_games.get_item(i))?;
+            let input = app.messages.last().cloned().unwrap_or_default();
+            let result = repo::champion_names(app.db_connection.as_ref().unwrap())
+                .map_err(|e| eyre!(e))
+                .and_then(|champions| {
+                    draft::parse(&input, &game.info, &champions).map_err(|e| eyre!(e))
+                })
+                .and_then(|entries| {
+                    Ok(repo::set_draft(
+                        app.db_connection.as_ref().unwrap(),
+                        game.id,
+                        &entries,
+                    )?)
+                });
+            match result {
+                Ok(()) => {
+                    app.detail_tab = DetailTab::Draft;
+                    return Some(Message::OpenGameDetail);
+                }
+                Err(e) => {
+                    app.alert_message = format!("Failed to save draft: {e}");
+                    app.alert_type = AlertType::Error;
+                    return Some(Message::OpenAlert);
+                }
+            }
+        }
+        Message::OpenFilter => app.filtering = true,
+        Message::FilterKey(key) => {
+            app.filter_input.handle_event(&key);
+            match GameFilter::parse(app.filter_input.value()) {
+                Ok(filter) => {
+                    app.filter_error = None;
+                    if filter != app.game_filter {
+                        app.game_filter = filter;
+                        return Some(Message::ReloadDatabaseGames);
+                    }
+                }
+                // Keep showing the last filter that made sense
+                Err(e) => app.filter_error = Some(e.to_string()),
+            }
+        }
+        Message::CloseFilter => app.filtering = false,
+        Message::ClearFilter => {
+            app.filtering = false;
+            app.filter_input.reset();
+            app.filter_error = None;
+            if !app.game_filter.is_empty() {
+                app.game_filter = GameFilter::default();
+                return Some(Message::ReloadDatabaseGames);
+            }
+        }
+        Message::OpenEditGame => {
+            let game = app
+                .db_games
+                .state
+                .selected()
+                .and_then(|i| app.db_games.get_item(i))?;
+            app.edit_game = Some(GameForm::new(game.id, game.metadata()));
+            app.previous_screen = app.current_screen;
+            app.current_screen = CurrentScreen::EditGame;
+        }
+        Message::EditGameField => {
+            let form = app.edit_game.as_mut()?;
+            let field = form.selected_field()?;
+            if field == GameField::Manual {
+                form.metadata.manual = !form.metadata.manual;
+                return None;
+            }
+            app.input_title = field.to_string();
+            app.input = Input::new(form.value(field));
+            app.show_input = true;
+            app.next_screen = CurrentScreen::EditGame;
+            app.post_message = Some(Message::SetGameField);
+        }
+        Message::SetGameField => {
+            let form = app.edit_game.as_mut()?;
+            let field = form.selected_field()?;
+            let input = app.messages.last().cloned().unwrap_or_default();
+            if let Err(e) = form.set(field, &input) {
+                app.alert_message = e;
+                app.alert_type = AlertType::Error;
+                return Some(Message::OpenAlert);
+            }
+            if let (GameField::Series, Some(series_id)) = (field, form.metadata.series_id) {
+                let series = repo::all_series(app.db_connection.as_ref().unwrap());
+                if !series.is_ok_and(|s| s.iter().any(|s| s.id == series_id)) {
+                    form.metadata.series_id = None;
+                    app.alert_message = format!("There is no series {series_id}");
+                    app.alert_type = AlertType::Error;
+                    return Some(Message::OpenAlert);
+                }
+            }
+        }
+        Message::SwapGameSides => app.edit_game.as_mut()?.swap_sides(),
+        Message::SaveGame => {
+            let form = app.edit_game.take()?;
+            let result = repo::update_game_metadata(
+                app.db_connection.as_ref().unwrap(),
+                form.game_id,
+                &form.metadata,
+            );
+            app.current_screen = CurrentScreen::Main;
+            match result {
+                Ok(()) => return Some(Message::ReloadDatabaseGames),
+                Err(e) => {
+                    app.alert_message = format!("Failed to save game {}: {e}", form.game_id);
+                    app.alert_type = AlertType::Error;
+                    return Some(Message::OpenAlert);
+                }
+            }
+        }
+        Message::OpenRoster => {
+            app.previous_screen = app.current_screen;
+            app.current_screen = CurrentScreen::Roster;
+            return Some(Message::ReloadRoster);
+        }
+        Message::ReloadRoster => match repo::all_roster(app.db_connection.as_ref().unwrap()) {
+            Ok(roster) => {
+                app.roster = roster;
+                if app.roster_table_state.selected().is_none() {
+                    app.roster_table_state.select_first();
+                }
+            }
+            Err(e) => {
+                app.alert_message = format!("Failed to load rosters: {e}");
+                app.alert_type = AlertType::Error;
+                return Some(Message::OpenAlert);
+            }
+        },
+        Message::PromptRosterField(field) => {
+            let Some(title) = ROSTER_FIELDS.get(field) else {
+                return Some(Message::AddRosterEntry);
+            };
+            app.input_title = title.to_string();
+            app.next_screen = CurrentScreen::Roster;
+            app.post_message = Some(Message::PromptRosterField(field + 1));
+            return Some(Message::PromptInput);
+        }
+        Message::AddRosterEntry => {
+            let fields = &app.messages[app.messages.len().saturating_sub(ROSTER_FIELDS.len())..];
+            match do_add_roster_entry(app.db_connection.as_ref().unwrap(), fields) {
+                Ok(()) => return Some(Message::ReloadRoster),
+                Err(e) => {
+                    app.alert_message = format!("Failed to add roster entry: {e}");
+                    app.alert_type = AlertType::Error;
+                    return Some(Message::OpenAlert);
+                }
+            }
+        }
+        Message::RemoveRosterEntry => {
+            let entry = app
+                .roster_table_state
+                .selected()
+                .and_then(|i| app.roster.get(i))?;
+            match repo::delete_roster_entry(app.db_connection.as_ref().unwrap(), entry.id) {
+                Ok(()) => return Some(Message::ReloadRoster),
+                Err(e) => {
+                    app.alert_message = format!("Failed to remove roster entry: {e}");
+                    app.alert_type = AlertType::Error;
+                    return Some(Message::OpenAlert);
+                }
+            }
+        }
+        Message::ApplyRoster => {
+            let result = Aliases::load(&aliases_path(APP_NAME)).and_then(|aliases| {
+                Ok(repo::assign_all_teams_from_roster(
+                    app.db_connection.as_ref().unwrap(),
+                    &aliases.by_name(),
+                )?)
+            });
+            match result {
+                Ok(n) => {
+                    app.alert_message = format!("Assigned teams to {n} games");
+                    app.alert_type = AlertType::Success;
+                    app.show_alert = true;
+                    return Some(Message::ReloadDatabaseGames);
+                }
+                Err(e) => {
+                    app.alert_message = format!("Failed to assign teams: {e}");
+                    app.alert_type = AlertType::Error;
+                    return Some(Message::OpenAlert);
+                }
+            }
+        }
         Message::OpenStats => {
+            if let Err(e) = do_refresh_players(app) {
+                app.alert_message = format!("Failed to resolve players: {e}");
+                app.alert_type = AlertType::Error;
+                app.show_alert = true;
+            }
             match repo::stats_overall(app.db_connection.as_ref().unwrap()) {
                 Ok(result) => {
                     app.overall_stats = result;
@@ -392,6 +746,58 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
                     app.show_alert = true;
                 }
             }
+            match repo::stats_teams(app.db_connection.as_ref().unwrap()) {
+                Ok(result) => {
+                    app.teams_stats = result;
+                    app.teams_sort_dir = -1;
+                }
+                Err(e) => {
+                    app.alert_message = format!("Failed to load stats: {}", e);
+                    app.alert_type = AlertType::Error;
+                    app.show_alert = true;
+                }
+            }
+            match repo::stats_series(app.db_connection.as_ref().unwrap()) {
+                Ok(series) => {
+                    app.standings = series::standings(&series);
+                    app.series = series;
+                }
+                Err(e) => {
+                    app.alert_message = format!("Failed to load stats: {}", e);
+                    app.alert_type = AlertType::Error;
+                    app.show_alert = true;
+                }
+            }
+            match repo::stats_draft_tendencies(app.db_connection.as_ref().unwrap()) {
+                Ok(result) => app.draft_tendencies = result,
+                Err(e) => {
+                    app.alert_message = format!("Failed to load stats: {}", e);
+                    app.alert_type = AlertType::Error;
+                    app.show_alert = true;
+                }
+            }
+            match (
+                repo::ratings(app.db_connection.as_ref().unwrap(), RatingKind::Team),
+                repo::ratings(app.db_connection.as_ref().unwrap(), RatingKind::Player),
+            ) {
+                (Ok(teams), Ok(players)) => {
+                    app.team_ratings = teams;
+                    app.player_ratings = players;
+                }
+                (Err(e), _) | (_, Err(e)) => {
+                    app.alert_message = format!("Failed to load stats: {}", e);
+                    app.alert_type = AlertType::Error;
+                    app.show_alert = true;
+                }
+            }
+            match repo::stats_player_matchups(app.db_connection.as_ref().unwrap()) {
+                Ok(result) => app.player_matchups = result,
+                Err(e) => {
+                    app.alert_message = format!("Failed to load stats: {}", e);
+                    app.alert_type = AlertType::Error;
+                    app.show_alert = true;
+                }
+            }
             match (
                 repo::stats_player_overall(app.db_connection.as_ref().unwrap()),
                 repo::stats_player_role(app.db_connection.as_ref().unwrap()),
@@ -412,6 +818,109 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
             }
             app.current_screen = CurrentScreen::Stats;
         }
+        Message::ExportStats => {
+            let dir = app.config.export_dir(APP_NAME);
+            // Load everything again, as the tables on screen are filtered and
+            // the champions are only loaded once their tab has been opened
+            match command::export_stats(
+                app.db_connection.as_ref().unwrap(),
+                &dir,
+                &ExportFormat::ALL,
+            ) {
+                Ok(_) => {
+                    app.alert_message = format!("Exported stats to {}", dir.display());
+                    app.alert_type = AlertType::Success;
+                }
+                Err(e) => {
+                    app.alert_message = format!("Export failed: {e}");
+                    app.alert_type = AlertType::Error;
+                }
+            }
+            return Some(Message::OpenAlert);
+        }
+        Message::PromptHeadToHead(team) => {
+            let Some(title) = ["First team", "Second team"].get(team) else {
+                return Some(Message::OpenHeadToHead);
+            };
+            app.input_title = title.to_string();
+            app.next_screen = CurrentScreen::Stats;
+            app.post_message = Some(Message::PromptHeadToHead(team + 1));
+            return Some(Message::PromptInput);
+        }
+        Message::OpenHeadToHead => {
+            let inputs = &app.messages[app.messages.len().saturating_sub(2)..];
+            let result = inputs
+                .iter()
+                .map(|input| resolve_team(&app.teams_stats, input))
+                .collect::<color_eyre::Result<Vec<_>>>()
+                .and_then(|teams| match teams.as_slice() {
+                    [a, b] if a == b => Err(eyre!("pick two different teams")),
+                    [a, b] => Ok(repo::head_to_head(
+                        app.db_connection.as_ref().unwrap(),
+                        [a, b],
+                    )?),
+                    _ => Err(eyre!("missing teams to compare")),
+                });
+            match result {
+                Ok(h2h) => {
+                    app.head_to_head = Some(h2h);
+                    app.current_screen = CurrentScreen::HeadToHead;
+                }
+                Err(e) => {
+                    app.alert_message = format!("Failed to compare teams: {e}");
+                    app.alert_type = AlertType::Error;
+                    return Some(Message::OpenAlert);
+                }
+            }
+        }
+        Message::PickVersusPlayer => {
+            let player = app
+                .players_table_state
+                .selected()
+                .and_then(|i| sorted_players(app).get(i).cloned())?;
+            match app.versus_pick.take() {
+                None => app.versus_pick = Some(player),
+                // Picking the same player again drops them
+                Some(first) if first.puuid == player.puuid => {}
+                Some(first) => match repo::shared_games(
+                    app.db_connection.as_ref().unwrap(),
+                    [&first.puuid, &player.puuid],
+                ) {
+                    Ok(games) => {
+                        app.player_versus = Some(PlayerVersus {
+                            players: [first, player],
+                            games,
+                        });
+                        app.current_screen = CurrentScreen::PlayerVersus;
+                    }
+                    Err(e) => {
+                        app.alert_message = format!("Failed to compare players: {e}");
+                        app.alert_type = AlertType::Error;
+                        return Some(Message::OpenAlert);
+                    }
+                },
+            }
+        }
+        Message::OpenSynergy => {
+            let player = app
+                .players_table_state
+                .selected()
+                .and_then(|i| sorted_players(app).get(i).cloned())?;
+            match repo::pair_games(app.db_connection.as_ref().unwrap()) {
+                Ok(games) => {
+                    app.synergy = Some(Synergy::new(&games, &player.puuid, &player.riot_id));
+                    app.current_screen = CurrentScreen::Synergy;
+                }
+                Err(e) => {
+                    app.alert_message = format!("Failed to load synergy: {e}");
+                    app.alert_type = AlertType::Error;
+                    return Some(Message::OpenAlert);
+                }
+            }
+        }
+        Message::ReturnToStats => {
+            app.current_screen = CurrentScreen::Stats;
+        }
         Message::Quit => {
             app.current_screen = CurrentScreen::Quit;
         }
@@ -420,16 +929,114 @@ pub fn update(app: &mut App, msg: Message) -> Option<Message> {
     None
 }
 
-fn do_import_manual(app: &App) -> color_eyre::Result<usize> {
-    let db_path = crate::app::db_path(APP_NAME);
-    let conn = Connection::open(db_path)?;
+/// Read the folder path from the prompt and work out which team
+/// abbreviations in its file names need asking about.
+fn find_unresolved_teams(app: &mut App) -> color_eyre::Result<()> {
     let path = PathBuf::from(app.messages.last().unwrap_or(&String::new()));
 
     if !path.is_dir() {
         return Err(eyre!("{} is not a directory.", path.display()));
     }
 
-    return Ok(import_manual_matches(&conn, &path)?);
+    app.import_naming = app.config.file_naming();
+    app.import_unresolved = app.import_naming.unresolved(&path)?;
+    app.import_dir = path;
+    Ok(())
+}
+
+fn do_import_manual(app: &App) -> color_eyre::Result<ImportPlan> {
+    let db_path = crate::app::db_path(APP_NAME);
+    let conn = Connection::open(db_path)?;
+
+    import::plan_manual_import(&conn, &app.import_dir, &app.import_naming).map_err(Into::into)
+}
+
+/// Add a roster entry from the answers to the `ROSTER_FIELDS` prompts.
+fn do_add_roster_entry(conn: &Connection, fields: &[String]) -> color_eyre::Result<()> {
+    let [team, player, from, to] = fields else {
+        return Err(eyre!("missing roster fields"));
+    };
+    let (team, player) = (team.trim(), player.trim());
+    if team.is_empty() || player.is_empty() {
+        return Err(eyre!("a roster entry needs a team and a player"));
+    }
+    let date = |d: &str| -> color_eyre::Result<Option<String>> {
+        match d.trim() {
+            "" => Ok(None),
+            d => Ok(Some(
+                NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                    .map_err(|e| eyre!("'{d}' is not a YYYY-MM-DD date: {e}"))?
+                    .to_string(),
+            )),
+        }
+    };
+    let (from, to) = (date(from)?, date(to)?);
+    repo::insert_roster_entry(conn, team, player, from.as_deref(), to.as_deref())?;
+    Ok(())
+}
+
+/// Match typed input to a team with stats, ignoring case. Anything that isn't
+/// a full name has to be part of exactly one team's name.
+fn resolve_team(teams: &[TeamStats], input: &str) -> color_eyre::Result<String> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Err(eyre!("no team entered"));
+    }
+    if let Some(team) = teams.iter().find(|t| t.team_name.to_lowercase() == input) {
+        return Ok(team.team_name.clone());
+    }
+    let matches: Vec<&str> = teams
+        .iter()
+        .map(|t| t.team_name.as_str())
+        .filter(|name| name.to_lowercase().contains(&input))
+        .collect();
+    match matches.as_slice() {
+        [team] => Ok(team.to_string()),
+        [] => Err(eyre!("no team matches '{input}'")),
+        many => Err(eyre!("'{input}' matches {}", many.join(", "))),
+    }
+}
+
+/// Name the stored games left out of the game list because their payload
+/// can't be read.
+pub fn alert_unreadable(app: &mut App, unreadable: &[UnreadableGame]) {
+    if unreadable.is_empty() {
+        return;
+    }
+    let games: Vec<String> = unreadable
+        .iter()
+        .map(|g| format!("{} ({})", g.id, g.error))
+        .collect();
+    app.alert_message = format!("Skipped games that couldn't be read: {}", games.join(", "));
+    app.alert_type = AlertType::Error;
+    app.show_alert = true;
+}
+
+/// The champion tab's tables, loaded when the tab is opened.
+fn load_champion_stats(app: &mut App) {
+    let conn = app.db_connection.as_ref().unwrap();
+    match (
+        repo::stats_all_champions(conn),
+        repo::stats_champion_history(conn),
+        repo::stats_champion_matchups(conn),
+    ) {
+        (Ok(champions), Ok(history), Ok(matchups)) => {
+            app.all_champs_stats = champions;
+            app.champs_history = history;
+            app.champion_matchups = matchups;
+        }
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+            app.alert_message = format!("Failed to load stats: {}", e);
+            app.alert_type = AlertType::Error;
+            app.show_alert = true;
+        }
+    }
+}
+
+fn do_refresh_players(app: &App) -> color_eyre::Result<()> {
+    let aliases = Aliases::load(&aliases_path(APP_NAME))?;
+    repo::refresh_players(app.db_connection.as_ref().unwrap(), &aliases.by_name())?;
+    Ok(())
 }
 
 fn do_search(app: &App) -> color_eyre::Result<Vec<Value>> {
@@ -454,22 +1061,21 @@ fn do_search(app: &App) -> color_eyre::Result<Vec<Value>> {
     let tag_line = components
         .last()
         .ok_or(color_eyre::eyre::eyre!("could not find tag_line"))?;
-    let acc = riot::fetch_account(&app.get_config_api_key(), &asia, riot_name, tag_line)?;
+    let acc = app.riot.fetch_account(asia, riot_name, tag_line)?;
     let puuid = acc
         .get("puuid")
         .ok_or(color_eyre::eyre::eyre!("missing puuid"))?
         .as_str()
         .ok_or(color_eyre::eyre::eyre!("cannot convert puuid to str"))?;
     // Get match ids
-    let match_ids_response = riot::fetch_match_ids(&app.get_config_api_key(), &sea, &puuid)?;
+    let match_ids_response = app.riot.fetch_match_ids(sea, puuid)?;
     let match_ids = match_ids_response
         .as_array()
         .ok_or(color_eyre::eyre::eyre!("could not read match id array"))?;
     let matches = match_ids
         .iter()
         .map(|mid| {
-            let response =
-                riot::fetch_match(&app.get_config_api_key(), &sea, mid.as_str().unwrap())?;
+            let response = app.riot.fetch_match(sea, mid.as_str().unwrap())?;
             Ok(response)
         })
         .collect::<color_eyre::Result<Vec<_>>>()?;